@@ -0,0 +1,234 @@
+//! Derive macros for the [`blockfrost`](https://docs.rs/blockfrost) crate.
+//!
+//! Use them through the `derive` feature of `blockfrost` rather than directly.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Attribute, Data, DeriveInput, Error, Fields,
+    Generics, LitInt,
+};
+
+/// Derive `FromPlutusData`, reading structs from a constructor and enums from the constructor
+/// of each variant.
+///
+/// Structs use constructor `0` and enum variants their index, unless annotated with
+/// `#[plutus(constr = N)]`.
+#[proc_macro_derive(FromPlutusData, attributes(plutus))]
+pub fn derive_from_plutus_data(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    from_plutus_data(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derive `ToPlutusData`, writing structs as a constructor and enums as the constructor of each
+/// variant.
+///
+/// Constructors are picked like for `FromPlutusData`.
+#[proc_macro_derive(ToPlutusData, attributes(plutus))]
+pub fn derive_to_plutus_data(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    to_plutus_data(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+// A struct, or a variant of an enum, with the constructor it maps to.
+struct Constructor<'a> {
+    tag: u64,
+    fields: &'a Fields,
+}
+
+fn constructors(input: &DeriveInput) -> syn::Result<Vec<(Option<&syn::Ident>, Constructor<'_>)>> {
+    match &input.data {
+        Data::Struct(data) => Ok(vec![(
+            None,
+            Constructor {
+                tag: constr_attribute(&input.attrs)?.unwrap_or(0),
+                fields: &data.fields,
+            },
+        )]),
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(index, variant)| {
+                Ok((
+                    Some(&variant.ident),
+                    Constructor {
+                        tag: constr_attribute(&variant.attrs)?.unwrap_or(index as u64),
+                        fields: &variant.fields,
+                    },
+                ))
+            })
+            .collect(),
+        Data::Union(_) => Err(Error::new(
+            input.span(),
+            "Plutus data can't be derived for unions",
+        )),
+    }
+}
+
+// Parse `#[plutus(constr = N)]`.
+fn constr_attribute(attrs: &[Attribute]) -> syn::Result<Option<u64>> {
+    let mut tag = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("plutus")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("constr") {
+                tag = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `constr = N`"))
+            }
+        })?;
+    }
+
+    Ok(tag)
+}
+
+// Add `bound` to every type parameter.
+fn add_bounds(generics: &Generics, bound: syn::Path) -> Generics {
+    let mut generics = generics.clone();
+
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
+
+fn field_names(fields: &Fields) -> Vec<syn::Ident> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            field
+                .ident
+                .clone()
+                .unwrap_or_else(|| format_ident!("field_{index}"))
+        })
+        .collect()
+}
+
+// Build `Path { a, b }`, `Path(a, b)` or `Path` from the names of the fields.
+fn construct(path: TokenStream2, fields: &Fields, values: &[TokenStream2]) -> TokenStream2 {
+    let names = fields.iter().filter_map(|field| field.ident.as_ref());
+
+    match fields {
+        Fields::Named(_) => quote!(#path { #(#names: #values),* }),
+        Fields::Unnamed(_) => quote!(#path(#(#values),*)),
+        Fields::Unit => path,
+    }
+}
+
+fn from_plutus_data(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = add_bounds(&input.generics, parse_quote!(::blockfrost::FromPlutusData));
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    let decode = |path: TokenStream2, constructor: &Constructor| {
+        let tag = constructor.tag;
+        let arity = constructor.fields.len();
+        let values: Vec<_> = (0..arity)
+            .map(|index| quote!(::blockfrost::FromPlutusData::from_plutus_data(&fields[#index])?))
+            .collect();
+        let value = construct(path, constructor.fields, &values);
+
+        if arity == 0 {
+            quote! {
+                data.expect_constr(#tag, 0)?;
+                Ok(#value)
+            }
+        } else {
+            quote! {
+                let fields = data.expect_constr(#tag, #arity)?;
+                Ok(#value)
+            }
+        }
+    };
+
+    let body = match input.data {
+        Data::Struct(_) => {
+            let constructors = constructors(input)?;
+            decode(quote!(Self), &constructors[0].1)
+        }
+        _ => {
+            let arms = constructors(input)?
+                .into_iter()
+                .map(|(variant, constructor)| {
+                    let tag = constructor.tag;
+                    let decode = decode(quote!(Self::#variant), &constructor);
+
+                    quote!(#tag => { #decode })
+                })
+                .collect::<Vec<_>>();
+            let message = format!("unknown constructor {{tag}} for {name}");
+
+            quote! {
+                match data.constr_tag()? {
+                    #(#arms)*
+                    tag => Err(::blockfrost::BlockfrostError::Parsing {
+                        message: format!(#message),
+                    }),
+                }
+            }
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::blockfrost::FromPlutusData for #name #type_generics #where_clause {
+            fn from_plutus_data(
+                data: &::blockfrost::PlutusData,
+            ) -> ::blockfrost::BlockfrostResult<Self> {
+                #body
+            }
+        }
+    })
+}
+
+fn to_plutus_data(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let generics = add_bounds(&input.generics, parse_quote!(::blockfrost::ToPlutusData));
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    let arms = constructors(input)?
+        .into_iter()
+        .map(|(variant, constructor)| {
+            let tag = constructor.tag;
+            let names = field_names(constructor.fields);
+            let path = match variant {
+                Some(variant) => quote!(Self::#variant),
+                None => quote!(Self),
+            };
+            let pattern = match constructor.fields {
+                Fields::Named(_) => quote!(#path { #(#names),* }),
+                Fields::Unnamed(_) => quote!(#path(#(#names),*)),
+                Fields::Unit => path,
+            };
+
+            quote! {
+                #pattern => ::blockfrost::PlutusData::Constr {
+                    tag: #tag,
+                    fields: vec![#(::blockfrost::ToPlutusData::to_plutus_data(#names)),*],
+                },
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let body = if arms.is_empty() {
+        quote!(match *self {})
+    } else {
+        quote!(match self { #(#arms)* })
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::blockfrost::ToPlutusData for #name #type_generics #where_clause {
+            fn to_plutus_data(&self) -> ::blockfrost::PlutusData {
+                #body
+            }
+        }
+    })
+}
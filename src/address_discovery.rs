@@ -0,0 +1,249 @@
+use crate::{AddressNetwork, Bip32PublicKey, BlockfrostAPI, BlockfrostError, BlockfrostResult};
+use futures::{stream, StreamExt, TryStreamExt};
+use std::future::Future;
+
+/// Number of consecutive unused addresses after which discovery stops, as set by [CIP-1852].
+///
+/// [CIP-1852]: https://cips.cardano.org/cip/CIP-1852
+pub const GAP_LIMIT: u32 = 20;
+
+/// Chain of addresses of an account, the role in its [CIP-1852] derivation path.
+///
+/// [CIP-1852]: https://cips.cardano.org/cip/CIP-1852
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AddressRole {
+    /// Receive addresses, role `0`.
+    External,
+    /// Change addresses, role `1`.
+    Internal,
+}
+
+impl AddressRole {
+    pub fn index(self) -> u32 {
+        match self {
+            AddressRole::External => 0,
+            AddressRole::Internal => 1,
+        }
+    }
+}
+
+/// An address derived from an account public key.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DiscoveredAddress {
+    pub address: String,
+    pub role: AddressRole,
+    pub index: u32,
+    /// Number of transactions of the address, `0` if it was never used.
+    pub tx_count: u64,
+}
+
+impl DiscoveredAddress {
+    pub fn is_used(&self) -> bool {
+        self.tx_count > 0
+    }
+}
+
+/// Used addresses of an account, returned by
+/// [`discover_addresses`](BlockfrostAPI::discover_addresses).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddressDiscovery {
+    /// Used external addresses, then used internal ones, by index.
+    pub used: Vec<DiscoveredAddress>,
+    /// First unused external address after the last used one, to receive funds.
+    pub next_receive: DiscoveredAddress,
+}
+
+impl BlockfrostAPI {
+    /// Find the used addresses of an account from its extended public key.
+    ///
+    /// Addresses of the external and internal chains are derived with
+    /// [`derive_address`](Self::derive_address) and checked with
+    /// [`addresses_total`](Self::addresses_total), [`GAP_LIMIT`] at a time with at most
+    /// [`batch_concurrency`](crate::BlockFrostSettings::batch_concurrency) requests in flight,
+    /// until [`GAP_LIMIT`] consecutive addresses of a chain are unused. The external chain is
+    /// scanned first, then the internal one.
+    ///
+    /// [`discover_addresses_offline`](Self::discover_addresses_offline) saves the
+    /// derivation requests.
+    pub async fn discover_addresses(&self, xpub: &str) -> BlockfrostResult<AddressDiscovery> {
+        self.discover_addresses_with(|role, index| async move {
+            let derived = self
+                .derive_address(xpub, &role.index().to_string(), &index.to_string())
+                .await?;
+
+            Ok(derived.address)
+        })
+        .await
+    }
+
+    /// Find the used addresses of an account like
+    /// [`discover_addresses`](Self::discover_addresses), deriving them locally from the account
+    /// key rather than through the API.
+    pub async fn discover_addresses_offline(
+        &self, account_key: &Bip32PublicKey, network: AddressNetwork,
+    ) -> BlockfrostResult<AddressDiscovery> {
+        self.discover_addresses_with(|role, index| async move {
+            account_key.base_address(role, index, network)
+        })
+        .await
+    }
+
+    async fn discover_addresses_with<F, Fut>(&self, derive: F) -> BlockfrostResult<AddressDiscovery>
+    where
+        F: Fn(AddressRole, u32) -> Fut,
+        Fut: Future<Output = BlockfrostResult<String>>,
+    {
+        let derive = &derive;
+        let lookup = |role: AddressRole| {
+            move |index: u32| async move {
+                let address = derive(role, index).await?;
+
+                // Addresses that never appeared on chain are not found.
+                let tx_count = match self.addresses_total(&address).await {
+                    Ok(total) => u64::try_from(total.tx_count).unwrap_or_default(),
+                    Err(BlockfrostError::Response { reason, .. }) if reason.status_code == 404 => 0,
+                    Err(error) => return Err(error),
+                };
+
+                Ok(DiscoveredAddress {
+                    address,
+                    role,
+                    index,
+                    tx_count,
+                })
+            }
+        };
+
+        let concurrency = self.batch_concurrency();
+        let external = discover_chain(lookup(AddressRole::External), concurrency).await?;
+        let internal = discover_chain(lookup(AddressRole::Internal), concurrency).await?;
+
+        let mut used = external.used;
+        used.extend(internal.used);
+
+        Ok(AddressDiscovery {
+            used,
+            next_receive: external.next_unused,
+        })
+    }
+}
+
+// Result of scanning a single chain.
+pub(crate) struct ChainDiscovery {
+    pub used: Vec<DiscoveredAddress>,
+    pub next_unused: DiscoveredAddress,
+}
+
+// Look up addresses of a chain by index, `GAP_LIMIT` at a time with at most `concurrency`
+// lookups in flight, until `GAP_LIMIT` consecutive ones are unused.
+pub(crate) async fn discover_chain<F, Fut>(
+    lookup: F, concurrency: usize,
+) -> BlockfrostResult<ChainDiscovery>
+where
+    F: Fn(u32) -> Fut,
+    Fut: Future<Output = BlockfrostResult<DiscoveredAddress>>,
+{
+    let mut used = Vec::new();
+    // Unused addresses since the last used one.
+    let mut unused = Vec::new();
+    let mut start = 0;
+
+    while unused.len() < GAP_LIMIT as usize {
+        let batch: Vec<_> = stream::iter(start..start + GAP_LIMIT)
+            .map(&lookup)
+            .buffered(concurrency)
+            .try_collect()
+            .await?;
+
+        for address in batch {
+            if unused.len() == GAP_LIMIT as usize {
+                break;
+            }
+
+            if address.is_used() {
+                used.push(address);
+                unused.clear();
+            } else {
+                unused.push(address);
+            }
+        }
+        start += GAP_LIMIT;
+    }
+
+    Ok(ChainDiscovery {
+        used,
+        // Safety: the loop only ends once `GAP_LIMIT` unused addresses were found.
+        next_unused: unused.swap_remove(0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Addresses of the chain are used at `used` indexes.
+    async fn discover(used: &[u32]) -> ChainDiscovery {
+        discover_chain(
+            |index| async move {
+                Ok(DiscoveredAddress {
+                    address: format!("addr_test{index}"),
+                    role: AddressRole::External,
+                    index,
+                    tx_count: used.contains(&index).into(),
+                })
+            },
+            4,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_discover_chain() {
+        let chain = discover(&[0, 1, 5, 24]).await;
+
+        assert_eq!(
+            chain.used.iter().map(|a| a.index).collect::<Vec<_>>(),
+            [0, 1, 5, 24]
+        );
+        assert_eq!(chain.next_unused.index, 25);
+        assert_eq!(chain.next_unused.address, "addr_test25");
+    }
+
+    #[tokio::test]
+    async fn test_discover_chain_gap() {
+        // Index 25 is the 20th unused address after index 5, so 26 is never reached.
+        let chain = discover(&[5, 26]).await;
+
+        assert_eq!(chain.used.len(), 1);
+        assert_eq!(chain.next_unused.index, 6);
+
+        let chain = discover(&[]).await;
+
+        assert!(chain.used.is_empty());
+        assert_eq!(chain.next_unused.index, 0);
+    }
+
+    #[tokio::test]
+    async fn test_discover_chain_error() {
+        let result = discover_chain(
+            |index| async move {
+                match index {
+                    12 => Err(BlockfrostError::Parsing {
+                        message: "invalid xpub".to_string(),
+                    }),
+                    _ => Ok(DiscoveredAddress {
+                        address: format!("addr_test{index}"),
+                        role: AddressRole::Internal,
+                        index,
+                        tx_count: 0,
+                    }),
+                }
+            },
+            4,
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+}
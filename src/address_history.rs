@@ -0,0 +1,502 @@
+use crate::{utils::parse_lovelace, LOVELACE_UNIT};
+use crate::{BlockfrostAPI, BlockfrostError, BlockfrostResult, Order, Pagination};
+use blockfrost_openapi::models::{tx_content::TxContent, tx_content_utxo::TxContentUtxo};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+/// Addresses whose transactions are followed by [`BlockfrostAPI::address_history`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HistoryOwner {
+    /// One or more addresses.
+    Addresses(BTreeSet<String>),
+    /// A stake address, owning every base address carrying its stake credential.
+    Account(String),
+}
+
+impl HistoryOwner {
+    /// Whether `address` belongs to the owner.
+    pub fn owns(&self, address: &str) -> bool {
+        match self {
+            HistoryOwner::Addresses(addresses) => addresses.contains(address),
+            HistoryOwner::Account(stake_address) => {
+                let account = stake_credential(stake_address);
+
+                account.is_some() && account == stake_credential(address)
+            }
+        }
+    }
+}
+
+/// A stake address (`stake1…`, `stake_test1…`) is followed as an account, anything else as a
+/// single address.
+impl From<&str> for HistoryOwner {
+    fn from(address: &str) -> Self {
+        if address.starts_with("stake") {
+            HistoryOwner::Account(address.to_string())
+        } else {
+            HistoryOwner::Addresses(BTreeSet::from([address.to_string()]))
+        }
+    }
+}
+
+impl From<Vec<String>> for HistoryOwner {
+    fn from(addresses: Vec<String>) -> Self {
+        HistoryOwner::Addresses(addresses.into_iter().collect())
+    }
+}
+
+/// Net change of lovelace and native assets, which can be negative.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValueDelta {
+    pub lovelace: i128,
+    /// Native assets keyed by `unit`, without zero entries.
+    pub assets: BTreeMap<String, i128>,
+}
+
+impl ValueDelta {
+    /// Whether nothing changed.
+    pub fn is_zero(&self) -> bool {
+        self.lovelace == 0 && self.assets.is_empty()
+    }
+
+    fn add(&mut self, amounts: &[HistoryAmount], sign: i128) -> BlockfrostResult<()> {
+        for amount in amounts {
+            let quantity: i128 =
+                amount
+                    .quantity
+                    .parse()
+                    .map_err(|reason| BlockfrostError::Parsing {
+                        message: format!("invalid quantity '{}': {reason}", amount.quantity),
+                    })?;
+
+            if amount.unit == LOVELACE_UNIT {
+                self.lovelace += sign * quantity;
+                continue;
+            }
+
+            let total = self.assets.entry(amount.unit.clone()).or_default();
+            *total += sign * quantity;
+
+            if *total == 0 {
+                self.assets.remove(&amount.unit);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// What a transaction did, from the point of view of a [`HistoryOwner`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HistoryTxKind {
+    /// Funds sent to the owner by someone else.
+    Receive,
+    /// Funds sent by the owner to someone else.
+    Send,
+    /// Funds moved between addresses of the owner.
+    SelfTransfer,
+    /// Sent by the owner, with a delegation certificate.
+    Delegation,
+    /// Sent by the owner, withdrawing rewards.
+    Withdrawal,
+    /// Sent by the owner, minting or burning assets.
+    Mint,
+}
+
+/// A transaction of [`BlockfrostAPI::address_history`], with its effect on the owner.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddressHistoryEntry {
+    pub tx_hash: String,
+    pub block_height: i32,
+    pub block_time: i32,
+    pub tx_index: i32,
+    /// Net change of the owner's balance, fees included.
+    pub delta: ValueDelta,
+    /// Fee of the transaction, if the owner spent any of its inputs.
+    pub fee: Option<u64>,
+    pub kind: HistoryTxKind,
+}
+
+impl AddressHistoryEntry {
+    /// Compute the effect of a transaction on `owner`, from
+    /// [`transaction_by_hash`](BlockfrostAPI::transaction_by_hash) and
+    /// [`transactions_utxos`](BlockfrostAPI::transactions_utxos).
+    ///
+    /// Reference inputs are ignored. A transaction whose scripts failed only spends its
+    /// collateral inputs and creates its collateral return.
+    pub fn new(
+        owner: &HistoryOwner, tx: &TxContent, utxos: &TxContentUtxo,
+    ) -> BlockfrostResult<Self> {
+        let invalid = |reason: serde_json::Error| BlockfrostError::Parsing {
+            message: format!("invalid UTXOs of transaction {}: {reason}", tx.hash),
+        };
+        let utxos: HistoryUtxos =
+            serde_json::from_value(serde_json::to_value(utxos).map_err(invalid)?)
+                .map_err(invalid)?;
+
+        let spent = utxos.inputs.iter().filter(|input| {
+            !input.reference.unwrap_or(false) && input.collateral != tx.valid_contract
+        });
+        let created = utxos
+            .outputs
+            .iter()
+            .filter(|output| output.collateral != tx.valid_contract);
+
+        let mut delta = ValueDelta::default();
+        let mut sender = false;
+
+        for input in spent.filter(|input| owner.owns(&input.address)) {
+            sender = true;
+            delta.add(&input.amount, -1)?;
+        }
+
+        let mut self_transfer = true;
+
+        for output in created {
+            if owner.owns(&output.address) {
+                delta.add(&output.amount, 1)?;
+            } else {
+                self_transfer = false;
+            }
+        }
+
+        let kind = match sender {
+            false => HistoryTxKind::Receive,
+            // Only the collateral was spent.
+            true if !tx.valid_contract => HistoryTxKind::Send,
+            true if tx.delegation_count > 0 => HistoryTxKind::Delegation,
+            true if tx.withdrawal_count > 0 => HistoryTxKind::Withdrawal,
+            true if tx.asset_mint_or_burn_count > 0 => HistoryTxKind::Mint,
+            true if self_transfer => HistoryTxKind::SelfTransfer,
+            true => HistoryTxKind::Send,
+        };
+        let fee = match sender {
+            true => Some(parse_lovelace(&tx.fees)?),
+            false => None,
+        };
+
+        Ok(Self {
+            tx_hash: tx.hash.clone(),
+            block_height: tx.block_height,
+            block_time: tx.block_time,
+            tx_index: tx.index,
+            delta,
+            fee,
+            kind,
+        })
+    }
+}
+
+/// The parts of [`TxContentUtxo`] needed to compute balance changes.
+#[derive(Deserialize)]
+struct HistoryUtxos {
+    inputs: Vec<HistoryUtxo>,
+    outputs: Vec<HistoryUtxo>,
+}
+
+#[derive(Deserialize)]
+struct HistoryUtxo {
+    address: String,
+    amount: Vec<HistoryAmount>,
+    #[serde(default)]
+    collateral: bool,
+    #[serde(default)]
+    reference: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct HistoryAmount {
+    unit: String,
+    quantity: String,
+}
+
+impl BlockfrostAPI {
+    /// Stream the transactions of an address, a set of addresses or a stake account, with the
+    /// net balance change, the fee paid and what each transaction did.
+    ///
+    /// The transactions are listed first, with
+    /// [`addresses_transactions`](Self::addresses_transactions) for each address or
+    /// [`accounts_transactions`](Self::accounts_transactions) for an account. Each of them is
+    /// then looked up with [`transaction_by_hash`](Self::transaction_by_hash) and
+    /// [`transactions_utxos`](Self::transactions_utxos), with at most
+    /// [`batch_concurrency`](crate::BlockFrostSettings::batch_concurrency) transactions at a
+    /// time, as the stream is polled.
+    pub fn address_history(
+        &self, owner: impl Into<HistoryOwner>, order: Order,
+    ) -> impl Stream<Item = BlockfrostResult<AddressHistoryEntry>> + '_ {
+        let owner = Arc::new(owner.into());
+        let listed = Arc::clone(&owner);
+
+        stream::once(async move { self.history_transactions(&listed, order).await })
+            .map_ok(|hashes| stream::iter(hashes.into_iter().map(Ok)))
+            .try_flatten()
+            .map_ok(move |hash| {
+                let owner = Arc::clone(&owner);
+
+                async move {
+                    let (tx, utxos) = futures::try_join!(
+                        self.transaction_by_hash(&hash),
+                        self.transactions_utxos(&hash)
+                    )?;
+
+                    AddressHistoryEntry::new(&owner, &tx, &utxos)
+                }
+            })
+            .try_buffered(self.batch_concurrency())
+    }
+
+    /// Hashes of the transactions of `owner`, without duplicates, in chain `order`.
+    async fn history_transactions(
+        &self, owner: &HistoryOwner, order: Order,
+    ) -> BlockfrostResult<Vec<String>> {
+        let pagination = Pagination {
+            order,
+            ..Pagination::all()
+        };
+        let mut transactions = BTreeMap::new();
+
+        match owner {
+            HistoryOwner::Addresses(addresses) => {
+                let pages: Vec<_> = stream::iter(addresses)
+                    .map(|address| self.addresses_transactions(address, pagination))
+                    .buffered(self.batch_concurrency())
+                    .try_collect()
+                    .await?;
+
+                for tx in pages.into_iter().flatten() {
+                    transactions.insert((tx.block_height, tx.tx_index), tx.tx_hash);
+                }
+            }
+            HistoryOwner::Account(stake_address) => {
+                for tx in self
+                    .accounts_transactions(stake_address, pagination)
+                    .await?
+                {
+                    transactions.insert((tx.block_height, tx.tx_index), tx.tx_hash);
+                }
+            }
+        }
+
+        Ok(match order {
+            Order::Asc => transactions.into_values().collect(),
+            Order::Desc => transactions.into_values().rev().collect(),
+        })
+    }
+}
+
+/// Whether the stake credential is a script, and its hash, of a stake or base address.
+fn stake_credential(address: &str) -> Option<(bool, Vec<u8>)> {
+    let (_, bytes) = bech32::decode(address).ok()?;
+    let header = *bytes.first()?;
+
+    match header >> 4 {
+        // Base addresses, with a script stake credential for types 2 and 3.
+        0..=3 if bytes.len() == 57 => Some((header >> 5 == 1, bytes[29..].to_vec())),
+        // Stake addresses, with a script credential for type 15.
+        14 | 15 if bytes.len() == 29 => Some((header >> 4 == 15, bytes[1..].to_vec())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{base_address, AddressNetwork};
+    use bech32::{Bech32, Hrp};
+    use rstest::rstest;
+    use serde_json::json;
+
+    const UNIT: &str = "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e7574636f696e";
+
+    fn address(payment: u8, stake: u8) -> String {
+        base_address(&[payment; 28], &[stake; 28], AddressNetwork::Mainnet)
+    }
+
+    fn stake_address(stake: u8) -> String {
+        let mut bytes = vec![0xe1];
+        bytes.extend([stake; 28]);
+
+        bech32::encode::<Bech32>(Hrp::parse_unchecked("stake"), &bytes).unwrap()
+    }
+
+    fn tx(valid_contract: bool, delegations: i32, withdrawals: i32, mints: i32) -> TxContent {
+        serde_json::from_value(json!({
+            "hash": "1e043f100dce12d107f679685acd2fc0610e10f72a92d412794c9773d11d8477",
+            "block": "356b7d7dbb696ccd12775c016941057a9dc70898d87a63fc752271bb46856940",
+            "block_height": 123456,
+            "block_time": 1635505891,
+            "slot": 42000000,
+            "index": 1,
+            "output_amount": [{ "unit": "lovelace", "quantity": "42000000" }],
+            "fees": "200000",
+            "deposit": "0",
+            "size": 433,
+            "invalid_before": null,
+            "invalid_hereafter": "13885913",
+            "utxo_count": 4,
+            "withdrawal_count": withdrawals,
+            "mir_cert_count": 0,
+            "delegation_count": delegations,
+            "stake_cert_count": 0,
+            "pool_update_count": 0,
+            "pool_retire_count": 0,
+            "asset_mint_or_burn_count": mints,
+            "redeemer_count": 0,
+            "valid_contract": valid_contract
+        }))
+        .unwrap()
+    }
+
+    fn utxo(address: &str, lovelace: u64, tokens: u64, collateral: bool) -> serde_json::Value {
+        let mut amount = vec![json!({ "unit": "lovelace", "quantity": lovelace.to_string() })];
+
+        if tokens > 0 {
+            amount.push(json!({ "unit": UNIT, "quantity": tokens.to_string() }));
+        }
+
+        json!({
+            "address": address,
+            "amount": amount,
+            "tx_hash": "1a0570af966fb355a7160e4f82d5a80b8681b7955f5d44bec0dce628516157f0",
+            "output_index": 0,
+            "data_hash": null,
+            "inline_datum": null,
+            "reference_script_hash": null,
+            "collateral": collateral
+        })
+    }
+
+    fn utxos(inputs: Vec<serde_json::Value>, outputs: Vec<serde_json::Value>) -> TxContentUtxo {
+        serde_json::from_value(json!({
+            "hash": "1e043f100dce12d107f679685acd2fc0610e10f72a92d412794c9773d11d8477",
+            "inputs": inputs,
+            "outputs": outputs
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_receive() {
+        let owner = HistoryOwner::from(address(1, 2).as_str());
+        let utxos = utxos(
+            vec![utxo(&address(3, 4), 20_200_000, 5, false)],
+            vec![
+                utxo(&address(1, 2), 10_000_000, 3, false),
+                utxo(&address(3, 4), 10_000_000, 2, false),
+            ],
+        );
+        let entry = AddressHistoryEntry::new(&owner, &tx(true, 0, 0, 0), &utxos).unwrap();
+
+        assert_eq!(entry.kind, HistoryTxKind::Receive);
+        assert_eq!(entry.fee, None);
+        assert_eq!(entry.delta.lovelace, 10_000_000);
+        assert_eq!(entry.delta.assets, BTreeMap::from([(UNIT.to_string(), 3)]));
+        assert_eq!(entry.block_height, 123456);
+        assert_eq!(entry.tx_index, 1);
+    }
+
+    #[test]
+    fn test_send() {
+        let owner = HistoryOwner::from(address(1, 2).as_str());
+        let utxos = utxos(
+            vec![utxo(&address(1, 2), 20_200_000, 5, false)],
+            vec![
+                utxo(&address(1, 2), 7_000_000, 2, false),
+                utxo(&address(3, 4), 13_000_000, 3, false),
+            ],
+        );
+        let entry = AddressHistoryEntry::new(&owner, &tx(true, 0, 0, 0), &utxos).unwrap();
+
+        assert_eq!(entry.kind, HistoryTxKind::Send);
+        assert_eq!(entry.fee, Some(200_000));
+        assert_eq!(entry.delta.lovelace, -13_200_000);
+        assert_eq!(entry.delta.assets, BTreeMap::from([(UNIT.to_string(), -3)]));
+    }
+
+    #[rstest]
+    #[case(tx(true, 0, 0, 0), HistoryTxKind::SelfTransfer)]
+    #[case(tx(true, 1, 0, 0), HistoryTxKind::Delegation)]
+    #[case(tx(true, 0, 1, 0), HistoryTxKind::Withdrawal)]
+    #[case(tx(true, 0, 0, 1), HistoryTxKind::Mint)]
+    fn test_kind(#[case] tx: TxContent, #[case] kind: HistoryTxKind) {
+        let owner = HistoryOwner::from(vec![address(1, 2), address(5, 6)]);
+        let utxos = utxos(
+            vec![utxo(&address(1, 2), 20_200_000, 5, false)],
+            vec![utxo(&address(5, 6), 20_000_000, 5, false)],
+        );
+        let entry = AddressHistoryEntry::new(&owner, &tx, &utxos).unwrap();
+
+        assert_eq!(entry.kind, kind);
+        assert_eq!(entry.delta.lovelace, -200_000);
+        assert!(entry.delta.assets.is_empty());
+    }
+
+    #[test]
+    fn test_account() {
+        let owner = HistoryOwner::from(stake_address(2).as_str());
+
+        assert!(matches!(owner, HistoryOwner::Account(_)));
+        assert!(owner.owns(&address(1, 2)));
+        assert!(owner.owns(&address(7, 2)));
+        assert!(!owner.owns(&address(1, 3)));
+        assert!(!owner.owns("Ae2tdPwUPEZFRbyhz3cpfC2CumGzNkFBN2L42rcUc2yjQpEkxDbkPodpMAi"));
+
+        let utxos = utxos(
+            vec![utxo(&address(1, 2), 20_200_000, 0, false)],
+            vec![utxo(&address(7, 2), 20_000_000, 0, false)],
+        );
+        let entry = AddressHistoryEntry::new(&owner, &tx(true, 0, 0, 0), &utxos).unwrap();
+
+        assert_eq!(entry.kind, HistoryTxKind::SelfTransfer);
+        assert_eq!(entry.delta.lovelace, -200_000);
+    }
+
+    #[test]
+    fn test_failed_scripts() {
+        let owner = HistoryOwner::from(address(1, 2).as_str());
+        let utxos = utxos(
+            vec![
+                utxo(&address(1, 2), 20_000_000, 5, false),
+                utxo(&address(1, 2), 5_000_000, 0, true),
+            ],
+            vec![
+                utxo(&address(3, 4), 20_000_000, 5, false),
+                utxo(&address(1, 2), 4_000_000, 0, true),
+            ],
+        );
+
+        // Only the collateral is spent
+        let entry = AddressHistoryEntry::new(&owner, &tx(false, 0, 0, 0), &utxos).unwrap();
+
+        assert_eq!(entry.delta.lovelace, -1_000_000);
+        assert!(entry.delta.assets.is_empty());
+        assert_eq!(entry.kind, HistoryTxKind::Send);
+
+        // Valid transactions ignore the collateral
+        let entry = AddressHistoryEntry::new(&owner, &tx(true, 0, 0, 0), &utxos).unwrap();
+
+        assert_eq!(entry.delta.lovelace, -20_000_000);
+        assert_eq!(entry.delta.assets, BTreeMap::from([(UNIT.to_string(), -5)]));
+        assert_eq!(entry.kind, HistoryTxKind::Send);
+    }
+
+    #[test]
+    fn test_reference_inputs() {
+        let owner = HistoryOwner::from(address(1, 2).as_str());
+        let mut reference = utxo(&address(1, 2), 50_000_000, 0, false);
+        reference["reference"] = json!(true);
+
+        let utxos = utxos(
+            vec![utxo(&address(3, 4), 10_200_000, 0, false), reference],
+            vec![utxo(&address(1, 2), 10_000_000, 0, false)],
+        );
+        let entry = AddressHistoryEntry::new(&owner, &tx(true, 0, 0, 0), &utxos).unwrap();
+
+        assert_eq!(entry.kind, HistoryTxKind::Receive);
+        assert_eq!(entry.delta.lovelace, 10_000_000);
+    }
+}
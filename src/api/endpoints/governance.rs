@@ -1,4 +1,4 @@
-use crate::*;
+use crate::{governance_id::normalize_drep_id, *};
 use blockfrost_openapi::models::{
     drep::Drep, drep_delegators_inner::DrepDelegatorsInner, drep_metadata::DrepMetadata,
     drep_updates_inner::DrepUpdatesInner, drep_votes_inner::DrepVotesInner,
@@ -15,7 +15,15 @@ impl BlockfrostAPI {
     }
 
     /// Return information about a specific delegated representative.
+    ///
+    /// The DRep methods accept [CIP-129] IDs as well as legacy [CIP-105] ones, which are
+    /// converted before the request. See [`GovernanceCredential`].
+    ///
+    /// [CIP-105]: https://cips.cardano.org/cip/CIP-0105
+    /// [CIP-129]: https://cips.cardano.org/cip/CIP-0129
     pub async fn dreps_by_id(&self, drep_id: &str) -> BlockfrostResult<Drep> {
+        let drep_id = normalize_drep_id(drep_id);
+
         self.call_endpoint(format!("/governance/dreps/{drep_id}").as_str())
             .await
     }
@@ -24,6 +32,8 @@ impl BlockfrostAPI {
     pub async fn dreps_delegators(
         &self, drep_id: &str, pagination: Pagination,
     ) -> BlockfrostResult<Vec<DrepDelegatorsInner>> {
+        let drep_id = normalize_drep_id(drep_id);
+
         self.call_paged_endpoint(
             format!("/governance/dreps/{drep_id}/delegators").as_str(),
             pagination,
@@ -33,6 +43,8 @@ impl BlockfrostAPI {
 
     /// Return the metadata for a specific DRep.
     pub async fn dreps_metadata(&self, drep_id: &str) -> BlockfrostResult<DrepMetadata> {
+        let drep_id = normalize_drep_id(drep_id);
+
         self.call_endpoint(format!("/governance/dreps/{drep_id}/metadata").as_str())
             .await
     }
@@ -41,6 +53,8 @@ impl BlockfrostAPI {
     pub async fn dreps_updates(
         &self, drep_id: &str, pagination: Pagination,
     ) -> BlockfrostResult<Vec<DrepUpdatesInner>> {
+        let drep_id = normalize_drep_id(drep_id);
+
         self.call_paged_endpoint(
             format!("/governance/dreps/{drep_id}/updates").as_str(),
             pagination,
@@ -52,6 +66,8 @@ impl BlockfrostAPI {
     pub async fn dreps_votes(
         &self, drep_id: &str, pagination: Pagination,
     ) -> BlockfrostResult<Vec<DrepVotesInner>> {
+        let drep_id = normalize_drep_id(drep_id);
+
         self.call_paged_endpoint(
             format!("/governance/dreps/{drep_id}/votes").as_str(),
             pagination,
@@ -66,6 +82,11 @@ impl BlockfrostAPI {
     }
 
     /// Return information about a specific governance proposal.
+    ///
+    /// To look up a [CIP-129] `gov_action1…` ID, parse it into a [`GovActionId`] and use
+    /// [`proposals_by_gov_action`](Self::proposals_by_gov_action).
+    ///
+    /// [CIP-129]: https://cips.cardano.org/cip/CIP-0129
     pub async fn proposals_by_id(
         &self, tx_hash: &str, cert_index: i32,
     ) -> BlockfrostResult<Proposal> {
@@ -114,6 +135,43 @@ impl BlockfrostAPI {
         )
         .await
     }
+
+    /// Same as [`proposals_by_id`](Self::proposals_by_id), for a [`GovActionId`].
+    pub async fn proposals_by_gov_action(&self, id: &GovActionId) -> BlockfrostResult<Proposal> {
+        self.proposals_by_id(&id.tx_hash(), id.cert_index()).await
+    }
+
+    /// Same as [`proposals_parameters`](Self::proposals_parameters), for a [`GovActionId`].
+    pub async fn proposals_parameters_by_gov_action(
+        &self, id: &GovActionId,
+    ) -> BlockfrostResult<ProposalParameters> {
+        self.proposals_parameters(&id.tx_hash(), id.cert_index())
+            .await
+    }
+
+    /// Same as [`proposals_withdrawals`](Self::proposals_withdrawals), for a [`GovActionId`].
+    pub async fn proposals_withdrawals_by_gov_action(
+        &self, id: &GovActionId, pagination: Pagination,
+    ) -> BlockfrostResult<Vec<ProposalWithdrawalsInner>> {
+        self.proposals_withdrawals(&id.tx_hash(), id.cert_index(), pagination)
+            .await
+    }
+
+    /// Same as [`proposals_votes`](Self::proposals_votes), for a [`GovActionId`].
+    pub async fn proposals_votes_by_gov_action(
+        &self, id: &GovActionId, pagination: Pagination,
+    ) -> BlockfrostResult<Vec<ProposalVotesInner>> {
+        self.proposals_votes(&id.tx_hash(), id.cert_index(), pagination)
+            .await
+    }
+
+    /// Same as [`proposals_metadata`](Self::proposals_metadata), for a [`GovActionId`].
+    pub async fn proposals_metadata_by_gov_action(
+        &self, id: &GovActionId,
+    ) -> BlockfrostResult<ProposalMetadata> {
+        self.proposals_metadata(&id.tx_hash(), id.cert_index())
+            .await
+    }
 }
 
 #[cfg(test)]
@@ -300,6 +358,34 @@ mod tests {
         serde_json::from_value::<ProposalMetadata>(json_value).unwrap();
     }
 
+    #[tokio::test]
+    async fn test_proposal_votes_by_gov_action() {
+        let server = httpmock::MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path(
+                "/governance/proposals/2dd15e0ef6e6a17841cb9541c27724072ce4d4b79b91e58432fbaa32d9572531/1/votes",
+            );
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .body("[]");
+        });
+
+        let mut settings = BlockFrostSettings::new();
+        settings.base_url = Some(server.base_url());
+
+        let api = BlockfrostAPI::new("mainnetxxxxxxxxxxxxxxxxxxxxxxxxxxx", settings);
+        let id: GovActionId = "2dd15e0ef6e6a17841cb9541c27724072ce4d4b79b91e58432fbaa32d9572531#1"
+            .parse()
+            .unwrap();
+        let votes = api
+            .proposals_votes_by_gov_action(&id, Pagination::default())
+            .await
+            .unwrap();
+
+        mock.assert();
+        assert!(votes.is_empty());
+    }
+
     #[tokio::test]
     async fn test_proposal_parameters() {
         // Note: ProposalParameters requires a nested ProposalParametersParameters struct
@@ -14,4 +14,4 @@ mod nutlink;
 mod pools;
 mod scripts;
 mod transactions;
-mod utils;
+pub(crate) mod utils;
@@ -1,7 +1,9 @@
 use crate::{request::send_request, url::Url, *};
 use blockfrost_openapi::models::utils_addresses_xpub::UtilsAddressesXpub;
 use reqwest::{header::HeaderValue, Body, Method};
-use serde_json::{from_str as json_from, Value};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::from_str as json_from;
+use std::{collections::BTreeMap, fmt, str::FromStr};
 
 impl BlockfrostAPI {
     /// Derive an address from an xpub key.
@@ -13,7 +15,9 @@ impl BlockfrostAPI {
     }
 
     /// Submit a transaction for execution units evaluation.
-    pub async fn utils_tx_evaluate(&self, transaction_data: Vec<u8>) -> BlockfrostResult<Value> {
+    pub async fn utils_tx_evaluate(
+        &self, transaction_data: Vec<u8>,
+    ) -> BlockfrostResult<TxEvaluation> {
         let body = Body::from(transaction_data);
         let url = Url::from_endpoint(self.base_url.as_str(), "/utils/txs/evaluate")?;
 
@@ -38,15 +42,16 @@ impl BlockfrostAPI {
     }
 
     /// Submit a transaction for execution units evaluation with additional UTXOs.
-    pub async fn utils_tx_evaluate_utxos(&self, request_body: Value) -> BlockfrostResult<Value> {
-        let body = Body::from(request_body.to_string());
+    pub async fn utils_tx_evaluate_utxos(
+        &self, request_body: &TxEvaluationRequest,
+    ) -> BlockfrostResult<TxEvaluation> {
         let url = Url::from_endpoint(self.base_url.as_str(), "/utils/txs/evaluate/utxos")?;
 
         let request = self
             .client
             .request(Method::POST, &url)
             .header("Content-Type", HeaderValue::from_static("application/json"))
-            .body(body);
+            .json(request_body);
 
         let (status, text) = send_request(request, self.settings.retry_settings)
             .await
@@ -62,3 +67,436 @@ impl BlockfrostAPI {
         json_from(&text).map_err(|reason| json_error(url, text, reason))
     }
 }
+
+/// Request body of [`utils_tx_evaluate_utxos`](BlockfrostAPI::utils_tx_evaluate_utxos).
+///
+/// Follows the Ogmios `EvaluateTx` format: the transaction CBOR and the UTxOs it spends or
+/// references that are not on-chain yet.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxEvaluationRequest {
+    /// Transaction CBOR, in hex.
+    pub cbor: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_utxo_set: Vec<AdditionalUtxo>,
+}
+
+impl TxEvaluationRequest {
+    /// Create a request for the transaction `transaction_data`, without additional UTxOs.
+    pub fn new(transaction_data: &[u8]) -> Self {
+        Self {
+            cbor: hex::encode(transaction_data),
+            additional_utxo_set: Vec::new(),
+        }
+    }
+
+    /// Add a UTxO to the additional UTxO set.
+    pub fn with_utxo(mut self, input: UtxoReference, output: UtxoOutput) -> Self {
+        self.additional_utxo_set.push(AdditionalUtxo(input, output));
+        self
+    }
+}
+
+/// A UTxO of [`TxEvaluationRequest::additional_utxo_set`], serialized as `[input, output]`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AdditionalUtxo(pub UtxoReference, pub UtxoOutput);
+
+/// Reference to a transaction output.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtxoReference {
+    pub tx_id: String,
+    pub index: u32,
+}
+
+/// Transaction output of an [`AdditionalUtxo`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtxoOutput {
+    pub address: String,
+    pub value: UtxoValue,
+    /// Hash of the datum, for outputs with a datum hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datum_hash: Option<String>,
+    /// Inline datum CBOR, in hex.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datum: Option<String>,
+    /// Reference script, as `{ "plutus:v2": "<cbor hex>" }` or `{ "native": ... }`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<JsonValue>,
+}
+
+/// Value of a [`UtxoOutput`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UtxoValue {
+    /// Amount of lovelace.
+    pub coins: u64,
+    /// Native assets, keyed by `<policy_id>.<asset_name>` in hex.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub assets: BTreeMap<String, u64>,
+}
+
+/// Response of [`utils_tx_evaluate`](BlockfrostAPI::utils_tx_evaluate) and
+/// [`utils_tx_evaluate_utxos`](BlockfrostAPI::utils_tx_evaluate_utxos).
+///
+/// Either `result` or `fault` is set, following the Ogmios JSON-WSP format.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TxEvaluation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<TxEvaluationResult>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fault: Option<TxEvaluationFault>,
+}
+
+impl TxEvaluation {
+    /// Execution units of each redeemer, if the evaluation succeeded.
+    pub fn ex_units(&self) -> Option<&BTreeMap<RedeemerPointer, ExUnits>> {
+        match &self.result {
+            Some(TxEvaluationResult::EvaluationResult(ex_units)) => Some(ex_units),
+            _ => None,
+        }
+    }
+
+    /// Failures of each redeemer, if the evaluation failed because of script errors.
+    pub fn script_failures(&self) -> Option<&BTreeMap<RedeemerPointer, Vec<ScriptFailure>>> {
+        match &self.result {
+            Some(TxEvaluationResult::EvaluationFailure(EvaluationFailure::ScriptFailures(
+                failures,
+            ))) => Some(failures),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of a transaction evaluation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TxEvaluationResult {
+    /// Every script succeeded with the given budgets.
+    EvaluationResult(BTreeMap<RedeemerPointer, ExUnits>),
+    EvaluationFailure(EvaluationFailure),
+}
+
+/// Reason why a transaction could not be evaluated.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum EvaluationFailure {
+    /// Some scripts failed, with the errors of each redeemer.
+    ScriptFailures(BTreeMap<RedeemerPointer, Vec<ScriptFailure>>),
+    /// Any other failure, such as unknown inputs or an incompatible era.
+    #[serde(untagged)]
+    Other(JsonValue),
+}
+
+/// Error of a single redeemer.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScriptFailure {
+    /// The validator returned an error, along with the traces it emitted.
+    ValidatorFailed {
+        error: String,
+        #[serde(default)]
+        traces: Vec<String>,
+    },
+    /// Any other error, such as missing datums or scripts.
+    #[serde(untagged)]
+    Other(JsonValue),
+}
+
+/// Request rejected by the evaluator, for example because the CBOR could not be decoded.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxEvaluationFault {
+    pub code: String,
+    pub string: String,
+}
+
+/// Execution units consumed by a script.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ExUnits {
+    pub memory: u64,
+    pub steps: u64,
+}
+
+impl std::ops::Add for ExUnits {
+    type Output = ExUnits;
+
+    fn add(self, other: ExUnits) -> ExUnits {
+        ExUnits {
+            memory: self.memory + other.memory,
+            steps: self.steps + other.steps,
+        }
+    }
+}
+
+impl std::iter::Sum for ExUnits {
+    fn sum<I: Iterator<Item = ExUnits>>(iter: I) -> ExUnits {
+        iter.fold(ExUnits::default(), |total, ex_units| total + ex_units)
+    }
+}
+
+/// Identifies a redeemer by its purpose and index, serialized as `<purpose>:<index>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RedeemerPointer {
+    pub purpose: RedeemerPurpose,
+    pub index: u32,
+}
+
+/// What a redeemer is used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RedeemerPurpose {
+    Spend,
+    Mint,
+    Certificate,
+    Withdrawal,
+    Vote,
+    Propose,
+}
+
+impl RedeemerPurpose {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RedeemerPurpose::Spend => "spend",
+            RedeemerPurpose::Mint => "mint",
+            RedeemerPurpose::Certificate => "certificate",
+            RedeemerPurpose::Withdrawal => "withdrawal",
+            RedeemerPurpose::Vote => "vote",
+            RedeemerPurpose::Propose => "propose",
+        }
+    }
+}
+
+impl FromStr for RedeemerPointer {
+    type Err = BlockfrostError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || BlockfrostError::Parsing {
+            message: format!("invalid redeemer pointer '{text}'"),
+        };
+        let (purpose, index) = text.split_once(':').ok_or_else(invalid)?;
+
+        let purpose = match purpose {
+            "spend" => RedeemerPurpose::Spend,
+            "mint" => RedeemerPurpose::Mint,
+            "certificate" | "publish" => RedeemerPurpose::Certificate,
+            "withdrawal" => RedeemerPurpose::Withdrawal,
+            "vote" => RedeemerPurpose::Vote,
+            "propose" => RedeemerPurpose::Propose,
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self {
+            purpose,
+            index: index.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl fmt::Display for RedeemerPointer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.purpose.as_str(), self.index)
+    }
+}
+
+impl Serialize for RedeemerPointer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for RedeemerPointer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_utils_tx_evaluate_result() {
+        let json_value = json!({
+            "type": "jsonwsp/response",
+            "version": "1.0",
+            "servicename": "ogmios",
+            "methodname": "EvaluateTx",
+            "result": {
+                "EvaluationResult": {
+                    "spend:0": { "memory": 1700, "steps": 476468 },
+                    "mint:1": { "memory": 1100, "steps": 200000 }
+                }
+            },
+            "reflection": { "id": "c56e2e2d-1a2b-4a8b-9d5f-2d6f1e7a6b9c" }
+        });
+
+        let evaluation = serde_json::from_value::<TxEvaluation>(json_value).unwrap();
+        let ex_units = evaluation.ex_units().unwrap();
+        let spend = RedeemerPointer {
+            purpose: RedeemerPurpose::Spend,
+            index: 0,
+        };
+
+        assert_eq!(
+            ex_units[&spend],
+            ExUnits {
+                memory: 1700,
+                steps: 476468
+            }
+        );
+        assert_eq!(
+            ex_units.values().copied().sum::<ExUnits>(),
+            ExUnits {
+                memory: 2800,
+                steps: 676468
+            }
+        );
+        assert!(evaluation.script_failures().is_none());
+    }
+
+    #[test]
+    fn test_utils_tx_evaluate_script_failure() {
+        let json_value = json!({
+            "type": "jsonwsp/response",
+            "version": "1.0",
+            "servicename": "ogmios",
+            "methodname": "EvaluateTx",
+            "result": {
+                "EvaluationFailure": {
+                    "ScriptFailures": {
+                        "spend:1": [
+                            {
+                                "validatorFailed": {
+                                    "error": "An error has occurred: User error",
+                                    "traces": ["Deadline not reached", "PT5"]
+                                }
+                            }
+                        ],
+                        "withdrawal:0": [
+                            { "missingRequiredScripts": { "missing": ["withdrawal:0"] } }
+                        ]
+                    }
+                }
+            }
+        });
+
+        let evaluation = serde_json::from_value::<TxEvaluation>(json_value).unwrap();
+        let failures = evaluation.script_failures().unwrap();
+
+        assert_eq!(
+            failures[&"spend:1".parse().unwrap()],
+            vec![ScriptFailure::ValidatorFailed {
+                error: "An error has occurred: User error".to_string(),
+                traces: vec!["Deadline not reached".to_string(), "PT5".to_string()],
+            }]
+        );
+        assert!(matches!(
+            failures[&"withdrawal:0".parse().unwrap()][0],
+            ScriptFailure::Other(_)
+        ));
+        assert!(evaluation.ex_units().is_none());
+    }
+
+    #[test]
+    fn test_utils_tx_evaluate_other_failure() {
+        let json_value = json!({
+            "result": {
+                "EvaluationFailure": {
+                    "UnknownInputs": [
+                        {
+                            "txId": "bdff90a5ed9604e7d42cdbe412d2a0368207835e22a552244728a8259b2d6208",
+                            "index": 0
+                        }
+                    ]
+                }
+            }
+        });
+
+        let evaluation = serde_json::from_value::<TxEvaluation>(json_value).unwrap();
+
+        assert!(matches!(
+            evaluation.result,
+            Some(TxEvaluationResult::EvaluationFailure(
+                EvaluationFailure::Other(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_utils_tx_evaluate_fault() {
+        let json_value = json!({
+            "type": "jsonwsp/fault",
+            "version": "1.0",
+            "servicename": "ogmios",
+            "fault": {
+                "code": "client",
+                "string": "Invalid request: couldn't decode plausible transaction."
+            }
+        });
+
+        let evaluation = serde_json::from_value::<TxEvaluation>(json_value).unwrap();
+
+        assert_eq!(evaluation.fault.unwrap().code, "client");
+        assert!(evaluation.result.is_none());
+    }
+
+    #[test]
+    fn test_utils_tx_evaluate_utxos_request() {
+        let output = UtxoOutput {
+            address: "addr_test1wz2mzj532enpgu5vgwxuh249fpknx5ft9wxse2876z0mp2q89ye7k".to_string(),
+            value: UtxoValue {
+                coins: 2000000,
+                assets: BTreeMap::from([(
+                    "c6e65ba7878b2f8ea0ad39287d3e2fd256dc5c4160fc19bdf4c4d87e.7447454e53"
+                        .to_string(),
+                    10,
+                )]),
+            },
+            datum_hash: None,
+            datum: Some("d87980".to_string()),
+            script: None,
+        };
+        let input = UtxoReference {
+            tx_id: "bdff90a5ed9604e7d42cdbe412d2a0368207835e22a552244728a8259b2d6208".to_string(),
+            index: 1,
+        };
+        let request = TxEvaluationRequest::new(&[0x84, 0xa4]).with_utxo(input, output);
+
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "cbor": "84a4",
+                "additionalUtxoSet": [
+                    [
+                        {
+                            "txId": "bdff90a5ed9604e7d42cdbe412d2a0368207835e22a552244728a8259b2d6208",
+                            "index": 1
+                        },
+                        {
+                            "address": "addr_test1wz2mzj532enpgu5vgwxuh249fpknx5ft9wxse2876z0mp2q89ye7k",
+                            "value": {
+                                "coins": 2000000,
+                                "assets": {
+                                    "c6e65ba7878b2f8ea0ad39287d3e2fd256dc5c4160fc19bdf4c4d87e.7447454e53": 10
+                                }
+                            },
+                            "datum": "d87980"
+                        }
+                    ]
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_redeemer_pointer() {
+        let pointer: RedeemerPointer = "certificate:3".parse().unwrap();
+
+        assert_eq!(pointer.purpose, RedeemerPurpose::Certificate);
+        assert_eq!(pointer.index, 3);
+        assert_eq!(pointer.to_string(), "certificate:3");
+        assert!("spend".parse::<RedeemerPointer>().is_err());
+        assert!("reward:0".parse::<RedeemerPointer>().is_err());
+        assert!("spend:-1".parse::<RedeemerPointer>().is_err());
+    }
+}
@@ -2,18 +2,22 @@ pub(super) mod endpoints;
 use crate::{
     pagination::Pagination,
     request::{fetch_all_pages, send_get_request},
+    token_registry::TokenRegistryCache,
     url::Url,
     utils::build_header_map,
     utils::create_client_with_project_id,
-    BlockFrostSettings, BlockfrostError,
+    BlockFrostSettings, BlockfrostError, BlockfrostResult, ChainTime,
 };
 use reqwest::ClientBuilder;
+use std::sync::{Arc, Mutex, OnceLock};
 
 #[derive(Debug, Clone)]
 pub struct BlockfrostAPI {
     base_url: String,
     settings: BlockFrostSettings,
     client: reqwest::Client,
+    chain_time: Arc<OnceLock<ChainTime>>,
+    pub(crate) token_registry: Arc<Mutex<TokenRegistryCache>>,
 }
 
 impl BlockfrostAPI {
@@ -28,6 +32,8 @@ impl BlockfrostAPI {
             settings,
             client,
             base_url,
+            chain_time: Default::default(),
+            token_registry: Default::default(),
         }
     }
 
@@ -46,9 +52,26 @@ impl BlockfrostAPI {
                 settings,
                 client,
                 base_url,
+                chain_time: Default::default(),
+                token_registry: Default::default(),
             })
     }
 
+    /// Return the [`ChainTime`] of the network, to convert between slots, epochs and time.
+    ///
+    /// Built from [`genesis`](Self::genesis) and [`network_eras`](Self::network_eras) on the
+    /// first call, then cached and shared by clones of this client.
+    pub async fn chain_time(&self) -> BlockfrostResult<&ChainTime> {
+        if let Some(chain_time) = self.chain_time.get() {
+            return Ok(chain_time);
+        }
+
+        let (genesis, eras) = futures::try_join!(self.genesis(), self.network_eras())?;
+        let chain_time = ChainTime::new(&genesis, &eras)?;
+
+        Ok(self.chain_time.get_or_init(|| chain_time))
+    }
+
     async fn call_endpoint<T>(&self, url_endpoint: &str) -> Result<T, BlockfrostError>
     where
         T: for<'de> serde::Deserialize<'de> + serde::de::DeserializeOwned,
@@ -79,4 +102,8 @@ impl BlockfrostAPI {
             send_get_request(&self.client, url, self.settings.retry_settings).await
         }
     }
+
+    pub(crate) fn batch_concurrency(&self) -> usize {
+        self.settings.batch_concurrency.max(1)
+    }
 }
@@ -0,0 +1,380 @@
+use crate::{utils::blake2b_160, BlockfrostError, BlockfrostResult};
+use bech32::{Bech32, Hrp};
+use std::{fmt, str::FromStr};
+
+/// Length of a policy ID in bytes (a blake2b-224 script hash).
+pub const POLICY_ID_LENGTH: usize = 28;
+
+/// Maximum length of an asset name in bytes.
+pub const ASSET_NAME_MAX_LENGTH: usize = 32;
+
+/// Unit used by the API for ADA amounts, which is not a native asset.
+pub const LOVELACE_UNIT: &str = "lovelace";
+
+/// A native asset identifier, split into policy ID and asset name.
+///
+/// Endpoints such as [`assets`](crate::BlockfrostAPI::assets),
+/// [`assets_policy_by_id`](crate::BlockfrostAPI::assets_policy_by_id) and
+/// [`accounts_addresses_assets`](crate::BlockfrostAPI::accounts_addresses_assets) return assets
+/// as a `unit`, the hex concatenation of both parts. [`AssetId`] decodes it so the name, its
+/// [CIP-67] label and its [CIP-14] fingerprint can be displayed without extra lookups.
+///
+/// [CIP-14]: https://cips.cardano.org/cip/CIP-0014
+/// [CIP-67]: https://cips.cardano.org/cip/CIP-0067
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AssetId {
+    policy_id: [u8; POLICY_ID_LENGTH],
+    asset_name: Vec<u8>,
+}
+
+impl AssetId {
+    /// Create an [`AssetId`] from a hex policy ID and a hex asset name.
+    pub fn new(policy_id: &str, asset_name: &str) -> BlockfrostResult<Self> {
+        let policy_id = decode_hex(policy_id, "policy ID")?;
+        let asset_name = decode_hex(asset_name, "asset name")?;
+
+        Self::from_bytes(&policy_id, asset_name)
+    }
+
+    /// Create an [`AssetId`] from raw policy ID and asset name bytes.
+    pub fn from_bytes(policy_id: &[u8], asset_name: Vec<u8>) -> BlockfrostResult<Self> {
+        let policy_id = policy_id.try_into().map_err(|_| BlockfrostError::Parsing {
+            message: format!(
+                "policy ID must be {POLICY_ID_LENGTH} bytes long, got {}",
+                policy_id.len()
+            ),
+        })?;
+
+        if asset_name.len() > ASSET_NAME_MAX_LENGTH {
+            return Err(BlockfrostError::Parsing {
+                message: format!(
+                    "asset name must be at most {ASSET_NAME_MAX_LENGTH} bytes long, got {}",
+                    asset_name.len()
+                ),
+            });
+        }
+
+        Ok(Self {
+            policy_id,
+            asset_name,
+        })
+    }
+
+    /// Parse a `unit` as returned by the API (hex policy ID followed by the hex asset name).
+    ///
+    /// Fails for [`LOVELACE_UNIT`], which is not a native asset.
+    pub fn from_unit(unit: &str) -> BlockfrostResult<Self> {
+        if unit == LOVELACE_UNIT {
+            return Err(BlockfrostError::Parsing {
+                message: format!("'{LOVELACE_UNIT}' is not a native asset unit"),
+            });
+        }
+
+        let (policy_id, asset_name) =
+            unit.split_at_checked(POLICY_ID_LENGTH * 2).ok_or_else(|| {
+                BlockfrostError::Parsing {
+                    message: format!("asset unit '{unit}' is shorter than a policy ID"),
+                }
+            })?;
+
+        Self::new(policy_id, asset_name)
+    }
+
+    /// Policy ID as raw bytes.
+    pub fn policy_id(&self) -> &[u8; POLICY_ID_LENGTH] {
+        &self.policy_id
+    }
+
+    /// Policy ID in hex.
+    pub fn policy_id_hex(&self) -> String {
+        hex::encode(self.policy_id)
+    }
+
+    /// Asset name as raw bytes, including any CIP-67 label prefix.
+    pub fn asset_name(&self) -> &[u8] {
+        &self.asset_name
+    }
+
+    /// Asset name in hex, including any CIP-67 label prefix.
+    pub fn asset_name_hex(&self) -> String {
+        hex::encode(&self.asset_name)
+    }
+
+    /// The `unit` of this asset, as used by the API.
+    pub fn unit(&self) -> String {
+        self.policy_id_hex() + &self.asset_name_hex()
+    }
+
+    /// Asset name decoded as UTF-8, if it is valid and printable.
+    ///
+    /// The CIP-67 label prefix, if any, is not stripped. See [`AssetId::display_name`].
+    pub fn asset_name_utf8(&self) -> Option<&str> {
+        printable_utf8(&self.asset_name)
+    }
+
+    /// The [CIP-67] label prefixing the asset name, if any.
+    ///
+    /// [CIP-67]: https://cips.cardano.org/cip/CIP-0067
+    pub fn label(&self) -> Option<Cip67Label> {
+        let prefix = self.asset_name.get(..4)?;
+
+        Cip67Label::from_prefix(prefix.try_into().ok()?)
+    }
+
+    /// Asset name without its CIP-67 label prefix, if any.
+    pub fn name_without_label(&self) -> &[u8] {
+        match self.label() {
+            Some(_) => &self.asset_name[4..],
+            None => &self.asset_name,
+        }
+    }
+
+    /// Human-readable name: the label-stripped asset name as UTF-8 when it is printable,
+    /// hex otherwise.
+    pub fn display_name(&self) -> String {
+        let name = self.name_without_label();
+
+        match printable_utf8(name) {
+            Some(name) => name.to_string(),
+            None => hex::encode(name),
+        }
+    }
+
+    /// [CIP-14] fingerprint of the asset, `asset1…`.
+    ///
+    /// [CIP-14]: https://cips.cardano.org/cip/CIP-0014
+    pub fn fingerprint(&self) -> String {
+        let mut bytes = self.policy_id.to_vec();
+        bytes.extend_from_slice(&self.asset_name);
+
+        // Safety: the HRP is a valid constant and a 20 bytes payload is below the length limit.
+        bech32::encode::<Bech32>(Hrp::parse_unchecked("asset"), &blake2b_160(&bytes)).unwrap()
+    }
+}
+
+impl FromStr for AssetId {
+    type Err = BlockfrostError;
+
+    fn from_str(unit: &str) -> Result<Self, Self::Err> {
+        Self::from_unit(unit)
+    }
+}
+
+/// Formats the asset as its `unit`.
+impl fmt::Display for AssetId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.unit())
+    }
+}
+
+/// Asset name label defined by [CIP-67].
+///
+/// The label is encoded in the first 4 bytes of the asset name, as
+/// `[0000 | 16 bits label | 8 bits CRC-8 of the label | 0000]`.
+///
+/// [CIP-67]: https://cips.cardano.org/cip/CIP-0067
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Cip67Label {
+    /// `(100)` reference NFT, holding the CIP-68 metadata datum.
+    ReferenceNft,
+    /// `(222)` non-fungible token.
+    Nft,
+    /// `(333)` fungible token.
+    Ft,
+    /// `(444)` rich fungible token.
+    Rft,
+    /// Any other registered or unregistered label.
+    Other(u16),
+}
+
+impl Cip67Label {
+    /// Numeric value of the label.
+    pub fn value(self) -> u16 {
+        match self {
+            Cip67Label::ReferenceNft => 100,
+            Cip67Label::Nft => 222,
+            Cip67Label::Ft => 333,
+            Cip67Label::Rft => 444,
+            Cip67Label::Other(value) => value,
+        }
+    }
+
+    /// Create a label from its numeric value.
+    pub fn from_value(value: u16) -> Self {
+        match value {
+            100 => Cip67Label::ReferenceNft,
+            222 => Cip67Label::Nft,
+            333 => Cip67Label::Ft,
+            444 => Cip67Label::Rft,
+            value => Cip67Label::Other(value),
+        }
+    }
+
+    /// Encode the label into its 4 bytes asset name prefix.
+    pub fn to_prefix(self) -> [u8; 4] {
+        let [high, low] = self.value().to_be_bytes();
+        let checksum = crc8(&[high, low]);
+
+        let prefixed: u32 = (u32::from(self.value()) << 12) | (u32::from(checksum) << 4);
+
+        prefixed.to_be_bytes()
+    }
+
+    /// Decode a label from an asset name prefix, checking its padding and checksum.
+    pub fn from_prefix(prefix: [u8; 4]) -> Option<Self> {
+        let prefixed = u32::from_be_bytes(prefix);
+
+        if prefixed >> 28 != 0 || prefixed & 0xf != 0 {
+            return None;
+        }
+
+        let value = (prefixed >> 12) as u16;
+        let checksum = ((prefixed >> 4) & 0xff) as u8;
+
+        (crc8(&value.to_be_bytes()) == checksum).then(|| Self::from_value(value))
+    }
+}
+
+impl fmt::Display for Cip67Label {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({})", self.value())
+    }
+}
+
+// CRC-8 with polynomial 0x07 and zero initial value, as required by CIP-67.
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |mut crc, byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+fn printable_utf8(bytes: &[u8]) -> Option<&str> {
+    let text = std::str::from_utf8(bytes).ok()?;
+
+    (!text.is_empty() && !text.chars().any(char::is_control)).then_some(text)
+}
+
+fn decode_hex(text: &str, what: &str) -> BlockfrostResult<Vec<u8>> {
+    hex::decode(text).map_err(|reason| BlockfrostError::Parsing {
+        message: format!("invalid hex in {what} '{text}': {reason}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        "7eae28af2208be856f7a119668ae52a49b73725e326dc16579dcc373",
+        "",
+        "asset1rjklcrnsdzqp65wjgrg55sy9723kw09mlgvlc3"
+    )]
+    #[case(
+        "7eae28af2208be856f7a119668ae52a49b73725e326dc16579dcc37e",
+        "",
+        "asset1nl0puwxmhas8fawxp8nx4e2q3wekg969n2auw3"
+    )]
+    #[case(
+        "1e349c9bdea19fd6c147626a5260bc44b71635f398b67c59881df209",
+        "504154415445",
+        "asset1hv4p5tv2a837mzqrst04d0dcptdjmluqvdx9k3"
+    )]
+    #[case(
+        "7eae28af2208be856f7a119668ae52a49b73725e326dc16579dcc373",
+        "1e349c9bdea19fd6c147626a5260bc44b71635f398b67c59881df209",
+        "asset17jd78wukhtrnmjh3fngzasxm8rck0l2r4hhyyt"
+    )]
+    #[case(
+        "1e349c9bdea19fd6c147626a5260bc44b71635f398b67c59881df209",
+        "7eae28af2208be856f7a119668ae52a49b73725e326dc16579dcc373",
+        "asset1aqrdypg669jgazruv5ah07nuyqe0wxjhe2el6f"
+    )]
+    fn test_fingerprint(#[case] policy_id: &str, #[case] asset_name: &str, #[case] expected: &str) {
+        let asset = AssetId::new(policy_id, asset_name).unwrap();
+
+        assert_eq!(asset.fingerprint(), expected);
+    }
+
+    #[test]
+    fn test_from_unit() {
+        let unit = "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e7574636f696e";
+        let asset: AssetId = unit.parse().unwrap();
+
+        assert_eq!(
+            asset.policy_id_hex(),
+            "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a7"
+        );
+        assert_eq!(asset.asset_name_hex(), "6e7574636f696e");
+        assert_eq!(asset.asset_name_utf8(), Some("nutcoin"));
+        assert_eq!(asset.display_name(), "nutcoin");
+        assert_eq!(asset.label(), None);
+        assert_eq!(asset.unit(), unit);
+        assert_eq!(asset.to_string(), unit);
+    }
+
+    #[rstest]
+    #[case("lovelace")]
+    #[case("b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38")]
+    #[case("b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e757")]
+    #[case("zzd07d45fe9514f80213f4020e5a61241458be626841cde717cb38a7")]
+    fn test_from_unit_invalid(#[case] unit: &str) {
+        assert!(AssetId::from_unit(unit).is_err());
+    }
+
+    #[rstest]
+    #[case(Cip67Label::ReferenceNft, "000643b0")]
+    #[case(Cip67Label::Nft, "000de140")]
+    #[case(Cip67Label::Ft, "0014df10")]
+    #[case(Cip67Label::Rft, "001bc280")]
+    #[case(Cip67Label::Other(0), "00000000")]
+    #[case(Cip67Label::Other(1), "00001070")]
+    #[case(Cip67Label::Other(65535), "0ffff240")]
+    fn test_cip67_label_prefix(#[case] label: Cip67Label, #[case] prefix: &str) {
+        assert_eq!(hex::encode(label.to_prefix()), prefix);
+
+        let prefix = hex::decode(prefix).unwrap().try_into().unwrap();
+        assert_eq!(Cip67Label::from_prefix(prefix), Some(label));
+    }
+
+    #[test]
+    fn test_cip67_label_invalid_checksum() {
+        assert_eq!(Cip67Label::from_prefix([0x00, 0x0d, 0xe1, 0x50]), None);
+        assert_eq!(Cip67Label::from_prefix([0x10, 0x0d, 0xe1, 0x40]), None);
+    }
+
+    #[test]
+    fn test_cip68_nft_name() {
+        let asset = AssetId::new(
+            "1e349c9bdea19fd6c147626a5260bc44b71635f398b67c59881df209",
+            "000de14048656c6c6f",
+        )
+        .unwrap();
+
+        assert_eq!(asset.label(), Some(Cip67Label::Nft));
+        assert_eq!(asset.name_without_label(), b"Hello");
+        assert_eq!(asset.display_name(), "Hello");
+        assert_eq!(asset.asset_name_utf8(), None);
+    }
+
+    #[test]
+    fn test_binary_name_display() {
+        let asset = AssetId::new(
+            "1e349c9bdea19fd6c147626a5260bc44b71635f398b67c59881df209",
+            "00ff01",
+        )
+        .unwrap();
+
+        assert_eq!(asset.asset_name_utf8(), None);
+        assert_eq!(asset.display_name(), "00ff01");
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{BlockfrostAPI, BlockfrostResult, Pagination};
+use blockfrost_openapi::models::{
+    address_utxo_content_inner::AddressUtxoContentInner, asset::Asset, tx_content::TxContent,
+};
+use futures::{stream, StreamExt};
+use std::{collections::HashMap, future::Future};
+
+/// Results of a batch request, keyed by input.
+///
+/// Each item succeeds or fails on its own, so a missing transaction does not hide the others.
+pub type BatchResults<T> = HashMap<String, BlockfrostResult<T>>;
+
+impl BlockfrostAPI {
+    /// Run `fetch` for every distinct key, with at most
+    /// [`batch_concurrency`](crate::BlockFrostSettings::batch_concurrency) requests at a time.
+    ///
+    /// Requests go through the same client, so they are retried with the same
+    /// [`RetrySettings`](crate::RetrySettings) when the rate limit is reached.
+    ///
+    /// ```no_run
+    /// # async fn example(api: blockfrost::BlockfrostAPI) {
+    /// let hashes = ["1e043f100dce12d107f679685acd2fc0610e10f72a92d412794c9773d11d8477"];
+    /// let utxos = api
+    ///     .batch(hashes, |hash| {
+    ///         let api = &api;
+    ///         async move { api.transactions_utxos(&hash).await }
+    ///     })
+    ///     .await;
+    /// # }
+    /// ```
+    pub async fn batch<K, T, F, Fut>(
+        &self, keys: impl IntoIterator<Item = K>, fetch: F,
+    ) -> BatchResults<T>
+    where
+        K: Into<String>,
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = BlockfrostResult<T>>,
+    {
+        let mut keys: Vec<String> = keys.into_iter().map(Into::into).collect();
+
+        keys.sort_unstable();
+        keys.dedup();
+
+        stream::iter(keys)
+            .map(|key| {
+                let result = fetch(key.clone());
+
+                async move { (key, result.await) }
+            })
+            .buffer_unordered(self.batch_concurrency())
+            .collect()
+            .await
+    }
+
+    /// Return the content of several transactions, see [`batch`](Self::batch).
+    pub async fn transactions_by_hashes(
+        &self, hashes: impl IntoIterator<Item = impl Into<String>>,
+    ) -> BatchResults<TxContent> {
+        self.batch(hashes, |hash| async move {
+            self.transaction_by_hash(&hash).await
+        })
+        .await
+    }
+
+    /// Return the information of several assets, see [`batch`](Self::batch).
+    pub async fn assets_by_ids(
+        &self, units: impl IntoIterator<Item = impl Into<String>>,
+    ) -> BatchResults<Asset> {
+        self.batch(units, |unit| async move { self.assets_by_id(&unit).await })
+            .await
+    }
+
+    /// Return the UTXOs of several addresses, see [`batch`](Self::batch).
+    ///
+    /// `pagination` applies to each address.
+    pub async fn addresses_utxos_many(
+        &self, addresses: impl IntoIterator<Item = impl Into<String>>, pagination: Pagination,
+    ) -> BatchResults<Vec<AddressUtxoContentInner>> {
+        self.batch(addresses, |address| async move {
+            self.addresses_utxos(&address, pagination).await
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlockFrostSettings, BlockfrostError};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[tokio::test]
+    async fn test_batch() {
+        let mut settings = BlockFrostSettings::new();
+        settings.batch_concurrency = 2;
+
+        let api = BlockfrostAPI::new("mainnetxxxxxxxxxxxxxxxxxxxxxxxxxxx", settings);
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let results = api
+            .batch(["a", "b", "missing", "c", "a"], |key| {
+                let running = Arc::clone(&running);
+                let peak = Arc::clone(&peak);
+
+                async move {
+                    peak.fetch_max(running.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+                    tokio::task::yield_now().await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+
+                    match key.as_str() {
+                        "missing" => Err(BlockfrostError::Parsing {
+                            message: "not found".to_string(),
+                        }),
+                        _ => Ok(key.to_uppercase()),
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results["a"].as_deref().unwrap(), "A");
+        assert_eq!(results["c"].as_deref().unwrap(), "C");
+        assert!(results["missing"].is_err());
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+}
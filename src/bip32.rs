@@ -0,0 +1,300 @@
+use crate::{utils::blake2b_224, AddressRole, BlockfrostError, BlockfrostResult};
+use bech32::{Bech32, Hrp};
+use curve25519_dalek::{edwards::CompressedEdwardsY, EdwardsPoint, Scalar};
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use std::{fmt, str::FromStr};
+
+/// First hardened derivation index, which can't be derived from a public key.
+pub const HARDENED_INDEX: u32 = 1 << 31;
+
+/// Network an address belongs to, encoded in its header and prefix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AddressNetwork {
+    Mainnet,
+    /// Any test network, such as preprod or preview.
+    Testnet,
+}
+
+impl AddressNetwork {
+    /// Network ID, in the low bits of the address header.
+    pub fn id(self) -> u8 {
+        match self {
+            AddressNetwork::Mainnet => 1,
+            AddressNetwork::Testnet => 0,
+        }
+    }
+
+    /// Human-readable prefix of bech32 addresses.
+    pub fn address_prefix(self) -> &'static str {
+        match self {
+            AddressNetwork::Mainnet => "addr",
+            AddressNetwork::Testnet => "addr_test",
+        }
+    }
+}
+
+/// An extended Ed25519 public key, for BIP32-Ed25519 public derivation.
+///
+/// Derives the addresses of a [CIP-1852] account offline, with the same results as
+/// [`derive_address`](crate::BlockfrostAPI::derive_address), from the account public key
+/// (path `m/1852'/1815'/account'`). Parsed from the hex used by the API, or from bech32 such as
+/// `acct_xvk1…`.
+///
+/// [CIP-1852]: https://cips.cardano.org/cip/CIP-1852
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Bip32PublicKey {
+    public_key: [u8; 32],
+    chain_code: [u8; 32],
+}
+
+impl Bip32PublicKey {
+    /// Create a key from its 64 bytes, the public key followed by the chain code.
+    pub fn from_bytes(bytes: &[u8]) -> BlockfrostResult<Self> {
+        let bytes: &[u8; 64] = bytes.try_into().map_err(|_| BlockfrostError::Parsing {
+            message: format!(
+                "extended public key must be 64 bytes long, got {}",
+                bytes.len()
+            ),
+        })?;
+        let (public_key, chain_code) = bytes.split_at(32);
+
+        Ok(Self {
+            // Safety: both halves of 64 bytes are 32 bytes long.
+            public_key: public_key.try_into().unwrap(),
+            chain_code: chain_code.try_into().unwrap(),
+        })
+    }
+
+    pub fn public_key(&self) -> &[u8; 32] {
+        &self.public_key
+    }
+
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+
+    /// The 64 bytes of the key, public key then chain code.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0; 64];
+
+        bytes[..32].copy_from_slice(&self.public_key);
+        bytes[32..].copy_from_slice(&self.chain_code);
+        bytes
+    }
+
+    /// Hash of the public key, which identifies it in addresses.
+    pub fn key_hash(&self) -> [u8; 28] {
+        blake2b_224(&self.public_key)
+    }
+
+    /// Derive the child key at a soft `index`.
+    ///
+    /// Fails for hardened indexes, from [`HARDENED_INDEX`], which need the private key.
+    pub fn derive(&self, index: u32) -> BlockfrostResult<Self> {
+        if index >= HARDENED_INDEX {
+            return Err(BlockfrostError::Parsing {
+                message: format!("can't derive hardened index {index} from a public key"),
+            });
+        }
+
+        let z = self.hmac(0x02, index);
+        let i = self.hmac(0x03, index);
+
+        // The left 28 bytes of Z, multiplied by 8.
+        let mut scalar = [0; 32];
+        let mut carry = 0;
+        for (byte, z_byte) in scalar.iter_mut().zip(&z[..28]) {
+            *byte = (z_byte << 3) | carry;
+            carry = z_byte >> 5;
+        }
+        scalar[28] = carry;
+
+        let point = CompressedEdwardsY(self.public_key)
+            .decompress()
+            .ok_or_else(|| BlockfrostError::Parsing {
+                message: "extended public key is not a valid Ed25519 point".to_string(),
+            })?;
+        let child = point + EdwardsPoint::mul_base(&Scalar::from_bytes_mod_order(scalar));
+
+        Ok(Self {
+            public_key: child.compress().to_bytes(),
+            // Safety: the right half of a SHA-512 HMAC is 32 bytes long.
+            chain_code: i[32..].try_into().unwrap(),
+        })
+    }
+
+    /// Derive a path of soft indexes, such as `[0, 5]`.
+    pub fn derive_path(&self, path: &[u32]) -> BlockfrostResult<Self> {
+        path.iter()
+            .try_fold(self.clone(), |key, &index| key.derive(index))
+    }
+
+    /// Base address of an account key, with the payment key at `role/index` and the stake key
+    /// at `2/0`, like [`derive_address`](crate::BlockfrostAPI::derive_address) returns.
+    pub fn base_address(
+        &self, role: AddressRole, index: u32, network: AddressNetwork,
+    ) -> BlockfrostResult<String> {
+        let payment = self.derive_path(&[role.index(), index])?;
+        let stake = self.derive_path(&[2, 0])?;
+
+        Ok(base_address(
+            &payment.key_hash(),
+            &stake.key_hash(),
+            network,
+        ))
+    }
+
+    fn hmac(&self, tag: u8, index: u32) -> [u8; 64] {
+        // Safety: HMAC accepts keys of any length.
+        let mut mac = Hmac::<Sha512>::new_from_slice(&self.chain_code).unwrap();
+
+        mac.update(&[tag]);
+        mac.update(&self.public_key);
+        mac.update(&index.to_le_bytes());
+        mac.finalize().into_bytes().into()
+    }
+}
+
+/// Parse a key from 128 hex characters, or from bech32 with any prefix.
+impl FromStr for Bip32PublicKey {
+    type Err = BlockfrostError;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        let bytes = match hex::decode(key) {
+            Ok(bytes) => bytes,
+            Err(_) => {
+                bech32::decode(key)
+                    .map_err(|reason| BlockfrostError::Parsing {
+                        message: format!("invalid extended public key '{key}': {reason}"),
+                    })?
+                    .1
+            }
+        };
+
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Formats the key as hex, as used by the API.
+impl fmt::Display for Bip32PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl fmt::Debug for Bip32PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Bip32PublicKey({self})")
+    }
+}
+
+/// Bech32 base address from the hashes of its payment and stake keys.
+pub fn base_address(
+    payment_key_hash: &[u8; 28], stake_key_hash: &[u8; 28], network: AddressNetwork,
+) -> String {
+    // Header type 0 is a base address with key hashes for both parts.
+    let mut bytes = vec![network.id()];
+
+    bytes.extend(payment_key_hash);
+    bytes.extend(stake_key_hash);
+
+    // Safety: the prefixes are valid constants, and the length is far below the limit.
+    bech32::encode::<Bech32>(Hrp::parse_unchecked(network.address_prefix()), &bytes).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockfrost_openapi::models::utils_addresses_xpub::UtilsAddressesXpub;
+    use rstest::rstest;
+    use serde_json::json;
+
+    // Account 0' and 1' of the CIP-19 test mnemonic, "test walk nut penalty hip pave soap entry
+    // language right filter choice".
+    const ACCOUNT_0: &str = "cf779aa32f35083707808532471cb64ee41426c9bbd46134dac2ac5b2a0ec0e98fa5fcd46abd9d46d4d8a97a8f3465e2c4e8f3c9dad9ff66823a161ecadca604";
+    const ACCOUNT_1: &str = "4f54b9d1205750a67ed0d3ed031e6028c927aff40197a855d6e2ee63c654c0f546f882953b51c3ba2f84aa56823aef508d3fd75b63030e849c98fbf239c93a2b";
+
+    fn account(key: &str) -> Bip32PublicKey {
+        key.parse().unwrap()
+    }
+
+    #[test]
+    fn test_derive() {
+        let key = account(ACCOUNT_0).derive(0).unwrap();
+
+        assert_eq!(
+            key.to_string(),
+            "51b1648f4ab0e87354ec563e10ea04d753120f6526095b509823df897d27f9c55e4d2767f0ac40f22a79502b0c174b8be73330b278ec52b056a08d4631ae4be3"
+        );
+        assert!(account(ACCOUNT_0).derive(HARDENED_INDEX).is_err());
+    }
+
+    #[test]
+    fn test_cip19_keys() {
+        // addr_vk1w0l2sr2zgfm26ztc6nl9xy8ghsk5sh6ldwemlpmp9xylzy4dtf7st80zhd
+        let payment = account(ACCOUNT_0).derive_path(&[0, 0]).unwrap();
+        // stake_vk1px4j0r2fk7ux5p23shz8f3y5y2qam7s954rgf3lg5merqcj6aetsft99wu
+        let stake = account(ACCOUNT_1).derive_path(&[2, 0]).unwrap();
+
+        assert_eq!(
+            hex::encode(payment.public_key()),
+            "73fea80d424276ad0978d4fe5310e8bc2d485f5f6bb3bf87612989f112ad5a7d"
+        );
+        assert_eq!(
+            hex::encode(stake.public_key()),
+            "09ab278d49b7b86a055185c474c4942281ddfa05a54684c7e8a6f230625aee57"
+        );
+        assert_eq!(
+            base_address(&payment.key_hash(), &stake.key_hash(), AddressNetwork::Mainnet),
+            "addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgse35a3x"
+        );
+        assert_eq!(
+            base_address(&payment.key_hash(), &stake.key_hash(), AddressNetwork::Testnet),
+            "addr_test1qz2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgs68faae"
+        );
+    }
+
+    #[rstest]
+    #[case(
+        AddressNetwork::Mainnet,
+        json!({
+            "xpub": ACCOUNT_0,
+            "role": 0,
+            "index": 0,
+            "address": "addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3jcu5d8ps7zex2k2xt3uqxgjqnnj83ws8lhrn648jjxtwqfjkjv7"
+        })
+    )]
+    #[case(
+        AddressNetwork::Testnet,
+        json!({
+            "xpub": ACCOUNT_0,
+            "role": 1,
+            "index": 3,
+            "address": "addr_test1qpmlksjdvcazqygmwa39zqnh4e4qgtqym042j5hhjm7klp3jcu5d8ps7zex2k2xt3uqxgjqnnj83ws8lhrn648jjxtwqdf8zl8"
+        })
+    )]
+    fn test_base_address(#[case] network: AddressNetwork, #[case] json_value: serde_json::Value) {
+        let derived = serde_json::from_value::<UtilsAddressesXpub>(json_value).unwrap();
+        let role = match derived.role {
+            0 => AddressRole::External,
+            _ => AddressRole::Internal,
+        };
+
+        assert_eq!(
+            account(&derived.xpub)
+                .base_address(role, derived.index as u32, network)
+                .unwrap(),
+            derived.address
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let bech32 = "acct_xvk1eame4ge0x5yrwpuqs5eyw89kfmjpgfkfh02xzdx6c2k9k2swcr5clf0u634tm82x6nv2j750x3j7938g70ya4k0lv6pr59s7etw2vpqgfmule";
+
+        assert_eq!(account(bech32), account(ACCOUNT_0));
+        assert!(ACCOUNT_0[..64].parse::<Bip32PublicKey>().is_err());
+        assert!("acct_xvk1eame".parse::<Bip32PublicKey>().is_err());
+    }
+}
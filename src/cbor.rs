@@ -0,0 +1,687 @@
+// Minimal CBOR (RFC 8949) decoder and encoder, covering what is needed to read ledger data.
+//
+// Decoding is done in place over a byte slice, so the raw bytes of any item can be recovered,
+// which is required to recompute hashes of transaction bodies exactly as they were signed.
+// Without the `cbor` feature, only the encoder is used, to hash native scripts.
+#![cfg_attr(not(feature = "cbor"), allow(dead_code))]
+
+use crate::{BlockfrostError, BlockfrostResult};
+
+pub(crate) const MAJOR_UNSIGNED: u8 = 0;
+pub(crate) const MAJOR_NEGATIVE: u8 = 1;
+pub(crate) const MAJOR_BYTES: u8 = 2;
+pub(crate) const MAJOR_TEXT: u8 = 3;
+pub(crate) const MAJOR_ARRAY: u8 = 4;
+pub(crate) const MAJOR_MAP: u8 = 5;
+pub(crate) const MAJOR_TAG: u8 = 6;
+pub(crate) const MAJOR_SIMPLE: u8 = 7;
+
+const INDEFINITE: u8 = 31;
+const BREAK: u8 = 0xff;
+const FALSE: u8 = 0xf4;
+const TRUE: u8 = 0xf5;
+const NULL: u8 = 0xf6;
+const UNDEFINED: u8 = 0xf7;
+
+// Tag wrapping sets since Conway, which are otherwise plain arrays.
+pub(crate) const TAG_SET: u64 = 258;
+
+// Deepest nesting of arrays, maps and tags accepted, so that malicious input can't overflow the
+// stack of the recursive decoders.
+const MAX_DEPTH: usize = 256;
+
+#[derive(Clone)]
+pub(crate) struct Decoder<'a> {
+    data: &'a [u8],
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            depth: 0,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    pub(crate) fn error(&self, message: impl std::fmt::Display) -> BlockfrostError {
+        BlockfrostError::Cbor {
+            message: format!("{message} at byte {}", self.position),
+        }
+    }
+
+    // Major type of the next item.
+    pub(crate) fn peek_major(&self) -> BlockfrostResult<u8> {
+        self.peek_byte().map(|byte| byte >> 5)
+    }
+
+    pub(crate) fn peek_byte(&self) -> BlockfrostResult<u8> {
+        self.data
+            .get(self.position)
+            .copied()
+            .ok_or_else(|| self.error("unexpected end of input"))
+    }
+
+    fn read_byte(&mut self) -> BlockfrostResult<u8> {
+        let byte = self.peek_byte()?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn read_slice(&mut self, length: u64) -> BlockfrostResult<&'a [u8]> {
+        let end = usize::try_from(length)
+            .ok()
+            .and_then(|length| self.position.checked_add(length))
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| self.error(format!("item of {length} bytes overflows the input")))?;
+        let slice = &self.data[self.position..end];
+
+        self.position = end;
+        Ok(slice)
+    }
+
+    // Read an item header, returning its major type and argument. The argument is `None` for
+    // indefinite-length items.
+    fn header(&mut self) -> BlockfrostResult<(u8, Option<u64>)> {
+        let byte = self.read_byte()?;
+        let (major, info) = (byte >> 5, byte & 0x1f);
+
+        let argument = match info {
+            0..=23 => u64::from(info),
+            24 => u64::from(self.read_byte()?),
+            25 => u16::from_be_bytes(self.read_array()?).into(),
+            26 => u32::from_be_bytes(self.read_array()?).into(),
+            27 => u64::from_be_bytes(self.read_array()?),
+            INDEFINITE if (MAJOR_BYTES..=MAJOR_MAP).contains(&major) => return Ok((major, None)),
+            // Breaks are consumed by the indefinite-length items they close.
+            INDEFINITE if major == MAJOR_SIMPLE => {
+                self.position -= 1;
+                return Err(self.error("unexpected break"));
+            }
+            _ => return Err(self.error(format!("invalid additional information {info}"))),
+        };
+
+        Ok((major, Some(argument)))
+    }
+
+    fn read_array<const N: usize>(&mut self) -> BlockfrostResult<[u8; N]> {
+        // Safety: read_slice returns exactly N bytes.
+        Ok(self.read_slice(N as u64)?.try_into().unwrap())
+    }
+
+    // Run `decode` one level deeper, failing beyond `MAX_DEPTH`.
+    fn nested<T>(
+        &mut self, decode: impl FnOnce(&mut Self) -> BlockfrostResult<T>,
+    ) -> BlockfrostResult<T> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error(format!("items nested more than {MAX_DEPTH} levels deep")));
+        }
+
+        self.depth += 1;
+        let result = decode(self);
+        self.depth -= 1;
+
+        result
+    }
+
+    // Chunks of indefinite-length strings, which must have a definite length themselves.
+    fn chunks(&mut self, major: u8, what: &str) -> BlockfrostResult<Vec<u8>> {
+        let mut bytes = Vec::new();
+
+        while !self.is_break()? {
+            match self.expect_header(major, what)? {
+                Some(length) => bytes.extend(self.read_slice(length)?),
+                None => return Err(self.error(format!("nested indefinite-length {what}"))),
+            }
+        }
+        self.position += 1;
+
+        Ok(bytes)
+    }
+
+    fn expect_header(&mut self, expected: u8, what: &str) -> BlockfrostResult<Option<u64>> {
+        let start = self.position;
+        let (major, argument) = self.header()?;
+
+        if major != expected {
+            self.position = start;
+            return Err(self.error(format!("expected {what}, found major type {major}")));
+        }
+
+        Ok(argument)
+    }
+
+    pub(crate) fn u64(&mut self) -> BlockfrostResult<u64> {
+        self.expect_header(MAJOR_UNSIGNED, "unsigned integer")
+            .map(|argument| argument.unwrap_or_default())
+    }
+
+    pub(crate) fn u32(&mut self) -> BlockfrostResult<u32> {
+        let value = self.u64()?;
+
+        u32::try_from(value).map_err(|_| self.error(format!("integer {value} is too large")))
+    }
+
+    // Signed integer, including bignums (tags 2 and 3) that fit in an i128.
+    pub(crate) fn int(&mut self) -> BlockfrostResult<i128> {
+        match self.peek_major()? {
+            MAJOR_UNSIGNED => Ok(self.u64()?.into()),
+            MAJOR_NEGATIVE => {
+                let argument = self.header()?.1.unwrap_or_default();
+                Ok(-1 - i128::from(argument))
+            }
+            MAJOR_TAG => {
+                let start = self.position;
+                let tag = self.tag()?;
+                let bytes = self.bytes()?;
+
+                if !matches!(tag, 2 | 3) {
+                    self.position = start;
+                    return Err(self.error("expected integer"));
+                }
+
+                let significant = bytes.iter().skip_while(|byte| **byte == 0).count();
+                let value = bytes
+                    .iter()
+                    .fold(0u128, |value, byte| (value << 8) | u128::from(*byte));
+
+                // Negative bignums encode `-1 - value`, so both signs allow up to `i128::MAX`.
+                match i128::try_from(value) {
+                    Ok(value) if significant <= 16 => Ok(if tag == 2 { value } else { -1 - value }),
+                    _ => {
+                        self.position = start;
+                        Err(self.error("integer doesn't fit in 128 bits"))
+                    }
+                }
+            }
+            major => Err(self.error(format!("expected integer, found major type {major}"))),
+        }
+    }
+
+    pub(crate) fn bytes(&mut self) -> BlockfrostResult<Vec<u8>> {
+        match self.expect_header(MAJOR_BYTES, "byte string")? {
+            Some(length) => Ok(self.read_slice(length)?.to_vec()),
+            None => self.chunks(MAJOR_BYTES, "byte string"),
+        }
+    }
+
+    // Byte string of a fixed size, such as a hash.
+    pub(crate) fn bytes_array<const N: usize>(&mut self) -> BlockfrostResult<[u8; N]> {
+        let bytes = self.bytes()?;
+        let length = bytes.len();
+
+        bytes
+            .try_into()
+            .map_err(|_| self.error(format!("expected {N} bytes, found {length}")))
+    }
+
+    pub(crate) fn hex<const N: usize>(&mut self) -> BlockfrostResult<String> {
+        self.bytes_array::<N>().map(hex::encode)
+    }
+
+    pub(crate) fn text(&mut self) -> BlockfrostResult<String> {
+        let bytes = match self.expect_header(MAJOR_TEXT, "text string")? {
+            Some(length) => self.read_slice(length)?.to_vec(),
+            None => self.chunks(MAJOR_TEXT, "text string")?,
+        };
+
+        String::from_utf8(bytes).map_err(|_| self.error("invalid UTF-8 in text string"))
+    }
+
+    // Array length, `None` if indefinite.
+    pub(crate) fn array(&mut self) -> BlockfrostResult<Option<u64>> {
+        self.expect_header(MAJOR_ARRAY, "array")
+    }
+
+    // Map length, `None` if indefinite.
+    pub(crate) fn map(&mut self) -> BlockfrostResult<Option<u64>> {
+        self.expect_header(MAJOR_MAP, "map")
+    }
+
+    pub(crate) fn tag(&mut self) -> BlockfrostResult<u64> {
+        self.expect_header(MAJOR_TAG, "tag")
+            .map(|argument| argument.unwrap_or_default())
+    }
+
+    pub(crate) fn bool(&mut self) -> BlockfrostResult<bool> {
+        match self.peek_byte()? {
+            FALSE | TRUE => Ok(self.read_byte()? == TRUE),
+            _ => Err(self.error("expected boolean")),
+        }
+    }
+
+    pub(crate) fn is_break(&self) -> BlockfrostResult<bool> {
+        self.peek_byte().map(|byte| byte == BREAK)
+    }
+
+    pub(crate) fn is_bool(&self) -> BlockfrostResult<bool> {
+        self.peek_byte().map(|byte| matches!(byte, FALSE | TRUE))
+    }
+
+    // Consume the break closing an indefinite-length item.
+    pub(crate) fn end(&mut self) -> BlockfrostResult<()> {
+        if !self.is_break()? {
+            return Err(self.error("expected break"));
+        }
+
+        self.position += 1;
+        Ok(())
+    }
+
+    // Consume a null (or undefined) item if it comes next.
+    pub(crate) fn null(&mut self) -> BlockfrostResult<bool> {
+        let is_null = matches!(self.peek_byte()?, NULL | UNDEFINED);
+
+        if is_null {
+            self.position += 1;
+        }
+        Ok(is_null)
+    }
+
+    // Decode an item, or return `None` if it is null.
+    pub(crate) fn nullable<T>(
+        &mut self, decode: impl FnOnce(&mut Self) -> BlockfrostResult<T>,
+    ) -> BlockfrostResult<Option<T>> {
+        if self.null()? {
+            Ok(None)
+        } else {
+            decode(self).map(Some)
+        }
+    }
+
+    // Decode the items of an array, definite or not.
+    pub(crate) fn array_of<T>(
+        &mut self, mut decode: impl FnMut(&mut Self) -> BlockfrostResult<T>,
+    ) -> BlockfrostResult<Vec<T>> {
+        let length = self.array()?;
+        self.items(length, |decoder| decode(decoder))
+    }
+
+    // Decode the items of a set, which is an array optionally tagged with 258.
+    pub(crate) fn set_of<T>(
+        &mut self, decode: impl FnMut(&mut Self) -> BlockfrostResult<T>,
+    ) -> BlockfrostResult<Vec<T>> {
+        if self.peek_major()? == MAJOR_TAG {
+            let tag = self.tag()?;
+
+            if tag != TAG_SET {
+                return Err(self.error(format!("expected set, found tag {tag}")));
+            }
+        }
+
+        self.array_of(decode)
+    }
+
+    // Decode the entries of a map, definite or not.
+    pub(crate) fn map_of<K, V>(
+        &mut self, mut decode_key: impl FnMut(&mut Self) -> BlockfrostResult<K>,
+        mut decode_value: impl FnMut(&mut Self) -> BlockfrostResult<V>,
+    ) -> BlockfrostResult<Vec<(K, V)>> {
+        let length = self.map()?;
+
+        self.items(length, |decoder| {
+            let key = decode_key(decoder)?;
+            let value = decode_value(decoder)?;
+            Ok((key, value))
+        })
+    }
+
+    // Decode an array of a fixed shape with `decode`, checking that it reads all the items of a
+    // definite array, and consuming the break of an indefinite one.
+    pub(crate) fn tuple<T>(
+        &mut self, decode: impl FnOnce(&mut Self) -> BlockfrostResult<T>,
+    ) -> BlockfrostResult<T> {
+        let length = self.array()?;
+        let start = self.position;
+        let value = self.nested(decode)?;
+
+        match length {
+            Some(length) => {
+                let mut items = Decoder {
+                    position: start,
+                    ..self.clone()
+                };
+
+                for _ in 0..length {
+                    items.skip()?;
+                }
+
+                if items.position != self.position {
+                    return Err(self.error(format!("expected an array of {length} items")));
+                }
+            }
+            None => self.end()?,
+        }
+
+        Ok(value)
+    }
+
+    fn items<T>(
+        &mut self, length: Option<u64>, mut decode: impl FnMut(&mut Self) -> BlockfrostResult<T>,
+    ) -> BlockfrostResult<Vec<T>> {
+        self.nested(|decoder| {
+            let mut items = Vec::new();
+
+            match length {
+                Some(length) => {
+                    for _ in 0..length {
+                        items.push(decode(decoder)?);
+                    }
+                }
+                None => {
+                    while !decoder.is_break()? {
+                        items.push(decode(decoder)?);
+                    }
+                    decoder.position += 1;
+                }
+            }
+
+            Ok(items)
+        })
+    }
+
+    // Skip the next item, whatever its type.
+    pub(crate) fn skip(&mut self) -> BlockfrostResult<()> {
+        let (major, argument) = self.header()?;
+
+        match (major, argument) {
+            (MAJOR_UNSIGNED | MAJOR_NEGATIVE | MAJOR_SIMPLE, _) => {}
+            (MAJOR_BYTES | MAJOR_TEXT, Some(length)) => {
+                self.read_slice(length)?;
+            }
+            (MAJOR_BYTES | MAJOR_TEXT, None) => {
+                self.chunks(major, "string")?;
+            }
+            (MAJOR_ARRAY, Some(length)) => {
+                self.nested(|decoder| (0..length).try_for_each(|_| decoder.skip()))?
+            }
+            (MAJOR_MAP, Some(length)) => self
+                .nested(|decoder| (0..length.saturating_mul(2)).try_for_each(|_| decoder.skip()))?,
+            (MAJOR_TAG, _) => self.nested(Self::skip)?,
+            (_, None) => self.nested(|decoder| {
+                while !decoder.is_break()? {
+                    decoder.skip()?;
+                }
+                decoder.position += 1;
+                Ok(())
+            })?,
+            _ => unreachable!("major types are 3 bits long"),
+        }
+
+        Ok(())
+    }
+
+    // Skip the next item and return its raw bytes.
+    pub(crate) fn raw(&mut self) -> BlockfrostResult<&'a [u8]> {
+        let start = self.position;
+        self.skip()?;
+        Ok(&self.data[start..self.position])
+    }
+
+    // Raw bytes of the content of a CBOR-in-CBOR item (tag 24).
+    pub(crate) fn embedded_cbor(&mut self) -> BlockfrostResult<Vec<u8>> {
+        let tag = self.tag()?;
+
+        if tag != 24 {
+            return Err(self.error(format!("expected embedded CBOR, found tag {tag}")));
+        }
+
+        self.bytes()
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Encoder {
+    data: Vec<u8>,
+}
+
+impl Encoder {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+
+    // Write an item header, with the shortest encoding of its argument.
+    fn header(&mut self, major: u8, argument: u64) -> &mut Self {
+        let major = major << 5;
+
+        match argument {
+            0..=23 => self.data.push(major | argument as u8),
+            24..=0xff => self.data.extend([major | 24, argument as u8]),
+            0x100..=0xffff => {
+                self.data.push(major | 25);
+                self.data.extend((argument as u16).to_be_bytes());
+            }
+            0x1_0000..=0xffff_ffff => {
+                self.data.push(major | 26);
+                self.data.extend((argument as u32).to_be_bytes());
+            }
+            _ => {
+                self.data.push(major | 27);
+                self.data.extend(argument.to_be_bytes());
+            }
+        }
+        self
+    }
+
+    pub(crate) fn u64(&mut self, value: u64) -> &mut Self {
+        self.header(MAJOR_UNSIGNED, value)
+    }
+
+    // Signed integer, as a bignum (tags 2 and 3) if it doesn't fit in 64 bits.
+    pub(crate) fn int(&mut self, value: i128) -> &mut Self {
+        let (major, argument) = if value < 0 {
+            (MAJOR_NEGATIVE, -1 - value)
+        } else {
+            (MAJOR_UNSIGNED, value)
+        };
+
+        match u64::try_from(argument) {
+            Ok(argument) => self.header(major, argument),
+            Err(_) => {
+                let bytes = argument.to_be_bytes();
+                let start = bytes.iter().position(|byte| *byte != 0).unwrap_or_default();
+
+                self.tag(if major == MAJOR_UNSIGNED { 2 } else { 3 })
+                    .bytes(&bytes[start..])
+            }
+        }
+    }
+
+    pub(crate) fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.header(MAJOR_BYTES, bytes.len() as u64);
+        self.data.extend(bytes);
+        self
+    }
+
+    pub(crate) fn array(&mut self, length: usize) -> &mut Self {
+        self.header(MAJOR_ARRAY, length as u64)
+    }
+
+    pub(crate) fn map(&mut self, length: usize) -> &mut Self {
+        self.header(MAJOR_MAP, length as u64)
+    }
+
+    pub(crate) fn tag(&mut self, tag: u64) -> &mut Self {
+        self.header(MAJOR_TAG, tag)
+    }
+
+    // Start an indefinite-length item, to be closed with `end`.
+    pub(crate) fn begin_indefinite(&mut self, major: u8) -> &mut Self {
+        self.data.push((major << 5) | INDEFINITE);
+        self
+    }
+
+    pub(crate) fn end(&mut self) -> &mut Self {
+        self.data.push(BREAK);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_hex(data: &str) -> Vec<u8> {
+        hex::decode(data).unwrap()
+    }
+
+    #[test]
+    fn test_integers() {
+        let data =
+            from_hex("001718181901001a000f42401b000000e8d4a51000203903e7c249010000000000000000");
+        let mut decoder = Decoder::new(&data);
+
+        assert_eq!(decoder.u64().unwrap(), 0);
+        assert_eq!(decoder.u64().unwrap(), 23);
+        assert_eq!(decoder.u64().unwrap(), 24);
+        assert_eq!(decoder.u64().unwrap(), 256);
+        assert_eq!(decoder.u64().unwrap(), 1000000);
+        assert_eq!(decoder.u64().unwrap(), 1000000000000);
+        assert_eq!(decoder.int().unwrap(), -1);
+        assert_eq!(decoder.int().unwrap(), -1000);
+        assert_eq!(decoder.int().unwrap(), 18446744073709551616);
+        assert!(decoder.is_empty());
+
+        // Bignums up to 128 bits, leading zeros included
+        let max = format!("c250{}", "7f".to_string() + &"ff".repeat(15));
+        let min = format!("c350{}", "7f".to_string() + &"ff".repeat(15));
+        let padded = format!("c251{}", "00".repeat(16) + "01");
+
+        assert_eq!(Decoder::new(&from_hex(&max)).int().unwrap(), i128::MAX);
+        assert_eq!(Decoder::new(&from_hex(&min)).int().unwrap(), i128::MIN);
+        assert_eq!(Decoder::new(&from_hex(&padded)).int().unwrap(), 1);
+        assert!(Decoder::new(&from_hex(&format!(
+            "c250{}",
+            "80".to_string() + &"00".repeat(15)
+        )))
+        .int()
+        .is_err());
+        assert!(Decoder::new(&from_hex(&format!("c251{}", "01".repeat(17))))
+            .int()
+            .is_err());
+    }
+
+    #[test]
+    fn test_strings() {
+        let data = from_hex("4401020304636162635f4201024103ff7f616163626263ff");
+        let mut decoder = Decoder::new(&data);
+
+        assert_eq!(decoder.bytes().unwrap(), vec![1, 2, 3, 4]);
+        assert_eq!(decoder.text().unwrap(), "abc");
+        assert_eq!(decoder.bytes().unwrap(), vec![1, 2, 3]);
+        assert_eq!(decoder.text().unwrap(), "abbc");
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn test_collections() {
+        // [_ 4, 5], {1: 2}, 258([6]), null
+        let data = from_hex("9f0405ffa10102d901028106f6");
+        let mut decoder = Decoder::new(&data);
+
+        assert_eq!(decoder.array_of(Decoder::u64).unwrap(), vec![4, 5]);
+        assert_eq!(
+            decoder.map_of(Decoder::u64, Decoder::u64).unwrap(),
+            vec![(1, 2)]
+        );
+        assert_eq!(decoder.set_of(Decoder::u64).unwrap(), vec![6]);
+        assert_eq!(decoder.nullable(Decoder::u64).unwrap(), None);
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn test_raw() {
+        // [1, [2, 3]], 0
+        let data = from_hex("820182020300");
+        let mut decoder = Decoder::new(&data);
+
+        assert_eq!(decoder.raw().unwrap(), &data[..5]);
+        assert_eq!(decoder.u64().unwrap(), 0);
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn test_skip() {
+        // {"a": [1, h'ff', 24(h'00')], "b": [_ true, -2]}
+        let data = from_hex("a26161830141ffd818410061629ff521ff");
+        let mut decoder = Decoder::new(&data);
+
+        decoder.skip().unwrap();
+        assert!(decoder.is_empty());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(Decoder::new(&[]).u64().is_err());
+        assert!(Decoder::new(&[0x61]).u64().is_err());
+        assert!(Decoder::new(&[0x44, 0x01]).bytes().is_err());
+        assert!(Decoder::new(&[0x1c]).skip().is_err());
+        assert!(Decoder::new(&[0x62, 0xff, 0xfe]).text().is_err());
+        // Stray break, and indefinite-length chunk of an indefinite-length string
+        assert!(Decoder::new(&[0xff]).skip().is_err());
+        assert!(Decoder::new(&[0x82, 0x01, 0xff]).skip().is_err());
+        assert!(Decoder::new(&[0x5f, 0x5f, 0xff, 0xff]).bytes().is_err());
+    }
+
+    #[test]
+    fn test_depth() {
+        fn nested_array(decoder: &mut Decoder) -> BlockfrostResult<usize> {
+            match decoder.peek_major()? {
+                MAJOR_ARRAY => Ok(decoder.array_of(nested_array)?.len()),
+                _ => decoder.u64().map(|_| 0),
+            }
+        }
+
+        // [[[...[0]...]]] nested as deep as allowed, then one level deeper
+        let mut data = vec![0x81; MAX_DEPTH];
+        data.push(0x00);
+
+        assert!(Decoder::new(&data).skip().is_ok());
+        assert!(nested_array(&mut Decoder::new(&data)).is_ok());
+
+        data.insert(0, 0x81);
+
+        assert!(Decoder::new(&data).skip().is_err());
+        assert!(nested_array(&mut Decoder::new(&data)).is_err());
+
+        let data = vec![0x81; 100_000];
+
+        assert!(Decoder::new(&data).skip().is_err());
+        assert!(nested_array(&mut Decoder::new(&data)).is_err());
+        assert!(Decoder::new(&vec![0xc1; 100_000]).skip().is_err());
+    }
+
+    #[test]
+    fn test_encoder() {
+        let mut encoder = Encoder::new();
+
+        encoder
+            .u64(23)
+            .u64(1000)
+            .int(-1000)
+            .int(18446744073709551616)
+            .int(-18446744073709551617)
+            .bytes(&[1, 2])
+            .array(2)
+            .map(0)
+            .tag(121)
+            .begin_indefinite(MAJOR_ARRAY)
+            .end();
+
+        assert_eq!(
+            hex::encode(encoder.into_bytes()),
+            "171903e83903e7c249010000000000000000c349010000000000000000420102\
+             82a0d8799fff"
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use crate::{BlockfrostError, BlockfrostResult};
+use blockfrost_openapi::models::{
+    genesis_content::GenesisContent, network_eras_inner::NetworkErasInner,
+};
+
+/// Converts between slots, epochs and wall-clock time for a specific network.
+///
+/// Built from [`genesis`](crate::BlockfrostAPI::genesis) and
+/// [`network_eras`](crate::BlockfrostAPI::network_eras), usually through
+/// [`BlockfrostAPI::chain_time`](crate::BlockfrostAPI::chain_time), which caches it.
+///
+/// Each era has its own slot and epoch lengths (Byron slots last 20 seconds, later eras 1
+/// second), and networks differ in their era history: mainnet and preprod start in Byron,
+/// while preview has no Byron blocks at all.
+///
+/// The last known era is assumed to last forever, so conversions past its end (the safe zone
+/// reported by the API) are extrapolations that a future hard fork could invalidate.
+///
+/// Times are Unix timestamps in seconds (UTC).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainTime {
+    system_start: i64,
+    eras: Vec<EraSummary>,
+}
+
+/// Boundaries and parameters of a single era, see [`ChainTime`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EraSummary {
+    pub start: EraBound,
+    pub end: EraBound,
+    /// Number of slots in an epoch.
+    pub epoch_length: u64,
+    /// Duration of a slot, in seconds.
+    pub slot_length: f64,
+}
+
+/// Start or end of an era.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EraBound {
+    /// Seconds elapsed since the network system start.
+    pub time: f64,
+    pub slot: u64,
+    pub epoch: u64,
+}
+
+/// An epoch number and the position of a slot inside of it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EpochSlot {
+    pub epoch: u64,
+    pub slot_in_epoch: u64,
+}
+
+/// First and last slots of an epoch, and the time range they cover.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EpochBoundary {
+    pub epoch: u64,
+    pub first_slot: u64,
+    pub last_slot: u64,
+    /// Start time of the first slot.
+    pub start_time: i64,
+    /// Start time of the first slot of the next epoch.
+    pub end_time: i64,
+}
+
+impl ChainTime {
+    /// Create a [`ChainTime`] from the network genesis and its list of eras.
+    pub fn new(genesis: &GenesisContent, eras: &[NetworkErasInner]) -> BlockfrostResult<Self> {
+        let eras: Vec<EraSummary> = eras.iter().map(EraSummary::from).collect();
+
+        if eras.is_empty() {
+            return Err(BlockfrostError::Parsing {
+                message: "network eras list is empty".to_string(),
+            });
+        }
+
+        Ok(Self {
+            system_start: genesis.system_start as i64,
+            eras,
+        })
+    }
+
+    /// Unix timestamp of the network system start (slot 0).
+    pub fn system_start(&self) -> i64 {
+        self.system_start
+    }
+
+    /// Eras of the network, in chronological order.
+    pub fn eras(&self) -> &[EraSummary] {
+        &self.eras
+    }
+
+    /// Unix timestamp at which `slot` starts.
+    pub fn slot_to_time(&self, slot: u64) -> i64 {
+        let era = self.era_by(|era| slot < era.end.slot);
+        let elapsed = era.start.time + (slot - era.start.slot) as f64 * era.slot_length;
+
+        self.system_start + elapsed.round() as i64
+    }
+
+    /// Slot in progress at the Unix timestamp `time`.
+    ///
+    /// Returns `None` if `time` is before the network system start.
+    pub fn time_to_slot(&self, time: i64) -> Option<u64> {
+        let elapsed = time.checked_sub(self.system_start).filter(|e| *e >= 0)? as f64;
+        let era = self.era_by(|era| elapsed < era.end.time);
+        let slots = ((elapsed - era.start.time) / era.slot_length).floor() as u64;
+
+        Some(era.start.slot + slots)
+    }
+
+    /// Epoch containing `slot`, and the position of `slot` in it.
+    pub fn slot_to_epoch(&self, slot: u64) -> EpochSlot {
+        let era = self.era_by(|era| slot < era.end.slot);
+        let slots = slot - era.start.slot;
+
+        EpochSlot {
+            epoch: era.start.epoch + slots / era.epoch_length,
+            slot_in_epoch: slots % era.epoch_length,
+        }
+    }
+
+    /// Absolute slot of the position `slot_in_epoch` inside of `epoch`.
+    pub fn epoch_slot_to_slot(&self, epoch_slot: EpochSlot) -> u64 {
+        self.epoch_first_slot(epoch_slot.epoch) + epoch_slot.slot_in_epoch
+    }
+
+    /// First slot of `epoch`.
+    pub fn epoch_first_slot(&self, epoch: u64) -> u64 {
+        let era = self.era_by(|era| epoch < era.end.epoch);
+
+        era.start.slot + (epoch - era.start.epoch) * era.epoch_length
+    }
+
+    /// Slot and time boundaries of `epoch`.
+    pub fn epoch_boundary(&self, epoch: u64) -> EpochBoundary {
+        let first_slot = self.epoch_first_slot(epoch);
+        let next_first_slot = self.epoch_first_slot(epoch + 1);
+
+        EpochBoundary {
+            epoch,
+            first_slot,
+            last_slot: next_first_slot - 1,
+            start_time: self.slot_to_time(first_slot),
+            end_time: self.slot_to_time(next_first_slot),
+        }
+    }
+
+    /// Epoch in progress at the Unix timestamp `time`.
+    ///
+    /// Returns `None` if `time` is before the network system start.
+    pub fn time_to_epoch(&self, time: i64) -> Option<EpochSlot> {
+        self.time_to_slot(time).map(|slot| self.slot_to_epoch(slot))
+    }
+
+    // Find the first non-empty era matching `is_before_end`, falling back to the last era.
+    fn era_by(&self, is_before_end: impl Fn(&EraSummary) -> bool) -> &EraSummary {
+        self.eras
+            .iter()
+            .filter(|era| era.start.slot < era.end.slot)
+            .find(|era| is_before_end(era))
+            .unwrap_or_else(|| self.eras.last().unwrap())
+    }
+}
+
+impl From<&NetworkErasInner> for EraSummary {
+    fn from(era: &NetworkErasInner) -> Self {
+        Self {
+            start: EraBound {
+                time: f64::from(era.start.time),
+                slot: era.start.slot as u64,
+                epoch: era.start.epoch as u64,
+            },
+            end: EraBound {
+                time: f64::from(era.end.time),
+                slot: era.end.slot as u64,
+                epoch: era.end.epoch as u64,
+            },
+            epoch_length: era.parameters.epoch_length as u64,
+            slot_length: f64::from(era.parameters.slot_length),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use serde_json::{json, Value};
+
+    fn era(start: (u64, u64, u64), end: (u64, u64, u64), epoch_length: u64, slot: u64) -> Value {
+        json!({
+            "start": { "time": start.0, "slot": start.1, "epoch": start.2 },
+            "end": { "time": end.0, "slot": end.1, "epoch": end.2 },
+            "parameters": { "epoch_length": epoch_length, "slot_length": slot, "safe_zone": 0 }
+        })
+    }
+
+    fn chain_time(system_start: i64, eras: Value) -> ChainTime {
+        let genesis = json!({
+          "active_slots_coefficient": 0.05,
+          "update_quorum": 5,
+          "max_lovelace_supply": "45000000000000000",
+          "network_magic": 764824073,
+          "epoch_length": 432000,
+          "system_start": system_start,
+          "slots_per_kes_period": 129600,
+          "slot_length": 1,
+          "max_kes_evolutions": 62,
+          "security_param": 2160
+        });
+        let genesis = serde_json::from_value::<GenesisContent>(genesis).unwrap();
+        let eras = serde_json::from_value::<Vec<NetworkErasInner>>(eras).unwrap();
+
+        ChainTime::new(&genesis, &eras).unwrap()
+    }
+
+    fn mainnet() -> ChainTime {
+        let eras = json!([
+            era((0, 0, 0), (89856000, 4492800, 208), 21600, 20),
+            era(
+                (89856000, 4492800, 208),
+                (101952000, 16588800, 236),
+                432000,
+                1
+            ),
+            era(
+                (101952000, 16588800, 236),
+                (108432000, 23068800, 251),
+                432000,
+                1
+            ),
+            era(
+                (108432000, 23068800, 251),
+                (125280000, 39916800, 290),
+                432000,
+                1
+            ),
+            era(
+                (125280000, 39916800, 290),
+                (157680000, 72316800, 365),
+                432000,
+                1
+            ),
+            era(
+                (157680000, 72316800, 365),
+                (219024000, 133660800, 507),
+                432000,
+                1
+            ),
+            era(
+                (219024000, 133660800, 507),
+                (227664000, 142300800, 527),
+                432000,
+                1
+            )
+        ]);
+
+        chain_time(1506203091, eras)
+    }
+
+    fn preprod() -> ChainTime {
+        let eras = json!([
+            era((0, 0, 0), (1728000, 86400, 4), 21600, 20),
+            era((1728000, 86400, 4), (2160000, 518400, 5), 432000, 1),
+            era((2160000, 518400, 5), (2592000, 950400, 6), 432000, 1),
+            era((2592000, 950400, 6), (3024000, 1382400, 7), 432000, 1),
+            era((3024000, 1382400, 7), (5184000, 3542400, 12), 432000, 1),
+            era((5184000, 3542400, 12), (70416000, 68774400, 163), 432000, 1),
+            era(
+                (70416000, 68774400, 163),
+                (75600000, 73958400, 175),
+                432000,
+                1
+            )
+        ]);
+
+        chain_time(1654041600, eras)
+    }
+
+    fn preview() -> ChainTime {
+        let eras = json!([
+            era((0, 0, 0), (0, 0, 0), 4320, 20),
+            era((0, 0, 0), (0, 0, 0), 86400, 1),
+            era((0, 0, 0), (0, 0, 0), 86400, 1),
+            era((0, 0, 0), (0, 0, 0), 86400, 1),
+            era((0, 0, 0), (259200, 259200, 3), 86400, 1),
+            era((259200, 259200, 3), (55814400, 55814400, 646), 86400, 1),
+            era(
+                (55814400, 55814400, 646),
+                (65664000, 65664000, 760),
+                86400,
+                1
+            )
+        ]);
+
+        chain_time(1666656000, eras)
+    }
+
+    #[rstest]
+    // Byron
+    #[case(mainnet(), 0, 1506203091)]
+    #[case(mainnet(), 4492799, 1596059071)]
+    // First Shelley slot
+    #[case(mainnet(), 4492800, 1596059091)]
+    // First Babbage slot
+    #[case(mainnet(), 72316800, 1663883091)]
+    // Past the last known era
+    #[case(mainnet(), 150000000, 1591566291 + 150000000)]
+    #[case(preprod(), 86399, 1655769580)]
+    #[case(preprod(), 86400, 1655769600)]
+    #[case(preview(), 0, 1666656000)]
+    #[case(preview(), 259200, 1666915200)]
+    fn test_slot_to_time(#[case] chain_time: ChainTime, #[case] slot: u64, #[case] time: i64) {
+        assert_eq!(chain_time.slot_to_time(slot), time);
+        assert_eq!(chain_time.time_to_slot(time), Some(slot));
+    }
+
+    #[rstest]
+    #[case(mainnet(), 1596059090, 4492799)]
+    #[case(mainnet(), 1596059081, 4492799)]
+    #[case(preprod(), 1655769599, 86399)]
+    fn test_time_to_slot_inside_byron_slot(
+        #[case] chain_time: ChainTime, #[case] time: i64, #[case] slot: u64,
+    ) {
+        assert_eq!(chain_time.time_to_slot(time), Some(slot));
+    }
+
+    #[test]
+    fn test_time_before_system_start() {
+        assert_eq!(mainnet().time_to_slot(1506203090), None);
+        assert_eq!(preview().time_to_epoch(0), None);
+    }
+
+    #[rstest]
+    #[case(mainnet(), 21599, 0, 21599)]
+    #[case(mainnet(), 4492800, 208, 0)]
+    #[case(mainnet(), 72316800 + 432000 + 7, 366, 7)]
+    #[case(mainnet(), 142300800 + 432000 * 3, 530, 0)]
+    #[case(preprod(), 86399, 3, 21599)]
+    #[case(preprod(), 86400, 4, 0)]
+    #[case(preview(), 86399, 0, 86399)]
+    #[case(preview(), 55814400 + 86401, 647, 1)]
+    fn test_slot_to_epoch(
+        #[case] chain_time: ChainTime, #[case] slot: u64, #[case] epoch: u64,
+        #[case] slot_in_epoch: u64,
+    ) {
+        let epoch_slot = EpochSlot {
+            epoch,
+            slot_in_epoch,
+        };
+
+        assert_eq!(chain_time.slot_to_epoch(slot), epoch_slot);
+        assert_eq!(chain_time.epoch_slot_to_slot(epoch_slot), slot);
+    }
+
+    #[test]
+    fn test_epoch_boundary() {
+        let boundary = mainnet().epoch_boundary(207);
+
+        assert_eq!(
+            boundary,
+            EpochBoundary {
+                epoch: 207,
+                first_slot: 4471200,
+                last_slot: 4492799,
+                start_time: 1595627091,
+                end_time: 1596059091,
+            }
+        );
+
+        let boundary = preview().epoch_boundary(0);
+
+        assert_eq!(boundary.first_slot, 0);
+        assert_eq!(boundary.last_slot, 86399);
+        assert_eq!(boundary.start_time, 1666656000);
+        assert_eq!(boundary.end_time, 1666742400);
+    }
+
+    #[test]
+    fn test_empty_eras() {
+        let genesis = GenesisContent::default();
+
+        assert!(ChainTime::new(&genesis, &[]).is_err());
+    }
+}
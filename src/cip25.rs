@@ -0,0 +1,530 @@
+use crate::{
+    AssetId, BlockfrostError, BlockfrostResult, JsonMap, JsonValue, ASSET_NAME_MAX_LENGTH,
+    POLICY_ID_LENGTH,
+};
+use blockfrost_openapi::models::asset::Asset;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Transaction metadata label of [CIP-25] NFT metadata.
+///
+/// [CIP-25]: https://cips.cardano.org/cip/CIP-0025
+pub const CIP25_LABEL: &str = "721";
+
+/// Version of the [CIP-25] standard.
+///
+/// Version 1 uses UTF-8 asset names as keys, version 2 hex-encoded ones.
+///
+/// [CIP-25]: https://cips.cardano.org/cip/CIP-0025
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Cip25Version {
+    #[default]
+    V1,
+    V2,
+}
+
+/// [CIP-25] metadata of a single NFT.
+///
+/// Built from [`assets_by_id`](crate::BlockfrostAPI::assets_by_id) with
+/// [`Cip25Metadata::from_asset`], or from label 721 metadata as returned by
+/// [`metadata_txs_by_label`](crate::BlockfrostAPI::metadata_txs_by_label) with
+/// [`Cip25Metadata::from_label_721`].
+///
+/// Parsing is lenient: strings split in chunks of 64 bytes are joined, and departures from the
+/// standard are listed in `violations` rather than failing, since many minted NFTs have some.
+///
+/// [CIP-25]: https://cips.cardano.org/cip/CIP-0025
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Cip25Metadata {
+    pub version: Cip25Version,
+    /// Required by the standard, `None` if missing or invalid.
+    pub name: Option<String>,
+    /// URI of the image, required by the standard, `None` if missing or invalid.
+    pub image: Option<String>,
+    /// Media type of `image`, an `image/*` type.
+    pub media_type: Option<String>,
+    pub description: Option<String>,
+    pub files: Vec<Cip25File>,
+    /// Any other properties, as is.
+    pub properties: JsonMap,
+    pub violations: Vec<Cip25Violation>,
+}
+
+/// A file of [`Cip25Metadata`], such as a higher resolution image or a video.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Cip25File {
+    pub name: Option<String>,
+    pub media_type: Option<String>,
+    pub src: Option<String>,
+    /// Any other properties, as is.
+    pub properties: JsonMap,
+}
+
+/// [`Cip25Metadata`] of an asset, read from label 721 metadata.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cip25Asset {
+    /// The asset, `None` if its name key is not a valid asset name, as reported in the
+    /// violations of `metadata`.
+    pub asset: Option<AssetId>,
+    /// Asset name key of the metadata, as is.
+    pub asset_name: String,
+    pub metadata: Cip25Metadata,
+}
+
+/// A departure from the [CIP-25] standard.
+///
+/// Fields are named by their path in the metadata, such as `files[0].src`.
+///
+/// [CIP-25]: https://cips.cardano.org/cip/CIP-0025
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Cip25Violation {
+    /// A required field is missing.
+    MissingField { field: String },
+    /// A field has the wrong type or an invalid value.
+    InvalidField { field: String, reason: String },
+    /// A version 2 asset name key is not hex, so the asset can't be identified.
+    InvalidAssetName { asset_name: String },
+    /// An asset name key is longer than [`ASSET_NAME_MAX_LENGTH`] bytes, so the asset can't be
+    /// identified.
+    AssetNameTooLong { asset_name: String },
+}
+
+impl fmt::Display for Cip25Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Cip25Violation::MissingField { field } => write!(f, "missing field '{field}'"),
+            Cip25Violation::InvalidField { field, reason } => {
+                write!(f, "invalid field '{field}': {reason}")
+            }
+            Cip25Violation::InvalidAssetName { asset_name } => {
+                write!(
+                    f,
+                    "asset name '{asset_name}' is not hex, as required by version 2"
+                )
+            }
+            Cip25Violation::AssetNameTooLong { asset_name } => {
+                write!(
+                    f,
+                    "asset name '{asset_name}' is longer than {ASSET_NAME_MAX_LENGTH} bytes"
+                )
+            }
+        }
+    }
+}
+
+impl Cip25Metadata {
+    /// Parse the metadata of a single asset, the object under its policy ID and asset name.
+    pub fn from_json(value: &JsonValue, version: Cip25Version) -> BlockfrostResult<Self> {
+        let object = value.as_object().ok_or_else(|| BlockfrostError::Parsing {
+            message: "CIP-25 metadata must be an object".to_string(),
+        })?;
+        let mut violations = Vec::new();
+
+        let name = required_string(object, "name", "name", &mut violations);
+        let image = required_string(object, "image", "image", &mut violations);
+        let media_type = optional_string(object, "mediaType", "mediaType", &mut violations);
+        let description = optional_string(object, "description", "description", &mut violations);
+
+        if let Some(media_type) = media_type.as_ref().filter(|t| !t.starts_with("image/")) {
+            violations.push(Cip25Violation::InvalidField {
+                field: "mediaType".to_string(),
+                reason: format!("'{media_type}' is not an image type"),
+            });
+        }
+
+        let files = match object.get("files") {
+            None => Vec::new(),
+            Some(JsonValue::Array(files)) => files
+                .iter()
+                .enumerate()
+                .filter_map(|(index, file)| parse_file(file, index, &mut violations))
+                .collect(),
+            Some(_) => {
+                violations.push(invalid_type("files", "an array"));
+                Vec::new()
+            }
+        };
+
+        let properties = object
+            .iter()
+            .filter(|(key, _)| {
+                !matches!(
+                    key.as_str(),
+                    "name" | "image" | "mediaType" | "description" | "files"
+                )
+            })
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+
+        Ok(Self {
+            version,
+            name,
+            image,
+            media_type,
+            description,
+            files,
+            properties,
+            violations,
+        })
+    }
+
+    /// Read the CIP-25 metadata of an asset returned by
+    /// [`assets_by_id`](crate::BlockfrostAPI::assets_by_id).
+    ///
+    /// Returns `None` if the asset has no on-chain metadata, or if it follows another standard
+    /// such as CIP-68. Metadata the API didn't recognize as CIP-25 is read as version 1, and
+    /// usually has violations.
+    pub fn from_asset(asset: &Asset) -> BlockfrostResult<Option<Self>> {
+        let asset = serde_json::to_value(asset).map_err(|reason| BlockfrostError::Parsing {
+            message: format!("invalid asset: {reason}"),
+        })?;
+
+        let version = match asset["onchain_metadata_standard"].as_str() {
+            Some("CIP25v2") => Cip25Version::V2,
+            Some("CIP25v1") | None => Cip25Version::V1,
+            Some(_) => return Ok(None),
+        };
+
+        match &asset["onchain_metadata"] {
+            JsonValue::Null => Ok(None),
+            metadata => Self::from_json(metadata, version).map(Some),
+        }
+    }
+
+    /// Read the metadata of every asset in label 721 transaction metadata, the
+    /// `json_metadata` of [`metadata_txs_by_label`](crate::BlockfrostAPI::metadata_txs_by_label)
+    /// with label [`CIP25_LABEL`].
+    ///
+    /// Fails if a policy ID is invalid. Assets whose name key is not hex in version 2, or is too
+    /// long, are returned without an [`AssetId`] and with the violation.
+    pub fn from_label_721(value: &JsonValue) -> BlockfrostResult<Vec<Cip25Asset>> {
+        let object = value.as_object().ok_or_else(|| BlockfrostError::Parsing {
+            message: "CIP-25 label 721 metadata must be an object".to_string(),
+        })?;
+
+        let version = match object.get("version") {
+            None => Cip25Version::V1,
+            Some(version) => match version_number(version) {
+                Some(1) => Cip25Version::V1,
+                Some(2) => Cip25Version::V2,
+                _ => {
+                    return Err(BlockfrostError::Parsing {
+                        message: format!("unknown CIP-25 version {version}"),
+                    })
+                }
+            },
+        };
+
+        let mut assets = Vec::new();
+
+        for (policy_id, policy) in object.iter().filter(|(key, _)| *key != "version") {
+            let policy = policy.as_object().ok_or_else(|| BlockfrostError::Parsing {
+                message: format!("CIP-25 metadata of policy {policy_id} must be an object"),
+            })?;
+            let policy_id = hex::decode(policy_id).map_err(|reason| BlockfrostError::Parsing {
+                message: format!("invalid hex in policy ID '{policy_id}': {reason}"),
+            })?;
+
+            if policy_id.len() != POLICY_ID_LENGTH {
+                return Err(BlockfrostError::Parsing {
+                    message: format!(
+                        "policy ID must be {POLICY_ID_LENGTH} bytes long, got {}",
+                        policy_id.len()
+                    ),
+                });
+            }
+
+            for (asset_name, metadata) in policy {
+                let mut metadata = Self::from_json(metadata, version)?;
+
+                let name_bytes = match version {
+                    Cip25Version::V1 => Some(asset_name.as_bytes().to_vec()),
+                    Cip25Version::V2 => hex::decode(asset_name).ok(),
+                };
+                let violation = match &name_bytes {
+                    None => Some(Cip25Violation::InvalidAssetName {
+                        asset_name: asset_name.clone(),
+                    }),
+                    Some(name_bytes) if name_bytes.len() > ASSET_NAME_MAX_LENGTH => {
+                        Some(Cip25Violation::AssetNameTooLong {
+                            asset_name: asset_name.clone(),
+                        })
+                    }
+                    Some(_) => None,
+                };
+
+                let asset = match violation {
+                    Some(violation) => {
+                        metadata.violations.push(violation);
+                        None
+                    }
+                    None => name_bytes
+                        .map(|name_bytes| AssetId::from_bytes(&policy_id, name_bytes))
+                        .transpose()?,
+                };
+
+                assets.push(Cip25Asset {
+                    asset,
+                    asset_name: asset_name.clone(),
+                    metadata,
+                });
+            }
+        }
+
+        Ok(assets)
+    }
+
+    /// Whether the metadata follows the standard.
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+fn parse_file(
+    value: &JsonValue, index: usize, violations: &mut Vec<Cip25Violation>,
+) -> Option<Cip25File> {
+    let field = |name: &str| format!("files[{index}].{name}");
+
+    let Some(object) = value.as_object() else {
+        violations.push(invalid_type(&format!("files[{index}]"), "an object"));
+        return None;
+    };
+
+    let media_type = required_string(object, "mediaType", &field("mediaType"), violations);
+
+    if let Some(media_type) = media_type.as_ref().filter(|t| !t.contains('/')) {
+        violations.push(Cip25Violation::InvalidField {
+            field: field("mediaType"),
+            reason: format!("'{media_type}' is not a media type"),
+        });
+    }
+
+    Some(Cip25File {
+        name: required_string(object, "name", &field("name"), violations),
+        media_type,
+        src: required_string(object, "src", &field("src"), violations),
+        properties: object
+            .iter()
+            .filter(|(key, _)| !matches!(key.as_str(), "name" | "mediaType" | "src"))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+    })
+}
+
+fn required_string(
+    object: &JsonMap, key: &str, field: &str, violations: &mut Vec<Cip25Violation>,
+) -> Option<String> {
+    if !object.contains_key(key) {
+        violations.push(Cip25Violation::MissingField {
+            field: field.to_string(),
+        });
+    }
+
+    optional_string(object, key, field, violations)
+}
+
+// A string, or an array of strings to join, as metadata strings are limited to 64 bytes.
+fn optional_string(
+    object: &JsonMap, key: &str, field: &str, violations: &mut Vec<Cip25Violation>,
+) -> Option<String> {
+    let joined = match object.get(key)? {
+        JsonValue::String(text) => Some(text.clone()),
+        JsonValue::Array(chunks) => chunks
+            .iter()
+            .map(JsonValue::as_str)
+            .collect::<Option<String>>(),
+        _ => None,
+    };
+
+    if joined.is_none() {
+        violations.push(invalid_type(field, "a string or an array of strings"));
+    }
+    joined
+}
+
+fn invalid_type(field: &str, expected: &str) -> Cip25Violation {
+    Cip25Violation::InvalidField {
+        field: field.to_string(),
+        reason: format!("expected {expected}"),
+    }
+}
+
+// Versions are written as numbers or strings, such as `2` or `"2.0"`.
+fn version_number(value: &JsonValue) -> Option<u64> {
+    match value {
+        JsonValue::Number(number) => number.as_u64().or_else(|| {
+            number
+                .as_f64()
+                .filter(|n| n.fract() == 0.0)
+                .map(|n| n as u64)
+        }),
+        JsonValue::String(text) => text.strip_suffix(".0").unwrap_or(text).parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const POLICY_ID: &str = "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a7";
+
+    #[test]
+    fn test_from_json() {
+        let json_value = json!({
+            "name": "NuttyNut #1",
+            "image": [
+                "ipfs://QmfKyJ4tuvHowwKQCbCHj4L5T3fSj8cjs7Aau8V7BWv226",
+                "/nut.png"
+            ],
+            "mediaType": "image/png",
+            "description": ["A very ", "nutty nut"],
+            "files": [
+                {
+                    "name": "NuttyNut #1 HD",
+                    "mediaType": "video/mp4",
+                    "src": ["ipfs://QmRhTTbUrPYEw3mJGGhQqQST9k86v1DPBiTTWJGKDJsVFw"],
+                    "length": 42
+                }
+            ],
+            "rarity": "legendary"
+        });
+        let metadata = Cip25Metadata::from_json(&json_value, Cip25Version::V1).unwrap();
+
+        assert!(metadata.is_valid());
+        assert_eq!(metadata.name.as_deref(), Some("NuttyNut #1"));
+        assert_eq!(
+            metadata.image.as_deref(),
+            Some("ipfs://QmfKyJ4tuvHowwKQCbCHj4L5T3fSj8cjs7Aau8V7BWv226/nut.png")
+        );
+        assert_eq!(metadata.description.as_deref(), Some("A very nutty nut"));
+        assert_eq!(metadata.files.len(), 1);
+        assert_eq!(metadata.files[0].media_type.as_deref(), Some("video/mp4"));
+        assert_eq!(metadata.files[0].properties["length"], 42);
+        assert_eq!(metadata.properties["rarity"], "legendary");
+        assert_eq!(metadata.properties.len(), 1);
+    }
+
+    #[test]
+    fn test_violations() {
+        let json_value = json!({
+            "image": 42,
+            "mediaType": "video/mp4",
+            "files": [{ "mediaType": "png", "src": "ipfs://Qm" }, "nut.png"]
+        });
+        let metadata = Cip25Metadata::from_json(&json_value, Cip25Version::V1).unwrap();
+
+        assert_eq!(metadata.name, None);
+        assert_eq!(metadata.image, None);
+        assert_eq!(metadata.files.len(), 1);
+        assert_eq!(
+            metadata
+                .violations
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "missing field 'name'",
+                "invalid field 'image': expected a string or an array of strings",
+                "invalid field 'mediaType': 'video/mp4' is not an image type",
+                "invalid field 'files[0].mediaType': 'png' is not a media type",
+                "missing field 'files[0].name'",
+                "invalid field 'files[1]': expected an object",
+            ]
+        );
+        assert!(Cip25Metadata::from_json(&json!("nut"), Cip25Version::V1).is_err());
+    }
+
+    #[test]
+    fn test_from_label_721() {
+        let v1 = json!({
+            POLICY_ID: {
+                "nutcoin": { "name": "Nut", "image": "ipfs://Qm1" }
+            }
+        });
+        let v2 = json!({
+            POLICY_ID: {
+                "6e7574636f696e": { "name": "Nut", "image": "ipfs://Qm1" },
+                "nutcoin": { "name": "Nut", "image": "ipfs://Qm1" }
+            },
+            "version": "2.0"
+        });
+
+        let assets = Cip25Metadata::from_label_721(&v1).unwrap();
+        assert_eq!(assets.len(), 1);
+        assert_eq!(
+            assets[0].asset.as_ref().unwrap().unit(),
+            format!("{POLICY_ID}6e7574636f696e")
+        );
+        assert_eq!(assets[0].asset_name, "nutcoin");
+        assert_eq!(assets[0].metadata.version, Cip25Version::V1);
+
+        let assets = Cip25Metadata::from_label_721(&v2).unwrap();
+        assert_eq!(assets.len(), 2);
+        for asset in &assets {
+            assert_eq!(asset.metadata.version, Cip25Version::V2);
+        }
+        assert_eq!(assets[0].asset.as_ref().unwrap().asset_name(), b"nutcoin");
+        assert!(assets[0].metadata.is_valid());
+        // Not hex, so not identified
+        assert_eq!(assets[1].asset, None);
+        assert_eq!(assets[1].asset_name, "nutcoin");
+        assert_eq!(
+            assets[1].metadata.violations,
+            [Cip25Violation::InvalidAssetName {
+                asset_name: "nutcoin".to_string()
+            }]
+        );
+
+        let long_name = "n".repeat(ASSET_NAME_MAX_LENGTH + 1);
+        let long = json!({
+            POLICY_ID: {
+                (long_name.clone()): { "name": "Nut", "image": "ipfs://Qm1" },
+                "nutcoin": { "name": "Nut", "image": "ipfs://Qm1" }
+            }
+        });
+
+        let assets = Cip25Metadata::from_label_721(&long).unwrap();
+        assert_eq!(assets.len(), 2);
+        assert_eq!(
+            assets[0].metadata.violations,
+            [Cip25Violation::AssetNameTooLong {
+                asset_name: long_name.clone()
+            }]
+        );
+        assert_eq!(assets[0].asset, None);
+        assert_eq!(assets[0].asset_name, long_name);
+        assert!(assets[1].asset.is_some());
+        assert!(assets[1].metadata.is_valid());
+
+        assert!(Cip25Metadata::from_label_721(&json!({ "nut": {} })).is_err());
+        assert!(Cip25Metadata::from_label_721(&json!({ "6e7574": { "nut": {} } })).is_err());
+        assert!(Cip25Metadata::from_label_721(&json!({ "version": 3 })).is_err());
+    }
+
+    #[test]
+    fn test_from_asset() {
+        let json_value = json!({
+            "asset": "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e7574636f696e",
+            "policy_id": POLICY_ID,
+            "asset_name": "6e7574636f696e",
+            "fingerprint": "asset1pkpwyknlvul7az0xx8czhl60pyel45rpje4z8w",
+            "quantity": "1",
+            "initial_mint_tx_hash": "6804edf9712d2b619edb6ac86861fe93a730693183a262b165fcc1ba1bc99cad",
+            "mint_or_burn_count": 1,
+            "onchain_metadata": {
+                "name": "My NFT token",
+                "image": "ipfs://ipfs/QmfKyJ4tuvHowwKQCbCHj4L5T3fSj8cjs7Aau8V7BWv226"
+            },
+            "onchain_metadata_standard": "CIP25v2",
+            "metadata": null
+        });
+        let asset = serde_json::from_value::<Asset>(json_value).unwrap();
+        let metadata = Cip25Metadata::from_asset(&asset).unwrap().unwrap();
+
+        assert_eq!(metadata.version, Cip25Version::V2);
+        assert_eq!(metadata.name.as_deref(), Some("My NFT token"));
+        assert!(metadata.is_valid());
+    }
+}
@@ -0,0 +1,259 @@
+use crate::{
+    AssetId, BlockfrostAPI, BlockfrostError, BlockfrostResult, Cip67Label, JsonMap, JsonValue,
+    Pagination, PlutusData,
+};
+
+/// [CIP-68] metadata of a token, read from the inline datum of its `(100)` reference token.
+///
+/// Returned by [`cip68_metadata`](BlockfrostAPI::cip68_metadata), or built from a datum with
+/// [`Cip68Metadata::from_datum`].
+///
+/// The datum is `Constr 0 [metadata, version, extra]`, where `metadata` is a map from UTF-8
+/// byte string keys, converted to JSON in [`Cip68Metadata::metadata`].
+///
+/// [CIP-68]: https://cips.cardano.org/cip/CIP-0068
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cip68Metadata {
+    /// The user token, labelled `(222)`, `(333)` or `(444)`.
+    pub asset: AssetId,
+    /// The `(100)` reference token holding the datum.
+    pub reference_asset: AssetId,
+    /// Metadata, with byte strings as UTF-8 text, or as hex when they aren't valid UTF-8.
+    pub metadata: JsonMap,
+    pub version: u64,
+    /// Custom data of the minting project.
+    pub extra: PlutusData,
+    /// The whole datum, as is.
+    pub datum: PlutusData,
+}
+
+impl Cip68Metadata {
+    /// Read the metadata of `asset`, a user token, from the datum of its reference token.
+    pub fn from_datum(asset: AssetId, datum: PlutusData) -> BlockfrostResult<Self> {
+        let reference_asset = reference_asset(&asset)?;
+        let fields = datum.expect_constr(0, 3)?;
+
+        let metadata = match plutus_data_to_json(&fields[0]) {
+            JsonValue::Object(metadata) => metadata,
+            _ => {
+                return Err(BlockfrostError::Parsing {
+                    message: "CIP-68 metadata must be a map".to_string(),
+                })
+            }
+        };
+        let version = fields[1]
+            .as_integer()
+            .and_then(|version| u64::try_from(version).ok())
+            .ok_or_else(|| BlockfrostError::Parsing {
+                message: format!("invalid CIP-68 version {:?}", fields[1]),
+            })?;
+        let extra = fields[2].clone();
+
+        Ok(Self {
+            asset,
+            reference_asset,
+            metadata,
+            version,
+            extra,
+            datum,
+        })
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.metadata.get("name")?.as_str()
+    }
+
+    pub fn image(&self) -> Option<&str> {
+        self.metadata.get("image")?.as_str()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.metadata.get("description")?.as_str()
+    }
+
+    /// Decimals of a `(333)` fungible token.
+    pub fn decimals(&self) -> Option<u64> {
+        self.metadata.get("decimals")?.as_u64()
+    }
+}
+
+impl BlockfrostAPI {
+    /// Return the [CIP-68] metadata of `asset`, a `(222)`, `(333)` or `(444)` token.
+    ///
+    /// Derives the unit of the `(100)` reference token, finds its current UTxO through
+    /// [`assets_addresses`](BlockfrostAPI::assets_addresses) and
+    /// [`addresses_utxos_asset`](BlockfrostAPI::addresses_utxos_asset), and decodes its inline
+    /// datum.
+    ///
+    /// [CIP-68]: https://cips.cardano.org/cip/CIP-0068
+    pub async fn cip68_metadata(&self, asset: &str) -> BlockfrostResult<Cip68Metadata> {
+        let asset = AssetId::from_unit(asset)?;
+        let reference_unit = reference_asset(&asset)?.unit();
+
+        let address = self
+            .assets_addresses(&reference_unit, Pagination::default())
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| BlockfrostError::Parsing {
+                message: format!("reference token {reference_unit} is not held by any address"),
+            })?
+            .address;
+
+        let inline_datum = self
+            .addresses_utxos_asset(&address, &reference_unit, Pagination::default())
+            .await?
+            .into_iter()
+            .find_map(|utxo| utxo.inline_datum)
+            .ok_or_else(|| BlockfrostError::Parsing {
+                message: format!("reference token {reference_unit} has no inline datum"),
+            })?;
+
+        Cip68Metadata::from_datum(asset, PlutusData::from_hex(&inline_datum)?)
+    }
+}
+
+// The `(100)` reference token of a user token.
+fn reference_asset(asset: &AssetId) -> BlockfrostResult<AssetId> {
+    match asset.label() {
+        Some(Cip67Label::Nft | Cip67Label::Ft | Cip67Label::Rft) => {
+            let mut asset_name = Cip67Label::ReferenceNft.to_prefix().to_vec();
+            asset_name.extend(asset.name_without_label());
+
+            AssetId::from_bytes(asset.policy_id(), asset_name)
+        }
+        label => Err(BlockfrostError::Parsing {
+            message: format!(
+                "asset {asset} is not a CIP-68 user token, its label is {}",
+                label.map_or("missing".to_string(), |label| label.to_string())
+            ),
+        }),
+    }
+}
+
+fn plutus_data_to_json(data: &PlutusData) -> JsonValue {
+    match data {
+        PlutusData::Constr { tag, fields } => serde_json::json!({
+            "constructor": tag,
+            "fields": fields.iter().map(plutus_data_to_json).collect::<Vec<_>>(),
+        }),
+        PlutusData::Map(entries) => JsonValue::Object(
+            entries
+                .iter()
+                .map(|(key, value)| {
+                    let key = match plutus_data_to_json(key) {
+                        JsonValue::String(key) => key,
+                        key => key.to_string(),
+                    };
+
+                    (key, plutus_data_to_json(value))
+                })
+                .collect(),
+        ),
+        PlutusData::List(items) => items.iter().map(plutus_data_to_json).collect(),
+        PlutusData::Integer(integer) => i64::try_from(*integer)
+            .map(JsonValue::from)
+            .unwrap_or_else(|_| JsonValue::String(integer.to_string())),
+        PlutusData::Bytes(bytes) => match std::str::from_utf8(bytes) {
+            Ok(text) => JsonValue::String(text.to_string()),
+            Err(_) => JsonValue::String(hex::encode(bytes)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const POLICY_ID: &str = "1e349c9bdea19fd6c147626a5260bc44b71635f398b67c59881df209";
+
+    fn bytes(text: &str) -> PlutusData {
+        PlutusData::Bytes(text.as_bytes().to_vec())
+    }
+
+    fn datum(metadata: Vec<(PlutusData, PlutusData)>, version: i128) -> PlutusData {
+        PlutusData::Constr {
+            tag: 0,
+            fields: vec![
+                PlutusData::Map(metadata),
+                PlutusData::Integer(version),
+                PlutusData::Constr {
+                    tag: 0,
+                    fields: vec![],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_nft() {
+        // (222) "Hello"
+        let asset = AssetId::new(POLICY_ID, "000de14048656c6c6f").unwrap();
+        let datum = datum(
+            vec![
+                (bytes("name"), bytes("Hello")),
+                (
+                    bytes("image"),
+                    bytes("ipfs://QmfKyJ4tuvHowwKQCbCHj4L5T3fSj8cjs7Aau8V7BWv226"),
+                ),
+                (bytes("hash"), PlutusData::Bytes(vec![0xff, 0x00])),
+                (
+                    bytes("files"),
+                    PlutusData::List(vec![PlutusData::Map(vec![(
+                        bytes("src"),
+                        bytes("ipfs://Qm"),
+                    )])]),
+                ),
+            ],
+            2,
+        );
+        let metadata = Cip68Metadata::from_datum(asset, datum).unwrap();
+
+        assert_eq!(
+            metadata.reference_asset.asset_name_hex(),
+            "000643b048656c6c6f"
+        );
+        assert_eq!(metadata.version, 2);
+        assert_eq!(metadata.name(), Some("Hello"));
+        assert_eq!(
+            metadata.image(),
+            Some("ipfs://QmfKyJ4tuvHowwKQCbCHj4L5T3fSj8cjs7Aau8V7BWv226")
+        );
+        assert_eq!(metadata.metadata["hash"], "ff00");
+        assert_eq!(metadata.metadata["files"], json!([{ "src": "ipfs://Qm" }]));
+        assert_eq!(metadata.extra.constr_tag().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_fungible_token() {
+        // (333) "Nut"
+        let asset = AssetId::new(POLICY_ID, "0014df104e7574").unwrap();
+        let datum = datum(
+            vec![
+                (bytes("name"), bytes("Nut")),
+                (bytes("ticker"), bytes("NUT")),
+                (bytes("decimals"), PlutusData::Integer(6)),
+            ],
+            1,
+        );
+        let metadata = Cip68Metadata::from_datum(asset, datum).unwrap();
+
+        assert_eq!(metadata.reference_asset.asset_name_hex(), "000643b04e7574");
+        assert_eq!(metadata.decimals(), Some(6));
+        assert_eq!(metadata.metadata["ticker"], "NUT");
+    }
+
+    #[test]
+    fn test_invalid() {
+        let nft = AssetId::new(POLICY_ID, "000de14048656c6c6f").unwrap();
+        // (100) reference token itself
+        let reference = AssetId::new(POLICY_ID, "000643b048656c6c6f").unwrap();
+        let not_cip68 = AssetId::new(POLICY_ID, "48656c6c6f").unwrap();
+
+        assert!(Cip68Metadata::from_datum(reference, datum(vec![], 1)).is_err());
+        assert!(Cip68Metadata::from_datum(not_cip68, datum(vec![], 1)).is_err());
+        assert!(Cip68Metadata::from_datum(nft.clone(), datum(vec![], -1)).is_err());
+        assert!(Cip68Metadata::from_datum(nft, PlutusData::List(vec![])).is_err());
+    }
+}
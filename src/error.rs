@@ -8,6 +8,7 @@ use {reqwest::Error as ReqwestError, serde_json::Error as SerdeJsonError};
 pub type BlockfrostResult<T, E = BlockfrostError> = std::result::Result<T, E>;
 
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum BlockfrostError {
     #[error("Reqwest error for URL {url}: {reason}")]
     Reqwest { url: String, reason: reqwest::Error },
@@ -23,6 +24,12 @@ pub enum BlockfrostError {
     Io(#[from] std::io::Error),
     #[error("Response error for URL {url}: {reason}")]
     Response { url: String, reason: ResponseError },
+    #[error("CBOR error: {message}")]
+    Cbor { message: String },
+    #[error("Hash mismatch: expected {expected}, computed {computed}")]
+    HashMismatch { expected: String, computed: String },
+    #[error("Invalid transaction: {reason}")]
+    InvalidTransaction { reason: String },
 }
 
 #[derive(Serialize, Deserialize, Error, Debug, Clone)]
@@ -0,0 +1,381 @@
+use crate::{BlockfrostAPI, BlockfrostError, BlockfrostResult, ExUnits};
+use blockfrost_openapi::models::epoch_param_content::EpochParamContent;
+
+/// Overhead added to the size of an output when computing its minimum ADA, in bytes.
+pub const UTXO_ENTRY_OVERHEAD: u64 = 160;
+
+/// Size of a reference scripts fee tier, in bytes.
+pub const REFERENCE_SCRIPTS_TIER_SIZE: u64 = 25_600;
+
+/// Largest total size of the reference scripts of a transaction allowed by the ledger, in bytes.
+pub const MAX_REFERENCE_SCRIPTS_SIZE: u64 = 204_800;
+
+/// Computes transaction fees and minimum output values offline, from protocol parameters.
+///
+/// Built from [`epochs_latest_parameters`](BlockfrostAPI::epochs_latest_parameters), either with
+/// [`FeeCalculator::new`] or [`BlockfrostAPI::fee_calculator`].
+///
+/// Prices are converted from their decimal representation to exact fractions, so the results
+/// match the ledger rules rather than floating point approximations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeCalculator {
+    /// Fee per byte of transaction (`min_fee_a`).
+    pub min_fee_a: u64,
+    /// Constant fee per transaction (`min_fee_b`).
+    pub min_fee_b: u64,
+    /// Price of a unit of script memory.
+    pub price_mem: f64,
+    /// Price of a script CPU step.
+    pub price_step: f64,
+    /// Base price per byte of reference scripts, multiplied by 1.2 every 25 KiB.
+    pub min_fee_ref_script_cost_per_byte: f64,
+    /// Cost per byte of UTxO storage.
+    pub coins_per_utxo_size: u64,
+}
+
+impl FeeCalculator {
+    /// Create a [`FeeCalculator`] from the protocol parameters of an epoch.
+    ///
+    /// Fails if the parameters predate Babbage, and so miss script prices or the UTxO cost.
+    pub fn new(params: &EpochParamContent) -> BlockfrostResult<Self> {
+        let missing = |name: &str| BlockfrostError::Parsing {
+            message: format!("protocol parameter '{name}' is missing or invalid"),
+        };
+
+        Ok(Self {
+            min_fee_a: u64::try_from(params.min_fee_a).map_err(|_| missing("min_fee_a"))?,
+            min_fee_b: u64::try_from(params.min_fee_b).map_err(|_| missing("min_fee_b"))?,
+            price_mem: params.price_mem.ok_or_else(|| missing("price_mem"))?,
+            price_step: params.price_step.ok_or_else(|| missing("price_step"))?,
+            min_fee_ref_script_cost_per_byte: params
+                .min_fee_ref_script_cost_per_byte
+                .unwrap_or_default(),
+            coins_per_utxo_size: params
+                .coins_per_utxo_size
+                .as_deref()
+                .and_then(|coins| coins.parse().ok())
+                .ok_or_else(|| missing("coins_per_utxo_size"))?,
+        })
+    }
+
+    /// Minimum fee of a transaction of `tx_size` bytes, running scripts with a total budget of
+    /// `ex_units` and referencing `ref_scripts_size` bytes of scripts.
+    ///
+    /// Fails if `ref_scripts_size` is above [`MAX_REFERENCE_SCRIPTS_SIZE`] or the fee doesn't fit
+    /// in a `u64`.
+    pub fn min_fee(
+        &self, tx_size: u64, ex_units: ExUnits, ref_scripts_size: u64,
+    ) -> BlockfrostResult<u64> {
+        let reference_scripts_fee = self.reference_scripts_fee(ref_scripts_size)?;
+
+        self.linear_fee(tx_size)?
+            .checked_add(self.script_fee(ex_units)?)
+            .and_then(|fee| fee.checked_add(reference_scripts_fee))
+            .ok_or_else(|| too_large("fee"))
+    }
+
+    /// Minimum fee of the serialized transaction `transaction_data`, see
+    /// [`FeeCalculator::min_fee`].
+    pub fn min_fee_for_tx(
+        &self, transaction_data: &[u8], ex_units: ExUnits, ref_scripts_size: u64,
+    ) -> BlockfrostResult<u64> {
+        self.min_fee(transaction_data.len() as u64, ex_units, ref_scripts_size)
+    }
+
+    /// Size-based part of the fee, `min_fee_a * tx_size + min_fee_b`.
+    pub fn linear_fee(&self, tx_size: u64) -> BlockfrostResult<u64> {
+        self.min_fee_a
+            .checked_mul(tx_size)
+            .and_then(|fee| fee.checked_add(self.min_fee_b))
+            .ok_or_else(|| too_large("linear fee"))
+    }
+
+    /// Cost of executing scripts with a total budget of `ex_units`, rounded up.
+    ///
+    /// The budget can be taken from a transaction evaluation, by summing
+    /// [`TxEvaluation::ex_units`](crate::TxEvaluation::ex_units).
+    pub fn script_fee(&self, ex_units: ExUnits) -> BlockfrostResult<u64> {
+        let fee = || {
+            let memory = Ratio::from_decimal(self.price_mem)?.mul_int(ex_units.memory)?;
+            let steps = Ratio::from_decimal(self.price_step)?.mul_int(ex_units.steps)?;
+
+            memory.checked_add(steps)?.ceil()
+        };
+
+        fee().ok_or_else(|| too_large("script fee"))
+    }
+
+    /// Cost of the reference scripts of a transaction, `ref_scripts_size` bytes in total.
+    ///
+    /// The price per byte is multiplied by 1.2 for every [`REFERENCE_SCRIPTS_TIER_SIZE`]
+    /// bytes, and the total is rounded down. Fails if `ref_scripts_size` is above
+    /// [`MAX_REFERENCE_SCRIPTS_SIZE`], which the ledger rejects.
+    pub fn reference_scripts_fee(&self, ref_scripts_size: u64) -> BlockfrostResult<u64> {
+        if ref_scripts_size > MAX_REFERENCE_SCRIPTS_SIZE {
+            return Err(BlockfrostError::Parsing {
+                message: format!(
+                    "reference scripts of {ref_scripts_size} bytes exceed the limit of \
+                     {MAX_REFERENCE_SCRIPTS_SIZE} bytes"
+                ),
+            });
+        }
+
+        let fee = || {
+            let multiplier = Ratio::new(6, 5);
+            let mut price = Ratio::from_decimal(self.min_fee_ref_script_cost_per_byte)?;
+            let mut fee = Ratio::new(0, 1);
+            let mut remaining = ref_scripts_size;
+
+            while remaining >= REFERENCE_SCRIPTS_TIER_SIZE {
+                fee = fee.checked_add(price.mul_int(REFERENCE_SCRIPTS_TIER_SIZE)?)?;
+                price = price.checked_mul(multiplier)?;
+                remaining -= REFERENCE_SCRIPTS_TIER_SIZE;
+            }
+
+            fee.checked_add(price.mul_int(remaining)?)?.floor()
+        };
+
+        fee().ok_or_else(|| too_large("reference scripts fee"))
+    }
+
+    /// Minimum ADA of an output, in lovelace.
+    ///
+    /// `output_size` is the size of the serialized output holding `coin` lovelace. The size of
+    /// the coin encoding is adjusted, as raising the coin to the minimum can make it longer.
+    pub fn min_ada_for_output(&self, output_size: u64, coin: u64) -> u64 {
+        let size_without_coin = output_size.saturating_sub(cbor_uint_size(coin));
+
+        let min_ada_with_coin_size = |coin_size: u64| {
+            (UTXO_ENTRY_OVERHEAD + size_without_coin + coin_size) * self.coins_per_utxo_size
+        };
+
+        // Find the smallest coin encoding that can hold the minimum it implies.
+        let mut coin_size = cbor_uint_size(coin);
+        let mut min_ada = min_ada_with_coin_size(coin_size);
+
+        while cbor_uint_size(min_ada) > coin_size {
+            coin_size = cbor_uint_size(min_ada);
+            min_ada = min_ada_with_coin_size(coin_size);
+        }
+
+        min_ada
+    }
+}
+
+impl BlockfrostAPI {
+    /// Return a [`FeeCalculator`] for the latest protocol parameters.
+    pub async fn fee_calculator(&self) -> BlockfrostResult<FeeCalculator> {
+        FeeCalculator::new(&self.epochs_latest_parameters().await?)
+    }
+}
+
+fn too_large(what: &str) -> BlockfrostError {
+    BlockfrostError::Parsing {
+        message: format!("{what} is too large"),
+    }
+}
+
+// Size of the CBOR encoding of an unsigned integer.
+fn cbor_uint_size(value: u64) -> u64 {
+    match value {
+        0..=23 => 1,
+        24..=0xff => 2,
+        0x100..=0xffff => 3,
+        0x1_0000..=0xffff_ffff => 5,
+        _ => 9,
+    }
+}
+
+// Non-negative fraction, used to apply prices without rounding errors. Operations return
+// `None` on overflow.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Ratio {
+    numerator: u128,
+    denominator: u128,
+}
+
+impl Ratio {
+    fn new(numerator: u128, denominator: u128) -> Self {
+        let gcd = gcd(numerator, denominator).max(1);
+
+        Self {
+            numerator: numerator / gcd,
+            denominator: denominator / gcd,
+        }
+    }
+
+    // Protocol parameters are decimal numbers, use their shortest representation.
+    fn from_decimal(value: f64) -> Option<Self> {
+        let text = value.max(0.0).to_string();
+        let (integer, fraction) = text.split_once('.').unwrap_or((&text, ""));
+        let digits = format!("{integer}{fraction}");
+
+        Some(Self::new(
+            digits.parse().ok()?,
+            10u128.checked_pow(fraction.len() as u32)?,
+        ))
+    }
+
+    fn mul_int(self, value: u64) -> Option<Self> {
+        self.checked_mul(Ratio::new(u128::from(value), 1))
+    }
+
+    // Common factors are divided out first, to keep the products small.
+    fn checked_mul(self, other: Ratio) -> Option<Self> {
+        let left = gcd(self.numerator, other.denominator).max(1);
+        let right = gcd(other.numerator, self.denominator).max(1);
+
+        Some(Ratio::new(
+            (self.numerator / left).checked_mul(other.numerator / right)?,
+            (self.denominator / right).checked_mul(other.denominator / left)?,
+        ))
+    }
+
+    fn checked_add(self, other: Ratio) -> Option<Self> {
+        let gcd = gcd(self.denominator, other.denominator).max(1);
+        let numerator = self
+            .numerator
+            .checked_mul(other.denominator / gcd)?
+            .checked_add(other.numerator.checked_mul(self.denominator / gcd)?)?;
+
+        Some(Ratio::new(
+            numerator,
+            (self.denominator / gcd).checked_mul(other.denominator)?,
+        ))
+    }
+
+    fn floor(self) -> Option<u64> {
+        u64::try_from(self.numerator / self.denominator).ok()
+    }
+
+    fn ceil(self) -> Option<u64> {
+        u64::try_from(self.numerator.div_ceil(self.denominator)).ok()
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn mainnet() -> FeeCalculator {
+        FeeCalculator {
+            min_fee_a: 44,
+            min_fee_b: 155381,
+            price_mem: 0.0577,
+            price_step: 0.0000721,
+            min_fee_ref_script_cost_per_byte: 15.0,
+            coins_per_utxo_size: 4310,
+        }
+    }
+
+    #[test]
+    fn test_linear_fee() {
+        assert_eq!(mainnet().linear_fee(0).unwrap(), 155381);
+        assert_eq!(mainnet().linear_fee(300).unwrap(), 168581);
+        assert_eq!(
+            mainnet()
+                .min_fee_for_tx(&[0; 300], ExUnits::default(), 0)
+                .unwrap(),
+            168581
+        );
+        assert!(mainnet().linear_fee(u64::MAX).is_err());
+    }
+
+    #[rstest]
+    #[case(0, 0, 0)]
+    // 98.09 + 34.3533428
+    #[case(1700, 476468, 133)]
+    // 577 + 721, exactly
+    #[case(10000, 10000000, 1298)]
+    #[case(14000000, 10000000000, 1528800)]
+    fn test_script_fee(#[case] memory: u64, #[case] steps: u64, #[case] expected: u64) {
+        assert_eq!(
+            mainnet().script_fee(ExUnits { memory, steps }).unwrap(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(0, 0)]
+    #[case(1000, 15000)]
+    #[case(25600, 384000)]
+    #[case(30000, 463200)]
+    #[case(51300, 846960)]
+    // 844800 + 21.6
+    #[case(51201, 844821)]
+    // 8 full tiers, 384000 * (1.2^8 - 1) / 0.2
+    #[case(204800, 6335648)]
+    fn test_reference_scripts_fee(#[case] size: u64, #[case] expected: u64) {
+        assert_eq!(mainnet().reference_scripts_fee(size).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_fee_overflow() {
+        let expensive = FeeCalculator {
+            price_mem: 2.0,
+            ..mainnet()
+        };
+        let tiny_price = FeeCalculator {
+            price_mem: 1e-40,
+            ..mainnet()
+        };
+        let ex_units = ExUnits {
+            memory: u64::MAX,
+            steps: u64::MAX,
+        };
+
+        // Past the ledger limit, and where the fractions used to overflow
+        assert!(mainnet().reference_scripts_fee(800 * 1024).is_err());
+        assert!(mainnet()
+            .reference_scripts_fee(MAX_REFERENCE_SCRIPTS_SIZE + 1)
+            .is_err());
+        assert!(mainnet().script_fee(ex_units).is_ok());
+        assert!(expensive.script_fee(ex_units).is_err());
+        assert!(tiny_price.script_fee(ExUnits::default()).is_err());
+        assert!(mainnet().min_fee(u64::MAX, ExUnits::default(), 0).is_err());
+    }
+
+    #[test]
+    fn test_min_fee() {
+        let ex_units = ExUnits {
+            memory: 1700,
+            steps: 476468,
+        };
+
+        assert_eq!(
+            mainnet().min_fee(300, ex_units, 1000).unwrap(),
+            168581 + 133 + 15000
+        );
+    }
+
+    #[rstest]
+    // Already encoded on 5 bytes
+    #[case(65, 1000000, 969750)]
+    // The coin grows from 1 to 5 bytes
+    #[case(61, 0, 969750)]
+    // Multi-asset output
+    #[case(120, 2000000, 1206800)]
+    fn test_min_ada_for_output(#[case] size: u64, #[case] coin: u64, #[case] expected: u64) {
+        assert_eq!(mainnet().min_ada_for_output(size, coin), expected);
+    }
+
+    #[test]
+    fn test_ratio_from_decimal() {
+        assert_eq!(Ratio::from_decimal(0.0577), Some(Ratio::new(577, 10000)));
+        assert_eq!(
+            Ratio::from_decimal(0.0000721),
+            Some(Ratio::new(721, 10000000))
+        );
+        assert_eq!(Ratio::from_decimal(15.0), Some(Ratio::new(15, 1)));
+        assert_eq!(Ratio::from_decimal(44.5), Some(Ratio::new(89, 2)));
+        assert_eq!(Ratio::from_decimal(1e-40), None);
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{BlockfrostAPI, BlockfrostResult};
+use blockfrost_openapi::models::{
+    tx_content::TxContent, tx_content_delegations_inner::TxContentDelegationsInner,
+    tx_content_metadata_inner::TxContentMetadataInner, tx_content_mirs_inner::TxContentMirsInner,
+    tx_content_pool_certs_inner::TxContentPoolCertsInner,
+    tx_content_pool_retires_inner::TxContentPoolRetiresInner,
+    tx_content_redeemers_inner::TxContentRedeemersInner,
+    tx_content_required_signers_inner::TxContentRequiredSignersInner,
+    tx_content_stake_addr_inner::TxContentStakeAddrInner, tx_content_utxo::TxContentUtxo,
+    tx_content_withdrawals_inner::TxContentWithdrawalsInner,
+};
+use std::future::Future;
+
+/// A transaction with all of its sub-resources, see [`BlockfrostAPI::transaction_full`].
+///
+/// Sub-resources the transaction has none of are empty.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FullTransaction {
+    pub content: TxContent,
+    pub utxos: TxContentUtxo,
+    pub metadata: Vec<TxContentMetadataInner>,
+    pub redeemers: Vec<TxContentRedeemersInner>,
+    pub required_signers: Vec<TxContentRequiredSignersInner>,
+    pub stakes: Vec<TxContentStakeAddrInner>,
+    pub delegations: Vec<TxContentDelegationsInner>,
+    pub withdrawals: Vec<TxContentWithdrawalsInner>,
+    pub mirs: Vec<TxContentMirsInner>,
+    pub pool_updates: Vec<TxContentPoolCertsInner>,
+    pub pool_retires: Vec<TxContentPoolRetiresInner>,
+}
+
+impl BlockfrostAPI {
+    /// Return a transaction with its UTXOs, metadata, redeemers, required signers and
+    /// certificates.
+    ///
+    /// After [`transaction_by_hash`](Self::transaction_by_hash), the sub-resources are fetched
+    /// concurrently, skipping those whose counter in [`TxContent`] is zero. Metadata and
+    /// required signers have no counter and are always fetched.
+    pub async fn transaction_full(&self, hash: &str) -> BlockfrostResult<FullTransaction> {
+        let content = self.transaction_by_hash(hash).await?;
+
+        let (
+            utxos,
+            metadata,
+            redeemers,
+            required_signers,
+            stakes,
+            delegations,
+            withdrawals,
+            mirs,
+            pool_updates,
+            pool_retires,
+        ) = futures::try_join!(
+            self.transactions_utxos(hash),
+            self.transactions_metadata(hash),
+            fetch_if(content.redeemer_count, self.transactions_redeemers(hash)),
+            self.transactions_required_signers(hash),
+            fetch_if(content.stake_cert_count, self.transactions_stakes(hash)),
+            fetch_if(
+                content.delegation_count,
+                self.transactions_delegations(hash)
+            ),
+            fetch_if(
+                content.withdrawal_count,
+                self.transactions_withdrawals(hash)
+            ),
+            fetch_if(content.mir_cert_count, self.transactions_mirs(hash)),
+            fetch_if(
+                content.pool_update_count,
+                self.transactions_pool_updates(hash)
+            ),
+            fetch_if(
+                content.pool_retire_count,
+                self.transactions_pool_retires(hash)
+            ),
+        )?;
+
+        Ok(FullTransaction {
+            content,
+            utxos,
+            metadata,
+            redeemers,
+            required_signers,
+            stakes,
+            delegations,
+            withdrawals,
+            mirs,
+            pool_updates,
+            pool_retires,
+        })
+    }
+}
+
+/// Await `request` if `count` is positive. Futures are lazy, so a skipped request is never sent.
+async fn fetch_if<T: Default>(
+    count: i32, request: impl Future<Output = BlockfrostResult<T>>,
+) -> BlockfrostResult<T> {
+    match count {
+        0 => Ok(T::default()),
+        _ => request.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlockfrostError;
+
+    #[tokio::test]
+    async fn test_fetch_if() {
+        let skipped: Vec<u32> = fetch_if(0, async { unreachable!() }).await.unwrap();
+        let fetched = fetch_if(2, async { Ok(vec![1, 2]) }).await.unwrap();
+        let failed = fetch_if::<Vec<u32>>(1, async {
+            Err(BlockfrostError::Parsing {
+                message: "failed".to_string(),
+            })
+        })
+        .await;
+
+        assert!(skipped.is_empty());
+        assert_eq!(fetched, [1, 2]);
+        assert!(failed.is_err());
+    }
+}
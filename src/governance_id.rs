@@ -0,0 +1,510 @@
+use crate::{utils::blake2b_224, BlockfrostError, BlockfrostResult};
+use bech32::{Bech32, Hrp};
+use std::{fmt, str::FromStr};
+
+/// Length of a key or script hash identifying a governance credential (blake2b-224).
+pub const CREDENTIAL_HASH_LENGTH: usize = 28;
+
+/// Length of a transaction hash in bytes (blake2b-256).
+pub const TX_HASH_LENGTH: usize = 32;
+
+/// Bech32 prefix of [CIP-129] governance action IDs.
+///
+/// [CIP-129]: https://cips.cardano.org/cip/CIP-0129
+pub const GOV_ACTION_PREFIX: &str = "gov_action";
+
+/// Governance role a credential acts in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum GovernanceRole {
+    /// Hot credential of a constitutional committee member, used to vote.
+    CommitteeHot,
+    /// Cold credential of a constitutional committee member, identifying the member.
+    CommitteeCold,
+    /// Delegated representative.
+    DRep,
+}
+
+impl GovernanceRole {
+    /// Bech32 prefix of the role, shared by CIP-129 and CIP-105 key hash IDs.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            GovernanceRole::CommitteeHot => "cc_hot",
+            GovernanceRole::CommitteeCold => "cc_cold",
+            GovernanceRole::DRep => "drep",
+        }
+    }
+
+    /// Key type in the upper nibble of the CIP-129 header byte.
+    fn key_type(self) -> u8 {
+        match self {
+            GovernanceRole::CommitteeHot => 0b0000,
+            GovernanceRole::CommitteeCold => 0b0001,
+            GovernanceRole::DRep => 0b0010,
+        }
+    }
+
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        [
+            GovernanceRole::CommitteeHot,
+            GovernanceRole::CommitteeCold,
+            GovernanceRole::DRep,
+        ]
+        .into_iter()
+        .find(|role| role.prefix() == prefix)
+    }
+}
+
+/// Whether a governance credential is a key hash or a script hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CredentialKind {
+    KeyHash,
+    ScriptHash,
+}
+
+impl CredentialKind {
+    /// Credential type in the lower nibble of the CIP-129 header byte.
+    fn credential_type(self) -> u8 {
+        match self {
+            CredentialKind::KeyHash => 0b0010,
+            CredentialKind::ScriptHash => 0b0011,
+        }
+    }
+}
+
+/// A DRep or constitutional committee credential.
+///
+/// Parsed from [CIP-129] IDs (`drep1…`, `cc_hot1…`, `cc_cold1…` with a header byte carrying the
+/// credential type) or from the legacy [CIP-105] ones (`drep1…` holding the bare key hash,
+/// `drep_script1…`, `drep_vk1…`, `drep_xvk1…` and their `cc_hot` and `cc_cold` counterparts).
+/// Verification keys are hashed into their key hash. [`Display`](fmt::Display) formats the
+/// CIP-129 ID, which is what the DRep endpoints of [`BlockfrostAPI`](crate::BlockfrostAPI) send.
+///
+/// [CIP-105]: https://cips.cardano.org/cip/CIP-0105
+/// [CIP-129]: https://cips.cardano.org/cip/CIP-0129
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GovernanceCredential {
+    role: GovernanceRole,
+    kind: CredentialKind,
+    hash: [u8; CREDENTIAL_HASH_LENGTH],
+}
+
+impl GovernanceCredential {
+    /// Create a credential from its role, kind and raw hash.
+    pub fn new(role: GovernanceRole, kind: CredentialKind, hash: &[u8]) -> BlockfrostResult<Self> {
+        let hash = hash.try_into().map_err(|_| BlockfrostError::Parsing {
+            message: format!(
+                "credential hash must be {CREDENTIAL_HASH_LENGTH} bytes long, got {}",
+                hash.len()
+            ),
+        })?;
+
+        Ok(Self { role, kind, hash })
+    }
+
+    /// Parse a CIP-129 or CIP-105 bech32 ID.
+    pub fn parse(id: &str) -> BlockfrostResult<Self> {
+        let invalid = |reason: String| BlockfrostError::Parsing {
+            message: format!("invalid governance credential '{id}': {reason}"),
+        };
+
+        let (hrp, bytes) = bech32::decode(id).map_err(|reason| invalid(reason.to_string()))?;
+        let prefix = hrp.as_str();
+
+        if let Some(role) = GovernanceRole::from_prefix(prefix) {
+            return match bytes.len() {
+                // CIP-105 key hash, without a header byte.
+                CREDENTIAL_HASH_LENGTH => Self::new(role, CredentialKind::KeyHash, &bytes),
+                // CIP-129, with a header byte.
+                len if len == CREDENTIAL_HASH_LENGTH + 1 => {
+                    let header = bytes[0];
+                    let kind = match header & 0x0f {
+                        0b0010 => CredentialKind::KeyHash,
+                        0b0011 => CredentialKind::ScriptHash,
+                        _ => return Err(invalid(format!("unknown header byte {header:#04x}"))),
+                    };
+
+                    if header >> 4 != role.key_type() {
+                        return Err(invalid(format!(
+                            "header byte {header:#04x} does not match the '{prefix}' prefix"
+                        )));
+                    }
+
+                    Self::new(role, kind, &bytes[1..])
+                }
+                len => Err(invalid(format!("unexpected payload length {len}"))),
+            };
+        }
+
+        let (role, suffix) = prefix
+            .rsplit_once('_')
+            .and_then(|(role, suffix)| Some((GovernanceRole::from_prefix(role)?, suffix)))
+            .ok_or_else(|| invalid(format!("unknown prefix '{prefix}'")))?;
+
+        match (suffix, bytes.len()) {
+            ("script", _) => Self::new(role, CredentialKind::ScriptHash, &bytes),
+            // Extended keys carry a chain code after the 32 bytes public key.
+            ("vk", 32) | ("xvk", 64) => {
+                Self::new(role, CredentialKind::KeyHash, &blake2b_224(&bytes[..32]))
+            }
+            ("vk" | "xvk", len) => Err(invalid(format!("unexpected key length {len}"))),
+            _ => Err(invalid(format!("unknown prefix '{prefix}'"))),
+        }
+    }
+
+    /// Governance role of the credential.
+    pub fn role(&self) -> GovernanceRole {
+        self.role
+    }
+
+    /// Whether the credential is a key hash or a script hash.
+    pub fn kind(&self) -> CredentialKind {
+        self.kind
+    }
+
+    /// Key or script hash as raw bytes.
+    pub fn hash(&self) -> &[u8; CREDENTIAL_HASH_LENGTH] {
+        &self.hash
+    }
+
+    /// Key or script hash in hex.
+    pub fn hash_hex(&self) -> String {
+        hex::encode(self.hash)
+    }
+
+    /// [CIP-129] bech32 ID, with the header byte.
+    ///
+    /// [CIP-129]: https://cips.cardano.org/cip/CIP-0129
+    pub fn to_cip129(&self) -> String {
+        let mut bytes = vec![self.role.key_type() << 4 | self.kind.credential_type()];
+        bytes.extend_from_slice(&self.hash);
+
+        encode(self.role.prefix(), &bytes)
+    }
+
+    /// Legacy [CIP-105] bech32 ID: the role prefix for key hashes, with a `_script` suffix for
+    /// script hashes.
+    ///
+    /// [CIP-105]: https://cips.cardano.org/cip/CIP-0105
+    pub fn to_cip105(&self) -> String {
+        match self.kind {
+            CredentialKind::KeyHash => encode(self.role.prefix(), &self.hash),
+            CredentialKind::ScriptHash => {
+                encode(&format!("{}_script", self.role.prefix()), &self.hash)
+            }
+        }
+    }
+}
+
+impl FromStr for GovernanceCredential {
+    type Err = BlockfrostError;
+
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        Self::parse(id)
+    }
+}
+
+/// Formats the credential as its CIP-129 ID.
+impl fmt::Display for GovernanceCredential {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_cip129())
+    }
+}
+
+/// A governance action, identified by the hash of the transaction proposing it and the index of
+/// the proposal within that transaction.
+///
+/// Parsed from a [CIP-129] `gov_action1…` ID or from `tx_hash#index`, and taken by the
+/// `proposals_*_by_gov_action` endpoints:
+///
+/// ```no_run
+/// # async fn example(api: blockfrost::BlockfrostAPI) -> blockfrost::BlockfrostResult<()> {
+/// use blockfrost::GovActionId;
+///
+/// let id: GovActionId = "gov_action1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqpzklpgpf"
+///     .parse()?;
+/// let proposal = api.proposals_by_gov_action(&id).await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [CIP-129]: https://cips.cardano.org/cip/CIP-0129
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GovActionId {
+    tx_hash: [u8; TX_HASH_LENGTH],
+    index: u16,
+}
+
+impl GovActionId {
+    /// Create a [`GovActionId`] from a hex transaction hash and the proposal index.
+    pub fn new(tx_hash: &str, cert_index: i32) -> BlockfrostResult<Self> {
+        let bytes = hex::decode(tx_hash).map_err(|reason| BlockfrostError::Parsing {
+            message: format!("invalid transaction hash '{tx_hash}': {reason}"),
+        })?;
+        let tx_hash = bytes
+            .try_into()
+            .map_err(|bytes: Vec<u8>| BlockfrostError::Parsing {
+                message: format!(
+                    "transaction hash must be {TX_HASH_LENGTH} bytes long, got {}",
+                    bytes.len()
+                ),
+            })?;
+        let index = u16::try_from(cert_index).map_err(|_| BlockfrostError::Parsing {
+            message: format!("invalid governance action index {cert_index}"),
+        })?;
+
+        Ok(Self { tx_hash, index })
+    }
+
+    #[cfg(feature = "cbor")]
+    pub(crate) fn from_parts(tx_hash: [u8; TX_HASH_LENGTH], index: u16) -> Self {
+        Self { tx_hash, index }
+    }
+
+    /// Parse a [CIP-129] `gov_action1…` ID.
+    ///
+    /// [CIP-129]: https://cips.cardano.org/cip/CIP-0129
+    pub fn from_bech32(id: &str) -> BlockfrostResult<Self> {
+        let invalid = |reason: String| BlockfrostError::Parsing {
+            message: format!("invalid governance action ID '{id}': {reason}"),
+        };
+
+        let (hrp, bytes) = bech32::decode(id).map_err(|reason| invalid(reason.to_string()))?;
+
+        if hrp.as_str() != GOV_ACTION_PREFIX {
+            return Err(invalid(format!("unexpected prefix '{hrp}'")));
+        }
+
+        // The index follows the transaction hash, as a big-endian integer of one or two bytes.
+        let (tx_hash, index) = bytes
+            .split_at_checked(TX_HASH_LENGTH)
+            .filter(|(_, index)| matches!(index.len(), 1 | 2))
+            .ok_or_else(|| invalid(format!("unexpected payload length {}", bytes.len())))?;
+        let index = index
+            .iter()
+            .fold(0, |acc, byte| acc << 8 | u16::from(*byte));
+
+        Ok(Self {
+            // Safety: the split above guarantees the length.
+            tx_hash: tx_hash.try_into().unwrap(),
+            index,
+        })
+    }
+
+    /// Hash of the transaction proposing the action, in hex.
+    pub fn tx_hash(&self) -> String {
+        hex::encode(self.tx_hash)
+    }
+
+    /// Index of the proposal within its transaction, as taken by the proposal endpoints.
+    pub fn cert_index(&self) -> i32 {
+        i32::from(self.index)
+    }
+
+    /// [CIP-129] `gov_action1…` ID.
+    ///
+    /// [CIP-129]: https://cips.cardano.org/cip/CIP-0129
+    pub fn to_bech32(&self) -> String {
+        let mut bytes = self.tx_hash.to_vec();
+
+        match u8::try_from(self.index) {
+            Ok(index) => bytes.push(index),
+            Err(_) => bytes.extend_from_slice(&self.index.to_be_bytes()),
+        }
+
+        encode(GOV_ACTION_PREFIX, &bytes)
+    }
+}
+
+/// Parse a CIP-129 `gov_action1…` ID, or `tx_hash#index`.
+impl FromStr for GovActionId {
+    type Err = BlockfrostError;
+
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        match id.split_once('#') {
+            Some((tx_hash, index)) => {
+                let index = index.parse().map_err(|_| BlockfrostError::Parsing {
+                    message: format!("invalid governance action index in '{id}'"),
+                })?;
+
+                Self::new(tx_hash, index)
+            }
+            None => Self::from_bech32(id),
+        }
+    }
+}
+
+/// Formats the action as its CIP-129 ID.
+impl fmt::Display for GovActionId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_bech32())
+    }
+}
+
+/// DRep ID as sent to the API: CIP-105 and CIP-129 IDs are converted to CIP-129, anything else
+/// (such as hex IDs or the predefined `drep_always_abstain`) is passed through unchanged.
+pub(crate) fn normalize_drep_id(drep_id: &str) -> String {
+    match GovernanceCredential::parse(drep_id) {
+        Ok(credential) if credential.role() == GovernanceRole::DRep => credential.to_cip129(),
+        _ => drep_id.to_string(),
+    }
+}
+
+fn encode(prefix: &str, bytes: &[u8]) -> String {
+    // Safety: the prefixes are valid constants, and the payloads are far below the length limit.
+    bech32::encode::<Bech32>(Hrp::parse_unchecked(prefix), bytes).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const HASH: &str = "db1bc3c3f99ce68977ceaf27ab4dd917123ef9e73f85c304236eab23";
+    const TX_HASH: &str = "2dd15e0ef6e6a17841cb9541c27724072ce4d4b79b91e58432fbaa32d9572531";
+
+    #[rstest]
+    #[case(
+        "drep1ytd3hs7rlxwwdzthe6hj026dmyt3y0heuulctscyydh2kgc9me7as",
+        GovernanceRole::DRep,
+        CredentialKind::KeyHash
+    )]
+    #[case(
+        "drep1y0d3hs7rlxwwdzthe6hj026dmyt3y0heuulctscyydh2kgc9ff0ah",
+        GovernanceRole::DRep,
+        CredentialKind::ScriptHash
+    )]
+    #[case(
+        "cc_hot1qtd3hs7rlxwwdzthe6hj026dmyt3y0heuulctscyydh2kgch0t5wa",
+        GovernanceRole::CommitteeHot,
+        CredentialKind::KeyHash
+    )]
+    #[case(
+        "cc_cold1z0d3hs7rlxwwdzthe6hj026dmyt3y0heuulctscyydh2kgcpp0e5n",
+        GovernanceRole::CommitteeCold,
+        CredentialKind::ScriptHash
+    )]
+    fn test_cip129_round_trip(
+        #[case] id: &str, #[case] role: GovernanceRole, #[case] kind: CredentialKind,
+    ) {
+        let credential: GovernanceCredential = id.parse().unwrap();
+
+        assert_eq!(credential.role(), role);
+        assert_eq!(credential.kind(), kind);
+        assert_eq!(credential.hash_hex(), HASH);
+        assert_eq!(credential.to_string(), id);
+    }
+
+    #[rstest]
+    #[case(
+        "drep1mvdu8slennngja7w4un6knwezufra70887zuxpprd64jxfveahn",
+        "drep1ytd3hs7rlxwwdzthe6hj026dmyt3y0heuulctscyydh2kgc9me7as"
+    )]
+    #[case(
+        "drep_script1mvdu8slennngja7w4un6knwezufra70887zuxpprd64jxxwy4tj",
+        "drep1y0d3hs7rlxwwdzthe6hj026dmyt3y0heuulctscyydh2kgc9ff0ah"
+    )]
+    #[case(
+        "cc_hot1mvdu8slennngja7w4un6knwezufra70887zuxpprd64jx9l56lt",
+        "cc_hot1qtd3hs7rlxwwdzthe6hj026dmyt3y0heuulctscyydh2kgch0t5wa"
+    )]
+    fn test_cip105_conversion(#[case] legacy: &str, #[case] cip129: &str) {
+        let credential = GovernanceCredential::parse(legacy).unwrap();
+
+        assert_eq!(credential.to_cip129(), cip129);
+        assert_eq!(credential.to_cip105(), legacy);
+        assert_eq!(GovernanceCredential::parse(cip129).unwrap(), credential);
+    }
+
+    #[test]
+    fn test_cip105_verification_key() {
+        let key = [7; 32];
+        let vk = encode("drep_vk", &key);
+        let xvk = encode("drep_xvk", &[key, [9; 32]].concat());
+
+        let credential = GovernanceCredential::parse(&vk).unwrap();
+
+        assert_eq!(credential.kind(), CredentialKind::KeyHash);
+        assert_eq!(credential.hash(), &blake2b_224(&key));
+        assert_eq!(GovernanceCredential::parse(&xvk).unwrap(), credential);
+    }
+
+    #[rstest]
+    #[case("not bech32")]
+    // CIP-129 DRep header on a committee prefix.
+    #[case(&encode("cc_hot", &[[0x22].as_slice(), &[0; 28]].concat()))]
+    // Unknown credential type.
+    #[case(&encode("drep", &[[0x24].as_slice(), &[0; 28]].concat()))]
+    #[case(&encode("drep", &[0; 20]))]
+    #[case(&encode("drep_vk", &[0; 28]))]
+    #[case(&encode("stake", &[0; 28]))]
+    fn test_credential_invalid(#[case] id: &str) {
+        assert!(matches!(
+            GovernanceCredential::parse(id),
+            Err(BlockfrostError::Parsing { .. })
+        ));
+    }
+
+    #[rstest]
+    #[case(
+        "drep1mvdu8slennngja7w4un6knwezufra70887zuxpprd64jxfveahn",
+        "drep1ytd3hs7rlxwwdzthe6hj026dmyt3y0heuulctscyydh2kgc9me7as"
+    )]
+    #[case(
+        "drep1ytd3hs7rlxwwdzthe6hj026dmyt3y0heuulctscyydh2kgc9me7as",
+        "drep1ytd3hs7rlxwwdzthe6hj026dmyt3y0heuulctscyydh2kgc9me7as"
+    )]
+    #[case("drep_always_abstain", "drep_always_abstain")]
+    #[case(HASH, HASH)]
+    #[case(
+        "cc_hot1mvdu8slennngja7w4un6knwezufra70887zuxpprd64jx9l56lt",
+        "cc_hot1mvdu8slennngja7w4un6knwezufra70887zuxpprd64jx9l56lt"
+    )]
+    fn test_normalize_drep_id(#[case] drep_id: &str, #[case] expected: &str) {
+        assert_eq!(normalize_drep_id(drep_id), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "gov_action1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqpzklpgpf",
+        &"00".repeat(32),
+        17
+    )]
+    #[case(
+        "gov_action19hg4urhku6shsswtj4quyaeyqukwf49hnwg7tppjlw4r9k2hy5csznq8cv3",
+        TX_HASH,
+        1
+    )]
+    #[case(
+        "gov_action19hg4urhku6shsswtj4quyaeyqukwf49hnwg7tppjlw4r9k2hy5csztqznfp7r",
+        TX_HASH,
+        300
+    )]
+    fn test_gov_action_id(#[case] id: &str, #[case] tx_hash: &str, #[case] cert_index: i32) {
+        let gov_action: GovActionId = id.parse().unwrap();
+
+        assert_eq!(gov_action.tx_hash(), tx_hash);
+        assert_eq!(gov_action.cert_index(), cert_index);
+        assert_eq!(gov_action.to_string(), id);
+        assert_eq!(GovActionId::new(tx_hash, cert_index).unwrap(), gov_action);
+        assert_eq!(
+            format!("{tx_hash}#{cert_index}")
+                .parse::<GovActionId>()
+                .unwrap(),
+            gov_action
+        );
+    }
+
+    #[rstest]
+    #[case("drep1ytd3hs7rlxwwdzthe6hj026dmyt3y0heuulctscyydh2kgc9me7as")]
+    #[case(&encode(GOV_ACTION_PREFIX, &[0; 32]))]
+    #[case(&encode(GOV_ACTION_PREFIX, &[0; 35]))]
+    #[case(&format!("{TX_HASH}#-1"))]
+    #[case(&format!("{TX_HASH}#x"))]
+    #[case("abcd#0")]
+    fn test_gov_action_id_invalid(#[case] id: &str) {
+        assert!(matches!(
+            id.parse::<GovActionId>(),
+            Err(BlockfrostError::Parsing { .. })
+        ));
+    }
+}
@@ -0,0 +1,314 @@
+use crate::{
+    utils::blake2b_256, BlockfrostAPI, BlockfrostError, BlockfrostIPFS, BlockfrostResult,
+    JsonValue, MetadataFetcher,
+};
+use futures::future::Either;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::future::Future;
+
+/// Fetches `ipfs://` URLs through the [Blockfrost IPFS gateway](BlockfrostIPFS::gateway), and
+/// other URLs with `http`.
+#[derive(Debug, Clone)]
+pub struct AnchorFetcher<F> {
+    pub http: F,
+    pub ipfs: BlockfrostIPFS,
+}
+
+impl<F: MetadataFetcher + Sync> MetadataFetcher for AnchorFetcher<F> {
+    fn fetch(&self, url: &str) -> impl Future<Output = BlockfrostResult<Vec<u8>>> + Send {
+        match ipfs_path(url) {
+            Some(ipfs_path) => {
+                let ipfs_path = ipfs_path.to_string();
+
+                Either::Left(async move { self.ipfs.gateway(&ipfs_path).await })
+            }
+            None => Either::Right(self.http.fetch(url)),
+        }
+    }
+}
+
+/// A governance metadata document, following [CIP-100], with a body such as a [`ProposalBody`]
+/// or a [`DRepBody`].
+///
+/// Fetched from the URL of an on-chain anchor and checked against its hash with
+/// [`GovernanceMetadata::fetch`]. JSON-LD values, such as `{"@value": "text"}`, are read as
+/// plain values.
+///
+/// [CIP-100]: https://cips.cardano.org/cip/CIP-0100
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GovernanceMetadata<B> {
+    /// Algorithm used to hash the body for the witnesses, `blake2b-256`.
+    pub hash_algorithm: Option<String>,
+    #[serde(default)]
+    pub authors: Vec<GovernanceAuthor>,
+    pub body: B,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GovernanceAuthor {
+    pub name: Option<String>,
+    pub witness: Option<GovernanceWitness>,
+}
+
+/// Signature of the document body by an author.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GovernanceWitness {
+    /// Such as `ed25519` or `CIP-0008`.
+    pub witness_algorithm: String,
+    pub public_key: String,
+    pub signature: String,
+}
+
+/// A link from a governance document to another resource.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GovernanceReference {
+    /// Such as `Other`, `GovernanceMetadata`, `Link` or `Identity`.
+    #[serde(rename = "@type")]
+    pub kind: String,
+    pub label: String,
+    pub uri: String,
+    pub reference_hash: Option<GovernanceReferenceHash>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GovernanceReferenceHash {
+    pub hash_digest: String,
+    pub hash_algorithm: String,
+}
+
+/// Body of a governance action, as defined by [CIP-108].
+///
+/// [CIP-108]: https://cips.cardano.org/cip/CIP-0108
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalBody {
+    pub title: String,
+    pub r#abstract: String,
+    pub motivation: String,
+    pub rationale: String,
+    #[serde(default)]
+    pub references: Vec<GovernanceReference>,
+}
+
+/// Body of a DRep profile, as defined by [CIP-119].
+///
+/// [CIP-119]: https://cips.cardano.org/cip/CIP-0119
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DRepBody {
+    pub given_name: String,
+    /// Address to receive donations.
+    pub payment_address: Option<String>,
+    pub image: Option<DRepImage>,
+    pub objectives: Option<String>,
+    pub motivations: Option<String>,
+    pub qualifications: Option<String>,
+    /// Whether the DRep asks not to be listed by wallets and tools.
+    pub do_not_list: Option<bool>,
+    #[serde(default)]
+    pub references: Vec<GovernanceReference>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DRepImage {
+    /// URL of the image, or the image itself as a base64 data URL.
+    pub content_url: String,
+    /// SHA-256 hash of the image, in hex, for images given by URL.
+    pub sha256: Option<String>,
+}
+
+impl<B: DeserializeOwned> GovernanceMetadata<B> {
+    /// Parse a document, checking that its blake2b-256 hash is `expected_hash`.
+    ///
+    /// Fails with [`BlockfrostError::HashMismatch`] if it was changed since it was anchored, and
+    /// with [`BlockfrostError::Parsing`] if it is invalid.
+    pub fn from_bytes(bytes: &[u8], expected_hash: &str) -> BlockfrostResult<Self> {
+        let hash = hex::encode(blake2b_256(bytes));
+        if !hash.eq_ignore_ascii_case(expected_hash) {
+            return Err(BlockfrostError::HashMismatch {
+                expected: expected_hash.to_string(),
+                computed: hash,
+            });
+        }
+
+        let parsing_error = |reason: serde_json::Error| BlockfrostError::Parsing {
+            message: format!("invalid governance metadata: {reason}"),
+        };
+        let document = serde_json::from_slice(bytes).map_err(parsing_error)?;
+
+        serde_json::from_value(plain_json_ld_values(document)).map_err(parsing_error)
+    }
+
+    /// Fetch the document of an anchor, from its `url`, and check it against its `hash`.
+    pub async fn fetch(
+        url: &str, hash: &str, fetcher: &impl MetadataFetcher,
+    ) -> BlockfrostResult<Self> {
+        Self::from_bytes(&fetcher.fetch(url).await?, hash)
+    }
+}
+
+impl BlockfrostAPI {
+    /// Return the [CIP-119] profile of a DRep, fetched from the URL of its anchor rather than
+    /// the copy parsed by Blockfrost, and checked against the anchor hash.
+    ///
+    /// Use an [`AnchorFetcher`] to fetch `ipfs://` anchors.
+    ///
+    /// [CIP-119]: https://cips.cardano.org/cip/CIP-0119
+    pub async fn dreps_metadata_verified(
+        &self, drep_id: &str, fetcher: &impl MetadataFetcher,
+    ) -> BlockfrostResult<GovernanceMetadata<DRepBody>> {
+        let anchor = self.dreps_metadata(drep_id).await?;
+
+        GovernanceMetadata::fetch(&anchor.url, &anchor.hash, fetcher).await
+    }
+
+    /// Return the [CIP-108] metadata of a proposal, fetched from the URL of its anchor rather
+    /// than the copy parsed by Blockfrost, and checked against the anchor hash.
+    ///
+    /// Use an [`AnchorFetcher`] to fetch `ipfs://` anchors.
+    ///
+    /// [CIP-108]: https://cips.cardano.org/cip/CIP-0108
+    pub async fn proposals_metadata_verified(
+        &self, tx_hash: &str, cert_index: i32, fetcher: &impl MetadataFetcher,
+    ) -> BlockfrostResult<GovernanceMetadata<ProposalBody>> {
+        let anchor = self.proposals_metadata(tx_hash, cert_index).await?;
+
+        GovernanceMetadata::fetch(&anchor.url, &anchor.hash, fetcher).await
+    }
+}
+
+// Path of an `ipfs://` URL for the gateway, without the optional `ipfs/` prefix.
+fn ipfs_path(url: &str) -> Option<&str> {
+    let path = url.strip_prefix("ipfs://")?;
+
+    Some(path.strip_prefix("ipfs/").unwrap_or(path))
+}
+
+// Replace JSON-LD value objects, `{"@value": value}` with an optional `@language`, by their value.
+fn plain_json_ld_values(value: JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(mut object) => {
+            let is_value_object = object.contains_key("@value")
+                && object
+                    .keys()
+                    .all(|key| matches!(key.as_str(), "@value" | "@language" | "@type"));
+
+            if is_value_object {
+                plain_json_ld_values(object.remove("@value").unwrap_or_default())
+            } else {
+                JsonValue::Object(
+                    object
+                        .into_iter()
+                        .map(|(key, value)| (key, plain_json_ld_values(value)))
+                        .collect(),
+                )
+            }
+        }
+        JsonValue::Array(items) => items.into_iter().map(plain_json_ld_values).collect(),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use serde_json::json;
+
+    const PROPOSAL: &str = r#"{"@context": {"@language": "en-us"}, "hashAlgorithm": "blake2b-256", "authors": [{"name": "Nut", "witness": {"witnessAlgorithm": "ed25519", "publicKey": "7ea09a34aebb13c9841c71397b1cabfec5ddf950405293dee496cac2f437480a", "signature": "a476985b4cc0d457f247797611799a6f6a80fc8cb7ec9dcb5a8223888d0618e30de165f3d869c4a0d9107d8a5b612ad7c5e42441907f5b91796f0d7187d64a01"}}], "body": {"title": {"@value": "Raise the treasury cap"}, "abstract": "More nuts for everyone.", "motivation": "Nuts are scarce.", "rationale": "Because.", "references": [{"@type": "Other", "label": "Forum", "uri": "https://forum.example.com/t/1"}, {"@type": "GovernanceMetadata", "label": "Previous", "uri": "ipfs://QmPrevious", "referenceHash": {"hashDigest": "69c0e5f5e5f5e5f5e5f5e5f5e5f5e5f5e5f5e5f5e5f5e5f5e5f5e5f5e5f5e5f5", "hashAlgorithm": "blake2b-256"}}]}}"#;
+    const PROPOSAL_HASH: &str = "f107bcb7af8dee991000ef13e05e7f1f833affb7d9803abc25f40bc784aa215c";
+
+    const DREP: &str = r#"{"hashAlgorithm": "blake2b-256", "authors": [], "body": {"givenName": "Nut DRep", "paymentAddress": "addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgse35a3x", "image": {"@type": "ImageObject", "contentUrl": "https://drep.example.com/nut.png", "sha256": "2a3e"}, "objectives": "Nuts.", "doNotList": false, "references": [{"@type": "Identity", "label": "X", "uri": "https://x.com/nut"}]}}"#;
+    const DREP_HASH: &str = "d3a59ed404e57ae40624c4d46f7eaacea3a8e098ef357c8789dc5342655b649c";
+
+    #[test]
+    fn test_proposal() {
+        let metadata =
+            GovernanceMetadata::<ProposalBody>::from_bytes(PROPOSAL.as_bytes(), PROPOSAL_HASH)
+                .unwrap();
+
+        assert_eq!(metadata.body.title, "Raise the treasury cap");
+        assert_eq!(metadata.body.r#abstract, "More nuts for everyone.");
+        assert_eq!(metadata.body.references.len(), 2);
+        assert_eq!(metadata.body.references[1].kind, "GovernanceMetadata");
+        assert_eq!(
+            metadata.body.references[1]
+                .reference_hash
+                .as_ref()
+                .unwrap()
+                .hash_algorithm,
+            "blake2b-256"
+        );
+        assert_eq!(
+            metadata.authors[0]
+                .witness
+                .as_ref()
+                .unwrap()
+                .witness_algorithm,
+            "ed25519"
+        );
+    }
+
+    #[test]
+    fn test_drep() {
+        let metadata =
+            GovernanceMetadata::<DRepBody>::from_bytes(DREP.as_bytes(), DREP_HASH).unwrap();
+
+        assert_eq!(metadata.body.given_name, "Nut DRep");
+        assert_eq!(metadata.body.do_not_list, Some(false));
+        assert_eq!(
+            metadata.body.image.unwrap().content_url,
+            "https://drep.example.com/nut.png"
+        );
+        assert_eq!(metadata.body.references[0].kind, "Identity");
+        assert!(metadata.authors.is_empty());
+    }
+
+    #[test]
+    fn test_hash_mismatch() {
+        let result = GovernanceMetadata::<ProposalBody>::from_bytes(DREP.as_bytes(), PROPOSAL_HASH);
+
+        match result {
+            Err(BlockfrostError::HashMismatch { expected, computed }) => {
+                assert_eq!(expected, PROPOSAL_HASH);
+                assert_eq!(computed, DREP_HASH);
+            }
+            result => panic!("expected a hash mismatch, got {result:?}"),
+        }
+
+        // A DRep profile is not a proposal
+        assert!(
+            GovernanceMetadata::<ProposalBody>::from_bytes(DREP.as_bytes(), DREP_HASH).is_err()
+        );
+    }
+
+    #[rstest]
+    #[case("ipfs://QmPrevious", Some("QmPrevious"))]
+    #[case("ipfs://ipfs/QmPrevious", Some("QmPrevious"))]
+    #[case("https://forum.example.com/t/1", None)]
+    fn test_ipfs_path(#[case] url: &str, #[case] expected: Option<&str>) {
+        assert_eq!(ipfs_path(url), expected);
+    }
+
+    #[test]
+    fn test_plain_json_ld_values() {
+        let document = json!({
+            "title": { "@value": "Nuts", "@language": "en" },
+            "list": [{ "@value": 1 }],
+            "reference": { "@type": "Other", "uri": "https://example.com" }
+        });
+
+        assert_eq!(
+            plain_json_ld_values(document),
+            json!({
+                "title": "Nuts",
+                "list": [1],
+                "reference": { "@type": "Other", "uri": "https://example.com" }
+            })
+        );
+    }
+}
@@ -2,23 +2,83 @@
     html_logo_url = "https://raw.githubusercontent.com/blockfrost/blockfrost-rust/master/docs-logo.svg"
 )]
 #![doc = include_str!("../README.md")]
+mod address_discovery;
+mod address_history;
 mod api;
+mod asset;
+mod batch;
+mod bip32;
+mod cbor;
+mod chain_time;
+mod cip25;
+#[cfg(feature = "cbor")]
+mod cip68;
+mod fee;
+mod full_transaction;
+mod governance_id;
+mod governance_metadata;
 mod ipfs;
+mod native_script;
 mod pagination;
+#[cfg(feature = "cbor")]
+mod plutus_data;
+mod pool_analytics;
+mod pool_metadata;
+mod portfolio;
+mod proposal_tally;
 mod request;
+mod script;
 mod settings;
+mod staking_export;
+mod token_registry;
+#[cfg(feature = "cbor")]
+mod transaction;
 mod url;
 mod utils;
+mod watcher;
 
 pub mod error;
 pub mod types;
+pub use address_discovery::*;
+pub use address_history::*;
 pub use api::*;
+pub use asset::*;
+pub use batch::*;
+pub use bip32::*;
+pub use chain_time::*;
+pub use cip25::*;
+#[cfg(feature = "cbor")]
+pub use cip68::*;
 pub use error::*;
+pub use fee::*;
+pub use full_transaction::*;
+pub use governance_id::*;
+pub use governance_metadata::*;
 pub use ipfs::BlockfrostIPFS;
+pub use native_script::*;
 pub use pagination::Order;
 pub use pagination::Pagination;
+#[cfg(feature = "cbor")]
+pub use plutus_data::*;
+pub use pool_analytics::*;
+pub use pool_metadata::*;
+pub use portfolio::*;
+pub use proposal_tally::*;
+pub use script::*;
 pub use settings::*;
+pub use staking_export::*;
+pub use token_registry::*;
+#[cfg(feature = "cbor")]
+pub use transaction::*;
 pub use types::*;
+pub use watcher::*;
+
+#[cfg(feature = "derive")]
+pub use blockfrost_derive::{FromPlutusData, ToPlutusData};
+
+// Lets the derive macros refer to `::blockfrost` from within this crate.
+#[cfg(feature = "derive")]
+extern crate self as blockfrost;
 
 pub const CARDANO_MAINNET_URL: &str = "https://cardano-mainnet.blockfrost.io/api/v0";
 pub const CARDANO_PREPROD_URL: &str = "https://cardano-preprod.blockfrost.io/api/v0";
@@ -0,0 +1,323 @@
+#[cfg(feature = "cbor")]
+use crate::cbor::Decoder;
+use crate::{
+    cbor::Encoder,
+    script::{script_hash, ScriptLanguage},
+    BlockfrostAPI, BlockfrostError, BlockfrostResult, CREDENTIAL_HASH_LENGTH,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// A native (timelock) script, requiring signatures and time constraints.
+///
+/// Parsed from the JSON returned by [`scripts_hash_json`](crate::BlockfrostAPI::scripts_hash_json),
+/// which is the format used by `cardano-cli`, or from CBOR with the `cbor` feature. Its hash is
+/// computed locally, without the feature.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum NativeScript {
+    /// Requires a signature from the key with hash `key_hash`.
+    Sig {
+        #[serde(rename = "keyHash")]
+        key_hash: String,
+    },
+    /// Requires all the scripts to be satisfied.
+    All { scripts: Vec<NativeScript> },
+    /// Requires any of the scripts to be satisfied.
+    Any { scripts: Vec<NativeScript> },
+    /// Requires at least `required` of the scripts to be satisfied.
+    AtLeast {
+        required: u32,
+        scripts: Vec<NativeScript>,
+    },
+    /// Satisfied before `slot`.
+    Before { slot: u64 },
+    /// Satisfied from `slot`.
+    After { slot: u64 },
+}
+
+impl NativeScript {
+    /// Check whether the script is satisfied by signatures from `key_hashes` at `slot`.
+    ///
+    /// The ledger checks time constraints against the validity interval of the transaction, so
+    /// this tells whether a transaction submitted now, with a validity interval around `slot`,
+    /// could spend from the script.
+    pub fn is_satisfied(&self, key_hashes: &HashSet<String>, slot: u64) -> bool {
+        let count = |scripts: &[NativeScript]| {
+            scripts
+                .iter()
+                .filter(|script| script.is_satisfied(key_hashes, slot))
+                .count()
+        };
+
+        match self {
+            NativeScript::Sig { key_hash } => key_hashes.contains(&key_hash.to_lowercase()),
+            NativeScript::All { scripts } => count(scripts) == scripts.len(),
+            NativeScript::Any { scripts } => count(scripts) > 0,
+            NativeScript::AtLeast { required, scripts } => count(scripts) >= *required as usize,
+            NativeScript::Before { slot: before } => slot < *before,
+            NativeScript::After { slot: after } => slot >= *after,
+        }
+    }
+
+    /// Hashes of all the keys the script refers to.
+    pub fn key_hashes(&self) -> HashSet<String> {
+        match self {
+            NativeScript::Sig { key_hash } => HashSet::from([key_hash.to_lowercase()]),
+            NativeScript::All { scripts }
+            | NativeScript::Any { scripts }
+            | NativeScript::AtLeast { scripts, .. } => {
+                scripts.iter().flat_map(NativeScript::key_hashes).collect()
+            }
+            NativeScript::Before { .. } | NativeScript::After { .. } => HashSet::new(),
+        }
+    }
+
+    /// Hash of the script, which is also the policy ID of assets minted by it.
+    ///
+    /// Fails with [`BlockfrostError::Parsing`] if a key hash is not valid.
+    pub fn hash(&self) -> BlockfrostResult<String> {
+        Ok(script_hash(ScriptLanguage::Native, &self.to_cbor()?))
+    }
+
+    /// Decode a native script from its CBOR bytes.
+    #[cfg(feature = "cbor")]
+    pub fn from_cbor(data: &[u8]) -> BlockfrostResult<Self> {
+        let mut decoder = Decoder::new(data);
+        let script = decode_native_script(&mut decoder)?;
+
+        if !decoder.is_empty() {
+            return Err(decoder.error("trailing bytes after native script"));
+        }
+
+        Ok(script)
+    }
+
+    /// Encode the script to CBOR, failing with [`BlockfrostError::Parsing`] if a key hash is not
+    /// 28 bytes of hex.
+    pub fn to_cbor(&self) -> BlockfrostResult<Vec<u8>> {
+        let mut encoder = Encoder::new();
+
+        encode_native_script(&mut encoder, self)?;
+        Ok(encoder.into_bytes())
+    }
+}
+
+impl BlockfrostAPI {
+    /// Return a native script, checking that its hash is `script_hash`.
+    ///
+    /// Fails with [`BlockfrostError::HashMismatch`] if the returned script doesn't hash to
+    /// `script_hash`, and with [`BlockfrostError::Parsing`] if it is not a native script.
+    pub async fn scripts_hash_json_decoded(
+        &self, script_hash: &str,
+    ) -> BlockfrostResult<NativeScript> {
+        let json = self
+            .scripts_hash_json(script_hash)
+            .await?
+            .json
+            .ok_or_else(|| BlockfrostError::Parsing {
+                message: format!("script {script_hash} is not a native script"),
+            })?;
+        let script: NativeScript =
+            serde_json::from_value(json).map_err(|reason| BlockfrostError::Parsing {
+                message: format!("invalid native script: {reason}"),
+            })?;
+
+        let hash = script.hash()?;
+        if !hash.eq_ignore_ascii_case(script_hash) {
+            return Err(BlockfrostError::HashMismatch {
+                expected: script_hash.to_string(),
+                computed: hash,
+            });
+        }
+
+        Ok(script)
+    }
+}
+
+#[cfg(feature = "cbor")]
+fn decode_native_script(decoder: &mut Decoder) -> BlockfrostResult<NativeScript> {
+    decoder.array()?;
+
+    let script = match decoder.u64()? {
+        0 => NativeScript::Sig {
+            key_hash: decoder.hex::<28>()?,
+        },
+        1 => NativeScript::All {
+            scripts: decoder.array_of(decode_native_script)?,
+        },
+        2 => NativeScript::Any {
+            scripts: decoder.array_of(decode_native_script)?,
+        },
+        3 => NativeScript::AtLeast {
+            required: decoder.u32()?,
+            scripts: decoder.array_of(decode_native_script)?,
+        },
+        4 => NativeScript::After {
+            slot: decoder.u64()?,
+        },
+        5 => NativeScript::Before {
+            slot: decoder.u64()?,
+        },
+        kind => return Err(decoder.error(format!("unknown native script kind {kind}"))),
+    };
+
+    Ok(script)
+}
+
+fn encode_native_script(encoder: &mut Encoder, script: &NativeScript) -> BlockfrostResult<()> {
+    match script {
+        NativeScript::Sig { key_hash } => {
+            let invalid = |reason: String| BlockfrostError::Parsing {
+                message: format!("invalid key hash '{key_hash}' in native script: {reason}"),
+            };
+            let key_hash = hex::decode(key_hash).map_err(|reason| invalid(reason.to_string()))?;
+
+            if key_hash.len() != CREDENTIAL_HASH_LENGTH {
+                return Err(invalid(format!(
+                    "expected {CREDENTIAL_HASH_LENGTH} bytes, found {}",
+                    key_hash.len()
+                )));
+            }
+
+            encoder.array(2).u64(0).bytes(&key_hash);
+        }
+        NativeScript::All { scripts } => encode_scripts(encoder.array(2).u64(1), scripts)?,
+        NativeScript::Any { scripts } => encode_scripts(encoder.array(2).u64(2), scripts)?,
+        NativeScript::AtLeast { required, scripts } => {
+            encode_scripts(encoder.array(3).u64(3).u64(u64::from(*required)), scripts)?
+        }
+        NativeScript::After { slot } => {
+            encoder.array(2).u64(4).u64(*slot);
+        }
+        NativeScript::Before { slot } => {
+            encoder.array(2).u64(5).u64(*slot);
+        }
+    }
+
+    Ok(())
+}
+
+fn encode_scripts(encoder: &mut Encoder, scripts: &[NativeScript]) -> BlockfrostResult<()> {
+    encoder.array(scripts.len());
+
+    for script in scripts {
+        encode_native_script(encoder, script)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const KEY_1: &str = "e09d36c79dec9bd1b3d9e152247701cd0bb860b5ebfd1de8abb6735a";
+    const KEY_2: &str = "a687dcc24e00dd3caafbeb5e68f97ca8ef269cb6fe971345eb951756";
+    const KEY_3: &str = "0bd1d702b2e6188fe0857a6dc7ffb0675229bab58c86638ffa87ed6d";
+
+    // 2 of 3 multisig, valid until slot 90000000
+    fn multisig() -> NativeScript {
+        let json_value = json!({
+            "type": "all",
+            "scripts": [
+                {
+                    "type": "atLeast",
+                    "required": 2,
+                    "scripts": [
+                        { "type": "sig", "keyHash": KEY_1 },
+                        { "type": "sig", "keyHash": KEY_2 },
+                        { "type": "sig", "keyHash": KEY_3 }
+                    ]
+                },
+                { "type": "before", "slot": 90000000 }
+            ]
+        });
+
+        serde_json::from_value(json_value).unwrap()
+    }
+
+    fn keys(keys: &[&str]) -> HashSet<String> {
+        keys.iter().map(|key| key.to_string()).collect()
+    }
+
+    #[test]
+    fn test_hash() {
+        let sig = NativeScript::Sig {
+            key_hash: KEY_1.to_string(),
+        };
+        let invalid = NativeScript::Any {
+            scripts: vec![NativeScript::Sig {
+                key_hash: "not hex".to_string(),
+            }],
+        };
+
+        assert_eq!(
+            sig.hash().unwrap(),
+            "208bdcaf2d83ae026964e23659c703a377473168a39cbdc2b0241115"
+        );
+        assert_eq!(
+            multisig().hash().unwrap(),
+            "3b5f72e9c9fdea7f3aa804e829e7c6df7ad62c9b5662fb84c97a9b18"
+        );
+        assert!(matches!(
+            invalid.hash(),
+            Err(BlockfrostError::Parsing { .. })
+        ));
+        // Valid hex, but not a key hash
+        assert!(NativeScript::Sig {
+            key_hash: KEY_1[..54].to_string()
+        }
+        .hash()
+        .is_err());
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_cbor() {
+        let cbor = multisig().to_cbor().unwrap();
+
+        assert_eq!(NativeScript::from_cbor(&cbor).unwrap(), multisig());
+        assert!(NativeScript::from_cbor(&cbor[1..]).is_err());
+        assert!(NativeScript::from_cbor(&[0x82, 0x06, 0x00]).is_err());
+    }
+
+    #[test]
+    fn test_is_satisfied() {
+        let script = multisig();
+
+        assert!(script.is_satisfied(&keys(&[KEY_1, KEY_3]), 89999999));
+        assert!(script.is_satisfied(&keys(&[KEY_1, KEY_2, KEY_3]), 0));
+        // Not enough signatures
+        assert!(!script.is_satisfied(&keys(&[KEY_2]), 0));
+        // Expired
+        assert!(!script.is_satisfied(&keys(&[KEY_1, KEY_2]), 90000000));
+
+        let after = NativeScript::After { slot: 100 };
+        assert!(!after.is_satisfied(&keys(&[]), 99));
+        assert!(after.is_satisfied(&keys(&[]), 100));
+        assert!(NativeScript::All { scripts: vec![] }.is_satisfied(&keys(&[]), 0));
+        assert!(!NativeScript::Any { scripts: vec![] }.is_satisfied(&keys(&[]), 0));
+    }
+
+    #[test]
+    fn test_key_hashes() {
+        assert_eq!(multisig().key_hashes(), keys(&[KEY_1, KEY_2, KEY_3]));
+    }
+
+    #[test]
+    fn test_json() {
+        let json_value = json!({ "type": "after", "slot": 42 });
+
+        assert_eq!(
+            serde_json::from_value::<NativeScript>(json_value.clone()).unwrap(),
+            NativeScript::After { slot: 42 }
+        );
+        assert_eq!(
+            serde_json::to_value(NativeScript::After { slot: 42 }).unwrap(),
+            json_value
+        );
+        assert!(serde_json::from_value::<NativeScript>(json!({ "type": "plutus" })).is_err());
+    }
+}
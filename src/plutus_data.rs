@@ -0,0 +1,703 @@
+use crate::{
+    cbor::{Decoder, Encoder, MAJOR_ARRAY, MAJOR_BYTES, MAJOR_MAP, MAJOR_TAG},
+    utils::blake2b_256,
+    BlockfrostAPI, BlockfrostError, BlockfrostResult, JsonValue,
+};
+use serde::{de, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::BTreeMap, ops::Deref};
+
+// Byte strings are split in chunks of at most 64 bytes, like the ledger does.
+const BYTES_CHUNK_SIZE: usize = 64;
+
+/// Plutus data, as found in datums and redeemers.
+///
+/// Decoded from CBOR with [`PlutusData::from_cbor`], or from the detailed JSON schema used by
+/// [`scripts_datum_hash`](BlockfrostAPI::scripts_datum_hash) with serde. Application types can
+/// be converted from and to Plutus data through [`FromPlutusData`] and [`ToPlutusData`], which
+/// can be derived with the `derive` feature.
+///
+/// Integers are limited to 128 bits, and to 64 bits when decoded from JSON.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PlutusData {
+    /// Constructor of a sum type, with its index and fields.
+    Constr {
+        tag: u64,
+        fields: Vec<PlutusData>,
+    },
+    Map(Vec<(PlutusData, PlutusData)>),
+    List(Vec<PlutusData>),
+    Integer(i128),
+    Bytes(Vec<u8>),
+}
+
+impl PlutusData {
+    /// Decode Plutus data from its CBOR bytes.
+    pub fn from_cbor(data: &[u8]) -> BlockfrostResult<Self> {
+        let mut decoder = Decoder::new(data);
+        let plutus_data = decode_plutus_data(&mut decoder)?;
+
+        if !decoder.is_empty() {
+            return Err(decoder.error("trailing bytes after Plutus data"));
+        }
+
+        Ok(plutus_data)
+    }
+
+    /// Decode Plutus data from hex-encoded CBOR, such as the inline datum of an output.
+    pub fn from_hex(cbor: &str) -> BlockfrostResult<Self> {
+        let data = hex::decode(cbor).map_err(|reason| BlockfrostError::Cbor {
+            message: format!("invalid hex: {reason}"),
+        })?;
+
+        Self::from_cbor(&data)
+    }
+
+    /// Encode Plutus data to CBOR, the way the ledger does.
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new();
+
+        encode_plutus_data(&mut encoder, self);
+        encoder.into_bytes()
+    }
+
+    /// Hash of the CBOR encoding of the data, in hex.
+    ///
+    /// The hash of data decoded from CBOR may differ from the hash of the original bytes, if
+    /// they were not encoded like the ledger does.
+    pub fn hash(&self) -> String {
+        hex::encode(blake2b_256(&self.to_cbor()))
+    }
+
+    /// Decode Plutus data from the detailed JSON schema.
+    pub fn from_json(value: &JsonValue) -> BlockfrostResult<Self> {
+        let invalid = || BlockfrostError::Parsing {
+            message: format!("invalid Plutus data: {value}"),
+        };
+        let object = value.as_object().ok_or_else(invalid)?;
+
+        if let Some(tag) = object.get("constructor") {
+            let fields = object
+                .get("fields")
+                .and_then(JsonValue::as_array)
+                .ok_or_else(invalid)?;
+
+            return Ok(PlutusData::Constr {
+                tag: tag.as_u64().ok_or_else(invalid)?,
+                fields: fields
+                    .iter()
+                    .map(Self::from_json)
+                    .collect::<Result<_, _>>()?,
+            });
+        }
+
+        match object.iter().next() {
+            Some((key, JsonValue::Array(entries))) if key == "map" && object.len() == 1 => {
+                let entry = |entry: &JsonValue| match (entry.get("k"), entry.get("v")) {
+                    (Some(key), Some(value)) => {
+                        Ok((Self::from_json(key)?, Self::from_json(value)?))
+                    }
+                    _ => Err(invalid()),
+                };
+
+                Ok(PlutusData::Map(
+                    entries.iter().map(entry).collect::<Result<_, _>>()?,
+                ))
+            }
+            Some((key, JsonValue::Array(items))) if key == "list" && object.len() == 1 => {
+                Ok(PlutusData::List(
+                    items
+                        .iter()
+                        .map(Self::from_json)
+                        .collect::<Result<_, _>>()?,
+                ))
+            }
+            Some((key, JsonValue::Number(number))) if key == "int" && object.len() == 1 => {
+                let integer = number
+                    .as_i64()
+                    .map(i128::from)
+                    .or_else(|| number.as_u64().map(i128::from))
+                    .ok_or_else(invalid)?;
+
+                Ok(PlutusData::Integer(integer))
+            }
+            Some((key, JsonValue::String(bytes))) if key == "bytes" && object.len() == 1 => Ok(
+                PlutusData::Bytes(hex::decode(bytes).map_err(|_| invalid())?),
+            ),
+            _ => Err(invalid()),
+        }
+    }
+
+    pub fn as_constr(&self) -> Option<(u64, &[PlutusData])> {
+        match self {
+            PlutusData::Constr { tag, fields } => Some((*tag, fields)),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&[(PlutusData, PlutusData)]> {
+        match self {
+            PlutusData::Map(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[PlutusData]> {
+        match self {
+            PlutusData::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            PlutusData::Integer(integer) => Some(*integer),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            PlutusData::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Index of the constructor, or an error if the data is not a constructor.
+    pub fn constr_tag(&self) -> BlockfrostResult<u64> {
+        self.as_constr()
+            .map(|(tag, _)| tag)
+            .ok_or_else(|| self.unexpected("a constructor"))
+    }
+
+    /// Fields of the constructor `tag`, checking that there are `arity` of them.
+    pub fn expect_constr(&self, tag: u64, arity: usize) -> BlockfrostResult<&[PlutusData]> {
+        match self.as_constr() {
+            Some((found, fields)) if found == tag && fields.len() == arity => Ok(fields),
+            _ => Err(self.unexpected(&format!("constructor {tag} with {arity} fields"))),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> BlockfrostError {
+        BlockfrostError::Parsing {
+            message: format!("expected {expected}, found Plutus data {self:?}"),
+        }
+    }
+}
+
+impl Serialize for PlutusData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct MapEntry<'a> {
+            k: &'a PlutusData,
+            v: &'a PlutusData,
+        }
+
+        let mut map = serializer.serialize_map(None)?;
+
+        match self {
+            PlutusData::Constr { tag, fields } => {
+                map.serialize_entry("constructor", tag)?;
+                map.serialize_entry("fields", fields)?;
+            }
+            PlutusData::Map(entries) => {
+                let entries: Vec<_> = entries.iter().map(|(k, v)| MapEntry { k, v }).collect();
+                map.serialize_entry("map", &entries)?;
+            }
+            PlutusData::List(items) => map.serialize_entry("list", items)?,
+            PlutusData::Integer(integer) => map.serialize_entry("int", integer)?,
+            PlutusData::Bytes(bytes) => map.serialize_entry("bytes", &hex::encode(bytes))?,
+        }
+
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for PlutusData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = JsonValue::deserialize(deserializer)?;
+
+        Self::from_json(&value).map_err(de::Error::custom)
+    }
+}
+
+/// Conversion from [`PlutusData`], derivable with the `derive` feature.
+///
+/// Derived structs are read from constructor `0`, and enum variants from the constructor of
+/// their index. Use `#[plutus(constr = N)]` to pick another constructor.
+pub trait FromPlutusData: Sized {
+    fn from_plutus_data(data: &PlutusData) -> BlockfrostResult<Self>;
+}
+
+/// Conversion to [`PlutusData`], derivable with the `derive` feature.
+pub trait ToPlutusData {
+    fn to_plutus_data(&self) -> PlutusData;
+}
+
+/// Byte string, converted to and from [`PlutusData::Bytes`].
+///
+/// Fixed-size byte strings such as hashes can also use `[u8; N]`, while `Vec<T>` is a list.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BoundedBytes(pub Vec<u8>);
+
+impl Deref for BoundedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Vec<u8>> for BoundedBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl FromPlutusData for PlutusData {
+    fn from_plutus_data(data: &PlutusData) -> BlockfrostResult<Self> {
+        Ok(data.clone())
+    }
+}
+
+impl ToPlutusData for PlutusData {
+    fn to_plutus_data(&self) -> PlutusData {
+        self.clone()
+    }
+}
+
+macro_rules! impl_plutus_data_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl FromPlutusData for $integer {
+                fn from_plutus_data(data: &PlutusData) -> BlockfrostResult<Self> {
+                    data.as_integer()
+                        .and_then(|integer| <$integer>::try_from(integer).ok())
+                        .ok_or_else(|| data.unexpected(stringify!($integer)))
+                }
+            }
+
+            impl ToPlutusData for $integer {
+                fn to_plutus_data(&self) -> PlutusData {
+                    PlutusData::Integer(i128::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+impl_plutus_data_integer!(i32, i64, i128, u32, u64);
+
+impl FromPlutusData for BoundedBytes {
+    fn from_plutus_data(data: &PlutusData) -> BlockfrostResult<Self> {
+        data.as_bytes()
+            .map(|bytes| BoundedBytes(bytes.to_vec()))
+            .ok_or_else(|| data.unexpected("bytes"))
+    }
+}
+
+impl ToPlutusData for BoundedBytes {
+    fn to_plutus_data(&self) -> PlutusData {
+        PlutusData::Bytes(self.0.clone())
+    }
+}
+
+impl<const N: usize> FromPlutusData for [u8; N] {
+    fn from_plutus_data(data: &PlutusData) -> BlockfrostResult<Self> {
+        data.as_bytes()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| data.unexpected(&format!("{N} bytes")))
+    }
+}
+
+impl<const N: usize> ToPlutusData for [u8; N] {
+    fn to_plutus_data(&self) -> PlutusData {
+        PlutusData::Bytes(self.to_vec())
+    }
+}
+
+// Booleans and optional values follow the encoding of `Bool` and `Maybe` in Plutus.
+impl FromPlutusData for bool {
+    fn from_plutus_data(data: &PlutusData) -> BlockfrostResult<Self> {
+        match data.constr_tag()? {
+            0 => data.expect_constr(0, 0).map(|_| false),
+            _ => data.expect_constr(1, 0).map(|_| true),
+        }
+    }
+}
+
+impl ToPlutusData for bool {
+    fn to_plutus_data(&self) -> PlutusData {
+        PlutusData::Constr {
+            tag: u64::from(*self),
+            fields: Vec::new(),
+        }
+    }
+}
+
+impl<T: FromPlutusData> FromPlutusData for Option<T> {
+    fn from_plutus_data(data: &PlutusData) -> BlockfrostResult<Self> {
+        match data.constr_tag()? {
+            0 => T::from_plutus_data(&data.expect_constr(0, 1)?[0]).map(Some),
+            _ => data.expect_constr(1, 0).map(|_| None),
+        }
+    }
+}
+
+impl<T: ToPlutusData> ToPlutusData for Option<T> {
+    fn to_plutus_data(&self) -> PlutusData {
+        match self {
+            Some(value) => PlutusData::Constr {
+                tag: 0,
+                fields: vec![value.to_plutus_data()],
+            },
+            None => PlutusData::Constr {
+                tag: 1,
+                fields: Vec::new(),
+            },
+        }
+    }
+}
+
+impl<T: FromPlutusData> FromPlutusData for Box<T> {
+    fn from_plutus_data(data: &PlutusData) -> BlockfrostResult<Self> {
+        T::from_plutus_data(data).map(Box::new)
+    }
+}
+
+impl<T: ToPlutusData> ToPlutusData for Box<T> {
+    fn to_plutus_data(&self) -> PlutusData {
+        self.as_ref().to_plutus_data()
+    }
+}
+
+impl<T: FromPlutusData> FromPlutusData for Vec<T> {
+    fn from_plutus_data(data: &PlutusData) -> BlockfrostResult<Self> {
+        data.as_list()
+            .ok_or_else(|| data.unexpected("a list"))?
+            .iter()
+            .map(T::from_plutus_data)
+            .collect()
+    }
+}
+
+impl<T: ToPlutusData> ToPlutusData for Vec<T> {
+    fn to_plutus_data(&self) -> PlutusData {
+        PlutusData::List(self.iter().map(T::to_plutus_data).collect())
+    }
+}
+
+impl<K: FromPlutusData + Ord, V: FromPlutusData> FromPlutusData for BTreeMap<K, V> {
+    fn from_plutus_data(data: &PlutusData) -> BlockfrostResult<Self> {
+        data.as_map()
+            .ok_or_else(|| data.unexpected("a map"))?
+            .iter()
+            .map(|(key, value)| Ok((K::from_plutus_data(key)?, V::from_plutus_data(value)?)))
+            .collect()
+    }
+}
+
+impl<K: ToPlutusData, V: ToPlutusData> ToPlutusData for BTreeMap<K, V> {
+    fn to_plutus_data(&self) -> PlutusData {
+        PlutusData::Map(
+            self.iter()
+                .map(|(key, value)| (key.to_plutus_data(), value.to_plutus_data()))
+                .collect(),
+        )
+    }
+}
+
+impl BlockfrostAPI {
+    /// Return a datum by its hash, decoded from its CBOR.
+    ///
+    /// Fails with [`BlockfrostError::HashMismatch`] if the hash of the returned datum is not
+    /// `datum_hash`.
+    pub async fn scripts_datum_hash_decoded(
+        &self, datum_hash: &str,
+    ) -> BlockfrostResult<PlutusData> {
+        let response = self.scripts_datum_hash_cbor(datum_hash).await?;
+        let cbor = response
+            .get("cbor")
+            .and_then(JsonValue::as_str)
+            .ok_or_else(|| BlockfrostError::Parsing {
+                message: format!("missing datum CBOR in {response}"),
+            })?;
+        let data = hex::decode(cbor).map_err(|reason| BlockfrostError::Cbor {
+            message: format!("invalid hex: {reason}"),
+        })?;
+
+        // Hash the original bytes, which may not be encoded like the ledger would.
+        let hash = hex::encode(blake2b_256(&data));
+        if !hash.eq_ignore_ascii_case(datum_hash) {
+            return Err(BlockfrostError::HashMismatch {
+                expected: datum_hash.to_string(),
+                computed: hash,
+            });
+        }
+
+        PlutusData::from_cbor(&data)
+    }
+}
+
+fn decode_plutus_data(decoder: &mut Decoder) -> BlockfrostResult<PlutusData> {
+    match decoder.peek_major()? {
+        MAJOR_TAG => {
+            // Constructors 0 to 6 and 7 to 127 have compact tags, the others are tagged with 102.
+            let tag = match decoder.clone().tag()? {
+                2 | 3 => return Ok(PlutusData::Integer(decoder.int()?)),
+                tag @ 121..=127 => {
+                    decoder.tag()?;
+                    tag - 121
+                }
+                tag @ 1280..=1400 => {
+                    decoder.tag()?;
+                    tag - 1280 + 7
+                }
+                102 => {
+                    decoder.tag()?;
+                    decoder.array()?;
+                    decoder.u64()?
+                }
+                tag => return Err(decoder.error(format!("unexpected tag {tag} in Plutus data"))),
+            };
+
+            Ok(PlutusData::Constr {
+                tag,
+                fields: decoder.array_of(decode_plutus_data)?,
+            })
+        }
+        MAJOR_MAP => Ok(PlutusData::Map(
+            decoder.map_of(decode_plutus_data, decode_plutus_data)?,
+        )),
+        MAJOR_ARRAY => Ok(PlutusData::List(decoder.array_of(decode_plutus_data)?)),
+        MAJOR_BYTES => Ok(PlutusData::Bytes(decoder.bytes()?)),
+        _ => Ok(PlutusData::Integer(decoder.int()?)),
+    }
+}
+
+fn encode_plutus_data(encoder: &mut Encoder, data: &PlutusData) {
+    match data {
+        PlutusData::Constr { tag, fields } => {
+            match tag {
+                0..=6 => {
+                    encoder.tag(121 + tag);
+                }
+                7..=127 => {
+                    encoder.tag(1280 + tag - 7);
+                }
+                _ => {
+                    encoder.tag(102).array(2).u64(*tag);
+                }
+            }
+            encode_list(encoder, fields);
+        }
+        PlutusData::Map(entries) => {
+            encoder.map(entries.len());
+
+            for (key, value) in entries {
+                encode_plutus_data(encoder, key);
+                encode_plutus_data(encoder, value);
+            }
+        }
+        PlutusData::List(items) => encode_list(encoder, items),
+        PlutusData::Integer(integer) => {
+            encoder.int(*integer);
+        }
+        PlutusData::Bytes(bytes) if bytes.len() <= BYTES_CHUNK_SIZE => {
+            encoder.bytes(bytes);
+        }
+        PlutusData::Bytes(bytes) => {
+            encoder.begin_indefinite(MAJOR_BYTES);
+
+            for chunk in bytes.chunks(BYTES_CHUNK_SIZE) {
+                encoder.bytes(chunk);
+            }
+            encoder.end();
+        }
+    }
+}
+
+// Non-empty lists are encoded with an indefinite length.
+fn encode_list(encoder: &mut Encoder, items: &[PlutusData]) {
+    if items.is_empty() {
+        encoder.array(0);
+        return;
+    }
+
+    encoder.begin_indefinite(MAJOR_ARRAY);
+    for item in items {
+        encode_plutus_data(encoder, item);
+    }
+    encoder.end();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use serde_json::json;
+
+    fn constr(tag: u64, fields: Vec<PlutusData>) -> PlutusData {
+        PlutusData::Constr { tag, fields }
+    }
+
+    #[rstest]
+    #[case(constr(0, vec![]), "d87980")]
+    #[case(constr(1, vec![PlutusData::Integer(42)]), "d87a9f182aff")]
+    #[case(constr(7, vec![]), "d9050080")]
+    #[case(constr(127, vec![]), "d9057880")]
+    #[case(constr(200, vec![]), "d8668218c880")]
+    #[case(PlutusData::List(vec![]), "80")]
+    #[case(PlutusData::List(vec![PlutusData::Integer(-1)]), "9f20ff")]
+    #[case(
+        PlutusData::Map(vec![(PlutusData::Bytes(vec![0xca, 0xfe]), PlutusData::Integer(1))]),
+        "a142cafe01"
+    )]
+    #[case(PlutusData::Integer(18446744073709551616), "c249010000000000000000")]
+    #[case(PlutusData::Bytes(vec![0xab; 65]), &format!("5f5840{}41abff", "ab".repeat(64)))]
+    fn test_cbor(#[case] data: PlutusData, #[case] cbor: &str) {
+        assert_eq!(hex::encode(data.to_cbor()), cbor);
+        assert_eq!(PlutusData::from_hex(cbor).unwrap(), data);
+    }
+
+    #[rstest]
+    #[case(i128::MAX)]
+    #[case(i128::MIN)]
+    #[case(1 << 120)]
+    #[case(-1 << 120)]
+    fn test_cbor_large_integers(#[case] value: i128) {
+        let data = PlutusData::Integer(value);
+
+        assert_eq!(PlutusData::from_cbor(&data.to_cbor()).unwrap(), data);
+    }
+
+    #[test]
+    fn test_cbor_definite_lists() {
+        // Lists may also have a definite length
+        assert_eq!(
+            PlutusData::from_hex("d87a81182a").unwrap(),
+            constr(1, vec![PlutusData::Integer(42)])
+        );
+        assert!(PlutusData::from_hex("d87a81182a00").is_err());
+        assert!(PlutusData::from_hex("d90102").is_err());
+    }
+
+    #[test]
+    fn test_json() {
+        let json_value = json!({
+            "constructor": 0,
+            "fields": [
+                { "bytes": "cafe" },
+                { "int": -5 },
+                { "list": [{ "int": 1 }] },
+                { "map": [{ "k": { "bytes": "" }, "v": { "int": 2 } }] }
+            ]
+        });
+        let data = serde_json::from_value::<PlutusData>(json_value.clone()).unwrap();
+
+        assert_eq!(
+            data,
+            constr(
+                0,
+                vec![
+                    PlutusData::Bytes(vec![0xca, 0xfe]),
+                    PlutusData::Integer(-5),
+                    PlutusData::List(vec![PlutusData::Integer(1)]),
+                    PlutusData::Map(vec![(PlutusData::Bytes(vec![]), PlutusData::Integer(2))]),
+                ]
+            )
+        );
+        assert_eq!(serde_json::to_value(&data).unwrap(), json_value);
+        assert!(serde_json::from_value::<PlutusData>(json!({ "int": "1" })).is_err());
+        assert!(serde_json::from_value::<PlutusData>(json!({ "bytes": "zz" })).is_err());
+    }
+
+    #[test]
+    fn test_hash() {
+        // Hash of the unit datum
+        assert_eq!(
+            constr(0, vec![]).hash(),
+            "923918e403bf43c34b4ef6b48eb2ee04babed17320d8d1b9ff9ad086e86f44ec"
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        let value: Option<BTreeMap<u64, Vec<bool>>> =
+            Some(BTreeMap::from([(1, vec![true, false])]));
+        let data = value.to_plutus_data();
+
+        assert_eq!(
+            data,
+            constr(
+                0,
+                vec![PlutusData::Map(vec![(
+                    PlutusData::Integer(1),
+                    PlutusData::List(vec![constr(1, vec![]), constr(0, vec![])]),
+                )])]
+            )
+        );
+        assert_eq!(FromPlutusData::from_plutus_data(&data).ok(), Some(value));
+        assert!(u32::from_plutus_data(&PlutusData::Integer(-1)).is_err());
+        assert!(<[u8; 2]>::from_plutus_data(&PlutusData::Bytes(vec![1])).is_err());
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive() {
+        use crate::{FromPlutusData, ToPlutusData};
+
+        #[derive(Debug, PartialEq, FromPlutusData, ToPlutusData)]
+        enum OrderStep {
+            Swap {
+                direction: bool,
+                min_receive: u64,
+            },
+            Deposit(u64),
+            #[plutus(constr = 5)]
+            Cancel,
+        }
+
+        #[derive(Debug, PartialEq, FromPlutusData, ToPlutusData)]
+        struct OrderDatum {
+            sender: [u8; 28],
+            asset_name: BoundedBytes,
+            step: OrderStep,
+            batcher_fee: Option<u64>,
+        }
+
+        let datum = OrderDatum {
+            sender: [1; 28],
+            asset_name: BoundedBytes(b"TOKEN".to_vec()),
+            step: OrderStep::Swap {
+                direction: true,
+                min_receive: 1000,
+            },
+            batcher_fee: None,
+        };
+        let data = datum.to_plutus_data();
+
+        assert_eq!(
+            data,
+            constr(
+                0,
+                vec![
+                    PlutusData::Bytes(vec![1; 28]),
+                    PlutusData::Bytes(b"TOKEN".to_vec()),
+                    constr(0, vec![constr(1, vec![]), PlutusData::Integer(1000)]),
+                    constr(1, vec![]),
+                ]
+            )
+        );
+        assert_eq!(
+            OrderDatum::from_plutus_data(&PlutusData::from_cbor(&data.to_cbor()).unwrap()).unwrap(),
+            datum
+        );
+        assert_eq!(OrderStep::Cancel.to_plutus_data(), constr(5, vec![]));
+        assert_eq!(
+            OrderStep::from_plutus_data(&constr(1, vec![PlutusData::Integer(3)])).unwrap(),
+            OrderStep::Deposit(3)
+        );
+        assert!(OrderStep::from_plutus_data(&constr(2, vec![])).is_err());
+        assert!(OrderDatum::from_plutus_data(&constr(0, vec![])).is_err());
+    }
+}
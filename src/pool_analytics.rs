@@ -0,0 +1,462 @@
+use crate::{utils::parse_lovelace, BlockfrostAPI, BlockfrostError, BlockfrostResult, Pagination};
+use blockfrost_openapi::models::{
+    epoch_param_content::EpochParamContent, genesis_content::GenesisContent, network::Network,
+    pool::Pool, pool_history_inner::PoolHistoryInner,
+};
+use futures::{stream, StreamExt, TryStreamExt};
+use std::collections::HashMap;
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+
+/// Network parameters used to analyze pool performance.
+///
+/// Built from [`genesis`](BlockfrostAPI::genesis), the current protocol parameters of
+/// [`epochs_latest_parameters`](BlockfrostAPI::epochs_latest_parameters) and
+/// [`network`](BlockfrostAPI::network) with [`PoolAnalyticsParams::new`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolAnalyticsParams {
+    /// Number of slots in an epoch.
+    pub epoch_length: u64,
+    /// Length of a slot, in seconds.
+    pub slot_length: u64,
+    /// Probability that a slot has a block.
+    pub active_slots_coefficient: f64,
+    /// Current desired number of pools (`n_opt`), which sets the saturation point.
+    pub k: u64,
+    /// Lovelace that can be staked, the maximum supply minus the reserves.
+    pub total_stake: u64,
+}
+
+impl PoolAnalyticsParams {
+    pub fn new(
+        genesis: &GenesisContent, params: &EpochParamContent, network: &Network,
+    ) -> BlockfrostResult<Self> {
+        let max_supply = parse_lovelace(&network.supply.max)?;
+        let reserves = parse_lovelace(&network.supply.reserves)?;
+
+        Ok(Self {
+            epoch_length: positive("epoch_length", genesis.epoch_length)?,
+            slot_length: positive("slot_length", genesis.slot_length)?,
+            active_slots_coefficient: genesis.active_slots_coefficient,
+            k: positive("n_opt", params.n_opt)?,
+            total_stake: max_supply.saturating_sub(reserves),
+        })
+    }
+
+    /// Number of blocks a pool with `active_size` of the active stake is expected to mint in an
+    /// epoch with the given decentralization parameter `d`.
+    ///
+    /// Until `d` reached zero, only `1 - d` of the blocks were minted by stake pools, the others
+    /// by the genesis nodes.
+    pub fn expected_blocks(&self, active_size: f64, decentralization: f64) -> f64 {
+        self.epoch_length as f64
+            * self.active_slots_coefficient
+            * (1.0 - decentralization).clamp(0.0, 1.0)
+            * active_size
+    }
+
+    /// Number of epochs in a year, `73` on mainnet.
+    pub fn epochs_per_year(&self) -> f64 {
+        SECONDS_PER_YEAR / (self.epoch_length * self.slot_length) as f64
+    }
+
+    /// Stake above which the rewards of a pool stop growing, in lovelace.
+    pub fn saturation_point(&self) -> u64 {
+        self.total_stake / self.k
+    }
+}
+
+/// Performance of a pool in a single epoch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolEpochPerformance {
+    pub epoch: u32,
+    /// Stake delegated to the pool for the epoch, in lovelace.
+    pub active_stake: u64,
+    /// Share of the total active stake delegated to the pool.
+    pub active_size: f64,
+    /// Blocks minted by the pool.
+    pub blocks: u64,
+    pub expected_blocks: f64,
+    /// Decentralization parameter `d` of the epoch.
+    pub decentralization: f64,
+    /// Desired number of pools (`n_opt`) of the epoch.
+    pub k: u64,
+    /// Total rewards of the pool, in lovelace.
+    pub rewards: u64,
+    /// Rewards kept by the operator, its fixed cost and margin, in lovelace.
+    pub fees: u64,
+}
+
+impl PoolEpochPerformance {
+    /// Read an epoch of [`pools_history`](BlockfrostAPI::pools_history), with the protocol
+    /// parameters of that epoch from [`epochs_parameters`](BlockfrostAPI::epochs_parameters).
+    pub fn new(
+        history: &PoolHistoryInner, epoch_params: &EpochParamContent, params: &PoolAnalyticsParams,
+    ) -> BlockfrostResult<Self> {
+        if epoch_params.epoch != history.epoch {
+            return Err(BlockfrostError::Parsing {
+                message: format!(
+                    "parameters of epoch {} given for epoch {}",
+                    epoch_params.epoch, history.epoch
+                ),
+            });
+        }
+
+        let decentralization = epoch_params.decentralisation_param;
+
+        Ok(Self {
+            epoch: u32::try_from(history.epoch).map_err(|_| BlockfrostError::Parsing {
+                message: format!("invalid epoch {}", history.epoch),
+            })?,
+            active_stake: parse_lovelace(&history.active_stake)?,
+            active_size: history.active_size,
+            blocks: u64::try_from(history.blocks).unwrap_or_default(),
+            expected_blocks: params.expected_blocks(history.active_size, decentralization),
+            decentralization,
+            k: positive("n_opt", epoch_params.n_opt)?,
+            rewards: parse_lovelace(&history.rewards)?,
+            fees: parse_lovelace(&history.fees)?,
+        })
+    }
+
+    /// Rewards shared by the delegators, after the operator's fixed cost and margin.
+    pub fn delegator_rewards(&self) -> u64 {
+        self.rewards.saturating_sub(self.fees)
+    }
+
+    /// Minted blocks as a percentage of the expected ones, `None` if none were expected.
+    pub fn luck(&self) -> Option<f64> {
+        luck(self.blocks, self.expected_blocks)
+    }
+
+    /// Annualized return of delegators, as a percentage of their stake.
+    ///
+    /// `None` if the pool had no active stake.
+    pub fn roa(&self, params: &PoolAnalyticsParams) -> Option<f64> {
+        roa(
+            self.delegator_rewards(),
+            self.active_stake,
+            params.epochs_per_year(),
+        )
+    }
+
+    /// Active stake as a share of the saturation point of the epoch, from its `k` and the
+    /// current [`total_stake`](PoolAnalyticsParams::total_stake).
+    pub fn saturation(&self, params: &PoolAnalyticsParams) -> f64 {
+        self.active_stake as f64 / (params.total_stake / self.k) as f64
+    }
+}
+
+/// Performance of a pool over its lifetime, returned by
+/// [`pool_analytics`](BlockfrostAPI::pool_analytics).
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolAnalytics {
+    pub pool_id: String,
+    pub params: PoolAnalyticsParams,
+    /// Performance in each epoch the pool had active stake, oldest first.
+    pub epochs: Vec<PoolEpochPerformance>,
+    /// Stake currently delegated to the pool, in lovelace.
+    pub live_stake: u64,
+}
+
+impl PoolAnalytics {
+    /// Combine a pool, its history and the protocol parameters of every epoch of the history.
+    pub fn new(
+        pool: &Pool, history: &[PoolHistoryInner], epoch_params: &[EpochParamContent],
+        params: PoolAnalyticsParams,
+    ) -> BlockfrostResult<Self> {
+        let epoch_params: HashMap<i32, &EpochParamContent> = epoch_params
+            .iter()
+            .map(|epoch_params| (epoch_params.epoch, epoch_params))
+            .collect();
+        let mut epochs = history
+            .iter()
+            .map(|history| {
+                let epoch_params =
+                    epoch_params
+                        .get(&history.epoch)
+                        .ok_or_else(|| BlockfrostError::Parsing {
+                            message: format!("missing parameters of epoch {}", history.epoch),
+                        })?;
+
+                PoolEpochPerformance::new(history, epoch_params, &params)
+            })
+            .collect::<BlockfrostResult<Vec<_>>>()?;
+
+        epochs.sort_by_key(|epoch| epoch.epoch);
+
+        Ok(Self {
+            pool_id: pool.pool_id.clone(),
+            live_stake: parse_lovelace(&pool.live_stake)?,
+            params,
+            epochs,
+        })
+    }
+
+    /// Blocks minted over the lifetime of the pool.
+    pub fn blocks(&self) -> u64 {
+        self.epochs.iter().map(|epoch| epoch.blocks).sum()
+    }
+
+    pub fn expected_blocks(&self) -> f64 {
+        self.epochs.iter().map(|epoch| epoch.expected_blocks).sum()
+    }
+
+    /// Lifetime luck, see [`PoolEpochPerformance::luck`].
+    pub fn luck(&self) -> Option<f64> {
+        luck(self.blocks(), self.expected_blocks())
+    }
+
+    /// Lifetime annualized return of delegators, weighted by the stake of each epoch.
+    ///
+    /// Rewards of the latest epochs are only known once they are distributed, so the history
+    /// may need to be trimmed for an accurate result.
+    pub fn roa(&self) -> Option<f64> {
+        roa(
+            self.epochs
+                .iter()
+                .map(|epoch| epoch.delegator_rewards())
+                .sum(),
+            self.epochs.iter().map(|epoch| epoch.active_stake).sum(),
+            self.params.epochs_per_year(),
+        )
+    }
+
+    /// Live stake as a share of the current saturation point, above `1.0` for oversaturated
+    /// pools.
+    pub fn saturation(&self) -> f64 {
+        self.live_stake as f64 / self.params.saturation_point() as f64
+    }
+}
+
+impl BlockfrostAPI {
+    /// Return the analytics of a pool: its expected and minted blocks, luck and returns in each
+    /// epoch of its history, and its saturation.
+    ///
+    /// Combines [`pools_by_id`](Self::pools_by_id), [`pools_history`](Self::pools_history),
+    /// [`genesis`](Self::genesis), [`epochs_latest_parameters`](Self::epochs_latest_parameters)
+    /// and [`network`](Self::network), then looks up the `k` and decentralization parameter of
+    /// every epoch of the history with [`epochs_parameters`](Self::epochs_parameters), with at
+    /// most [`batch_concurrency`](crate::BlockFrostSettings::batch_concurrency) requests at a
+    /// time. Long-lived pools need one request per epoch.
+    pub async fn pool_analytics(&self, pool_id: &str) -> BlockfrostResult<PoolAnalytics> {
+        let (pool, history, genesis, params, network) = futures::try_join!(
+            self.pools_by_id(pool_id),
+            self.pools_history(pool_id, Pagination::all()),
+            self.genesis(),
+            self.epochs_latest_parameters(),
+            self.network()
+        )?;
+
+        let epoch_params: Vec<EpochParamContent> = stream::iter(&history)
+            .map(|history| self.epochs_parameters(history.epoch))
+            .buffered(self.batch_concurrency())
+            .try_collect()
+            .await?;
+
+        PoolAnalytics::new(
+            &pool,
+            &history,
+            &epoch_params,
+            PoolAnalyticsParams::new(&genesis, &params, &network)?,
+        )
+    }
+}
+
+fn positive(name: &str, value: i32) -> BlockfrostResult<u64> {
+    u64::try_from(value)
+        .ok()
+        .filter(|value| *value > 0)
+        .ok_or_else(|| BlockfrostError::Parsing {
+            message: format!("network parameter '{name}' is missing or invalid"),
+        })
+}
+
+fn luck(blocks: u64, expected_blocks: f64) -> Option<f64> {
+    (expected_blocks > 0.0).then(|| blocks as f64 / expected_blocks * 100.0)
+}
+
+// Simple annualization, without compounding the rewards of each epoch.
+fn roa(delegator_rewards: u64, active_stake: u64, epochs_per_year: f64) -> Option<f64> {
+    (active_stake > 0)
+        .then(|| delegator_rewards as f64 / active_stake as f64 * epochs_per_year * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use serde_json::json;
+
+    const POOL_ID: &str = "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy";
+
+    // Mainnet values, with 38B ADA of stake for a saturation point of 76M ADA.
+    fn params() -> PoolAnalyticsParams {
+        let genesis = json!({
+            "active_slots_coefficient": 0.05,
+            "update_quorum": 5,
+            "max_lovelace_supply": "45000000000000000",
+            "network_magic": 764824073,
+            "epoch_length": 432000,
+            "system_start": 1506203091,
+            "slots_per_kes_period": 129600,
+            "slot_length": 1,
+            "max_kes_evolutions": 62,
+            "security_param": 2160
+        });
+        let network = json!({
+            "supply": {
+                "max": "45000000000000000",
+                "total": "38000000000000000",
+                "circulating": "37500000000000000",
+                "locked": "125006953355",
+                "treasury": "1500000000000000",
+                "reserves": "7000000000000000"
+            },
+            "stake": {
+                "live": "23204950463991654",
+                "active": "22210233523456321"
+            }
+        });
+        let params = EpochParamContent {
+            n_opt: 500,
+            ..Default::default()
+        };
+
+        PoolAnalyticsParams::new(
+            &serde_json::from_value(genesis).unwrap(),
+            &params,
+            &serde_json::from_value(network).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn epoch_params(epoch: i32, decentralization: f64) -> EpochParamContent {
+        EpochParamContent {
+            epoch,
+            n_opt: 500,
+            decentralisation_param: decentralization,
+            ..Default::default()
+        }
+    }
+
+    fn history(epoch: i32, blocks: i32, active_size: f64) -> PoolHistoryInner {
+        let json_value = json!({
+            "epoch": epoch,
+            "blocks": blocks,
+            "active_stake": "20000000000000",
+            "active_size": active_size,
+            "delegators_count": 115,
+            "rewards": "10000000000",
+            "fees": "840000000"
+        });
+
+        serde_json::from_value(json_value).unwrap()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{actual} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn test_params() {
+        let params = params();
+
+        assert_eq!(params.total_stake, 38_000_000_000_000_000);
+        assert_eq!(params.saturation_point(), 76_000_000_000_000);
+        assert_close(params.epochs_per_year(), 73.0);
+        assert_close(params.expected_blocks(0.001, 0.0), 21.6);
+        assert_close(params.expected_blocks(0.001, 0.32), 21.6 * 0.68);
+        assert_close(params.expected_blocks(0.001, 1.0), 0.0);
+    }
+
+    #[rstest]
+    #[case(27, 0.001, 0.0, Some(125.0))]
+    #[case(27, 0.001, 0.5, Some(250.0))]
+    #[case(0, 0.001, 0.0, Some(0.0))]
+    #[case(0, 0.0, 0.0, None)]
+    #[case(0, 0.001, 1.0, None)]
+    fn test_luck(
+        #[case] blocks: i32, #[case] active_size: f64, #[case] decentralization: f64,
+        #[case] expected: Option<f64>,
+    ) {
+        let epoch = PoolEpochPerformance::new(
+            &history(400, blocks, active_size),
+            &epoch_params(400, decentralization),
+            &params(),
+        )
+        .unwrap();
+
+        match (epoch.luck(), expected) {
+            (Some(luck), Some(expected)) => assert_close(luck, expected),
+            (luck, expected) => assert_eq!(luck, expected),
+        }
+    }
+
+    #[test]
+    fn test_epoch_roa() {
+        let params = params();
+        let epoch =
+            PoolEpochPerformance::new(&history(400, 27, 0.001), &epoch_params(400, 0.0), &params)
+                .unwrap();
+
+        assert_eq!(epoch.delegator_rewards(), 9_160_000_000);
+        // 0.0458% per epoch, 73 times a year
+        assert_close(epoch.roa(&params).unwrap(), 3.3434);
+        assert_close(epoch.saturation(&params), 20.0 / 76.0);
+    }
+
+    #[test]
+    fn test_epoch_params_mismatch() {
+        let result =
+            PoolEpochPerformance::new(&history(400, 27, 0.001), &epoch_params(401, 0.0), &params());
+
+        assert!(matches!(result, Err(BlockfrostError::Parsing { .. })));
+    }
+
+    #[test]
+    fn test_lifetime() {
+        let pool = json!({
+            "pool_id": POOL_ID,
+            "hex": "0f292fcaa02b8b2f9b3c8f9fd8e0bb21abedb692a6d5058df3ef2735",
+            "vrf_key": "b512cc7c1a8ba689c2d8fd27adfdbac2049a3f8f95c8b85e8298f14d7d8dc4e6",
+            "blocks_minted": 3512,
+            "blocks_epoch": 1,
+            "live_stake": "5721241414066",
+            "live_size": 0.00026787598158730844,
+            "live_saturation": 0.07458075839782029,
+            "live_delegators": 181,
+            "active_stake": "5727090990610",
+            "active_size": 0.0002677054019934437,
+            "declared_pledge": "250000000000",
+            "live_pledge": "356156149988",
+            "margin_cost": 0.049,
+            "fixed_cost": "340000000",
+            "reward_account": "stake1u98nnlkvkk23vtvf9273uq7cph5ww6u2yq2389psuqet90sv4xv9v",
+            "owners": [
+                "stake1u98nnlkvkk23vtvf9273uq7cph5ww6u2yq2389psuqet90sv4xv9v"
+            ],
+            "registration": [
+                "a96c79773b7506211eb56bf94886a2face17657d1009f52fb5ea05f19cc8823e"
+            ],
+            "retirement": [],
+            "calidus_key": null
+        });
+        let pool = serde_json::from_value(pool).unwrap();
+        let history = [history(401, 16, 0.001), history(400, 27, 0.001)];
+        // Epoch 400 is before the decentralization parameter reached zero
+        let epoch_params = [epoch_params(400, 0.5), epoch_params(401, 0.0)];
+        let analytics = PoolAnalytics::new(&pool, &history, &epoch_params, params()).unwrap();
+
+        assert_eq!(analytics.epochs[0].epoch, 400);
+        assert_eq!(analytics.blocks(), 43);
+        assert_close(analytics.expected_blocks(), 32.4);
+        assert_close(analytics.luck().unwrap(), 43.0 / 32.4 * 100.0);
+        assert_close(analytics.roa().unwrap(), 3.3434);
+        assert_close(analytics.saturation(), 5_721_241_414_066.0 / 76e12);
+        assert!(PoolAnalytics::new(&pool, &history, &epoch_params[1..], params()).is_err());
+    }
+}
@@ -0,0 +1,358 @@
+use crate::{
+    reqwest_error, utils::blake2b_256, BlockfrostAPI, BlockfrostError, BlockfrostResult,
+    ResponseError,
+};
+use blockfrost_openapi::models::pool_metadata::PoolMetadata;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+/// Maximum size of pool metadata, in bytes, above which the ledger rejects it.
+pub const POOL_METADATA_MAX_SIZE: usize = 512;
+
+/// Fetches off-chain documents, such as pool metadata, from their URL.
+///
+/// Implemented for [`reqwest::Client`]. Use a client without the Blockfrost project ID
+/// header, such as `reqwest::Client::new()`, as requests go to third-party servers.
+pub trait MetadataFetcher {
+    /// Return the raw content at `url`.
+    fn fetch(&self, url: &str) -> impl Future<Output = BlockfrostResult<Vec<u8>>> + Send;
+}
+
+impl MetadataFetcher for reqwest::Client {
+    fn fetch(&self, url: &str) -> impl Future<Output = BlockfrostResult<Vec<u8>>> + Send {
+        let request = self.get(url);
+        let url = url.to_string();
+
+        async move {
+            let response = request
+                .send()
+                .await
+                .map_err(|reason| reqwest_error(&url, reason))?;
+            let status = response.status();
+
+            if !status.is_success() {
+                return Err(BlockfrostError::Response {
+                    url,
+                    reason: ResponseError {
+                        status_code: status.as_u16(),
+                        error: status.canonical_reason().unwrap_or_default().to_string(),
+                        message: "Could not fetch metadata".to_string(),
+                    },
+                });
+            }
+
+            let bytes = response
+                .bytes()
+                .await
+                .map_err(|reason| reqwest_error(&url, reason))?;
+
+            Ok(bytes.to_vec())
+        }
+    }
+}
+
+/// Metadata document of a stake pool, as hosted at its registered URL.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OffchainPoolMetadata {
+    pub name: String,
+    pub description: String,
+    pub ticker: String,
+    pub homepage: String,
+    /// URL of the [`ExtendedPoolMetadata`].
+    pub extended: Option<String>,
+}
+
+impl OffchainPoolMetadata {
+    /// Parse a metadata document, checking that its blake2b-256 hash is `expected_hash`.
+    ///
+    /// Fails with [`BlockfrostError::HashMismatch`] if it was changed since its registration,
+    /// and with [`BlockfrostError::Parsing`] if it is too large or invalid.
+    pub fn from_bytes(bytes: &[u8], expected_hash: &str) -> BlockfrostResult<Self> {
+        let hash = hex::encode(blake2b_256(bytes));
+        if !hash.eq_ignore_ascii_case(expected_hash) {
+            return Err(BlockfrostError::HashMismatch {
+                expected: expected_hash.to_string(),
+                computed: hash,
+            });
+        }
+
+        if bytes.len() > POOL_METADATA_MAX_SIZE {
+            return Err(BlockfrostError::Parsing {
+                message: format!(
+                    "pool metadata is {} bytes long, above the limit of {POOL_METADATA_MAX_SIZE}",
+                    bytes.len()
+                ),
+            });
+        }
+
+        serde_json::from_slice(bytes).map_err(|reason| BlockfrostError::Parsing {
+            message: format!("invalid pool metadata: {reason}"),
+        })
+    }
+}
+
+/// Extended metadata of a stake pool, as defined by [CIP-6].
+///
+/// Its hash isn't registered on chain, so it is only as trustworthy as the server hosting it.
+///
+/// [CIP-6]: https://cips.cardano.org/cip/CIP-0006
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtendedPoolMetadata {
+    /// Version of the document, increased with each change.
+    pub serial: Option<u64>,
+    pub pool: ExtendedPoolInfo,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtendedPoolInfo {
+    /// Pool ID, in hex.
+    pub id: String,
+    /// ISO 3166-1 alpha-3 country code.
+    pub country: Option<String>,
+    /// Such as `active`, `retired` or `offline`.
+    pub status: Option<String>,
+    pub contact: Option<ExtendedPoolContact>,
+    pub media_assets: Option<ExtendedPoolMediaAssets>,
+    /// Proof of ownership of an Incentivized Testnet pool.
+    pub itn: Option<ExtendedPoolItn>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtendedPoolContact {
+    /// Preferred way of contact, the name of one of the other fields.
+    pub primary: Option<String>,
+    pub email: Option<String>,
+    pub facebook: Option<String>,
+    pub github: Option<String>,
+    pub feed: Option<String>,
+    pub telegram: Option<String>,
+    pub twitter: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtendedPoolMediaAssets {
+    pub icon_png_64x64: Option<String>,
+    pub logo_png: Option<String>,
+    pub logo_svg: Option<String>,
+    /// Foreground color, as `#RRGGBB`.
+    pub color_fg: Option<String>,
+    /// Background color, as `#RRGGBB`.
+    pub color_bg: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtendedPoolItn {
+    pub owner: String,
+    pub witness: String,
+}
+
+/// Pool metadata fetched from its registered URL and checked against its on-chain hash,
+/// returned by [`pools_metadata_verified`](BlockfrostAPI::pools_metadata_verified).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedPoolMetadata {
+    pub pool_id: String,
+    /// Registered URL of the metadata.
+    pub url: String,
+    /// Registered blake2b-256 hash of the metadata, which it matches.
+    pub hash: String,
+    pub metadata: OffchainPoolMetadata,
+    /// Document at the `extended` URL of the metadata, if any.
+    pub extended: Option<ExtendedPoolMetadata>,
+}
+
+impl VerifiedPoolMetadata {
+    /// Fetch and verify the metadata registered by a pool, as returned by
+    /// [`pools_metadata`](BlockfrostAPI::pools_metadata), and its extended metadata.
+    ///
+    /// Fails with [`BlockfrostError::HashMismatch`] if the document doesn't match its hash, and
+    /// with [`BlockfrostError::Parsing`] if the pool has no metadata, or if the extended
+    /// metadata is invalid or belongs to another pool.
+    pub async fn fetch(
+        registered: &PoolMetadata, fetcher: &impl MetadataFetcher,
+    ) -> BlockfrostResult<Self> {
+        let (Some(url), Some(hash)) = (&registered.url, &registered.hash) else {
+            return Err(BlockfrostError::Parsing {
+                message: format!("pool {} has no registered metadata", registered.pool_id),
+            });
+        };
+
+        let metadata = OffchainPoolMetadata::from_bytes(&fetcher.fetch(url).await?, hash)?;
+
+        let extended = match &metadata.extended {
+            Some(extended_url) => {
+                let bytes = fetcher.fetch(extended_url).await?;
+                let extended =
+                    serde_json::from_slice::<ExtendedPoolMetadata>(&bytes).map_err(|reason| {
+                        BlockfrostError::Parsing {
+                            message: format!("invalid extended pool metadata: {reason}"),
+                        }
+                    })?;
+
+                let id = &extended.pool.id;
+                if !id.eq_ignore_ascii_case(&registered.hex) && *id != registered.pool_id {
+                    return Err(BlockfrostError::Parsing {
+                        message: format!(
+                            "extended metadata of pool {} is for pool {id}",
+                            registered.pool_id
+                        ),
+                    });
+                }
+
+                Some(extended)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            pool_id: registered.pool_id.clone(),
+            url: url.clone(),
+            hash: hash.clone(),
+            metadata,
+            extended,
+        })
+    }
+}
+
+impl BlockfrostAPI {
+    /// Return the metadata of a pool, fetched from its registered URL rather than the copy
+    /// cached by Blockfrost, and checked against its registered hash.
+    ///
+    /// The `extended` link of the metadata is followed as well. See
+    /// [`VerifiedPoolMetadata::fetch`] for the possible errors.
+    pub async fn pools_metadata_verified(
+        &self, pool_id: &str, fetcher: &impl MetadataFetcher,
+    ) -> BlockfrostResult<VerifiedPoolMetadata> {
+        VerifiedPoolMetadata::fetch(&self.pools_metadata(pool_id).await?, fetcher).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    const METADATA: &str = r#"{"name": "Stake Nuts", "description": "The best pool ever", "ticker": "NUTS", "homepage": "https://stakenuts.com/", "extended": "https://stakenuts.com/extended.json"}"#;
+    const METADATA_HASH: &str = "3708ee5aca5c8b42d26c88abd3e0330d2552de4fa13bc411eff78ca72dc5bc04";
+    const POOL_HEX: &str = "0f292fcaa02b8b2f9b3c8f9fd8e0bb21abedb692a6d5058df3ef2735";
+
+    // Serves documents by URL.
+    struct Documents(HashMap<&'static str, String>);
+
+    impl MetadataFetcher for Documents {
+        fn fetch(&self, url: &str) -> impl Future<Output = BlockfrostResult<Vec<u8>>> + Send {
+            let document = self.0.get(url).map(|document| document.as_bytes().to_vec());
+            let url = url.to_string();
+
+            std::future::ready(document.ok_or_else(|| BlockfrostError::Parsing {
+                message: format!("{url} not found"),
+            }))
+        }
+    }
+
+    fn documents(pool_id: &str) -> Documents {
+        let extended = json!({
+            "serial": 2020072001,
+            "pool": {
+                "id": pool_id,
+                "country": "CZE",
+                "status": "active",
+                "contact": {
+                    "primary": "email",
+                    "email": "info@stakenuts.com",
+                    "twitter": "StakeNuts"
+                },
+                "media_assets": {
+                    "icon_png_64x64": "https://stakenuts.com/icon.png",
+                    "color_fg": "#ffffff",
+                    "color_bg": "#000000"
+                }
+            }
+        });
+
+        Documents(HashMap::from([
+            ("https://stakenuts.com/mainnet.json", METADATA.to_string()),
+            ("https://stakenuts.com/extended.json", extended.to_string()),
+        ]))
+    }
+
+    fn registered(hash: &str) -> PoolMetadata {
+        let json_value = json!({
+            "pool_id": "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy",
+            "hex": POOL_HEX,
+            "url": "https://stakenuts.com/mainnet.json",
+            "hash": hash,
+            "ticker": "NUTS",
+            "name": "Stake Nuts",
+            "description": "The best pool ever",
+            "homepage": "https://stakenuts.com/"
+        });
+
+        serde_json::from_value(json_value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let verified =
+            VerifiedPoolMetadata::fetch(&registered(METADATA_HASH), &documents(POOL_HEX))
+                .await
+                .unwrap();
+
+        assert_eq!(verified.metadata.ticker, "NUTS");
+        assert_eq!(verified.hash, METADATA_HASH);
+
+        let extended = verified.extended.unwrap();
+
+        assert_eq!(extended.serial, Some(2020072001));
+        assert_eq!(extended.pool.country.as_deref(), Some("CZE"));
+        assert_eq!(
+            extended.pool.contact.unwrap().email.as_deref(),
+            Some("info@stakenuts.com")
+        );
+        assert_eq!(
+            extended.pool.media_assets.unwrap().color_bg.as_deref(),
+            Some("#000000")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_hash_mismatch() {
+        let expected = "47c0c68cb57f4a5b4a87bad896fc274678e7aea98e200fa14a1cb40c0cab1d8c";
+        let result = VerifiedPoolMetadata::fetch(&registered(expected), &documents(POOL_HEX)).await;
+
+        match result {
+            Err(BlockfrostError::HashMismatch {
+                expected: hash,
+                computed,
+            }) => {
+                assert_eq!(hash, expected);
+                assert_eq!(computed, METADATA_HASH);
+            }
+            result => panic!("expected a hash mismatch, got {result:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_other_pool() {
+        let other_pool = "6b6164af70861c5537cc9c8e50fdae35139ca2c8c6fbb42e8b7e6bfb";
+        let result =
+            VerifiedPoolMetadata::fetch(&registered(METADATA_HASH), &documents(other_pool)).await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_from_bytes_too_large() {
+        let metadata = json!({
+            "name": "Stake Nuts",
+            "description": "a".repeat(POOL_METADATA_MAX_SIZE),
+            "ticker": "NUTS",
+            "homepage": "https://stakenuts.com/"
+        })
+        .to_string();
+        let hash = hex::encode(blake2b_256(metadata.as_bytes()));
+
+        assert!(OffchainPoolMetadata::from_bytes(metadata.as_bytes(), &hash).is_err());
+        assert!(OffchainPoolMetadata::from_bytes(METADATA.as_bytes(), METADATA_HASH).is_ok());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    format_quantity, utils::parse_lovelace, AssetId, BlockfrostAPI, BlockfrostResult,
+    Cip25Metadata, Cip67Label, Pagination, TokenRegistryMetadata,
+};
+use blockfrost_openapi::models::asset::Asset;
+use futures::{stream, StreamExt, TryStreamExt};
+use std::sync::PoisonError;
+
+/// Holdings of a stake account, returned by [`portfolio`](BlockfrostAPI::portfolio).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Portfolio {
+    pub stake_address: String,
+    /// Whether the stake address is registered.
+    pub active: bool,
+    /// Lovelace controlled by the account, in its addresses and rewards.
+    pub controlled_amount: u64,
+    /// Lovelace of rewards available for withdrawal.
+    pub withdrawable_amount: u64,
+    /// Pool the account delegates to.
+    pub pool_id: Option<String>,
+    /// DRep the account delegates its votes to.
+    pub drep_id: Option<String>,
+    pub assets: Vec<PortfolioAsset>,
+}
+
+/// A native asset held by a [`Portfolio`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PortfolioAsset {
+    pub asset: AssetId,
+    /// Raw quantity held, see [`PortfolioAsset::formatted_quantity`].
+    pub quantity: String,
+    /// Name from the token registry or CIP-25 metadata, or the decoded asset name.
+    pub name: String,
+    pub ticker: Option<String>,
+    /// Decimals from the token registry.
+    pub decimals: Option<u32>,
+    pub kind: AssetKind,
+}
+
+/// Whether an asset is fungible, from its CIP-67 label or its supply.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+    /// `(222)` token, or an unlabelled asset with a supply of one.
+    Nft,
+    /// `(333)` token, or an unlabelled asset with a larger supply.
+    Fungible,
+    /// `(444)` rich fungible token.
+    RichFungible,
+    /// `(100)` reference token, holding the CIP-68 metadata of another token.
+    Reference,
+}
+
+impl AssetKind {
+    /// Classify `asset`, from its label or from its total `supply` if it has none.
+    pub fn classify(asset: &AssetId, supply: &str) -> Self {
+        match asset.label() {
+            Some(Cip67Label::Nft) => AssetKind::Nft,
+            Some(Cip67Label::Ft) => AssetKind::Fungible,
+            Some(Cip67Label::Rft) => AssetKind::RichFungible,
+            Some(Cip67Label::ReferenceNft) => AssetKind::Reference,
+            Some(Cip67Label::Other(_)) | None if supply == "1" => AssetKind::Nft,
+            Some(Cip67Label::Other(_)) | None => AssetKind::Fungible,
+        }
+    }
+}
+
+impl PortfolioAsset {
+    /// Describe `quantity` of an asset from its details, as returned by
+    /// [`assets_by_id`](BlockfrostAPI::assets_by_id).
+    pub fn from_asset(details: &Asset, quantity: String) -> BlockfrostResult<Self> {
+        let asset = AssetId::from_unit(&details.asset)?;
+        let registry = TokenRegistryMetadata::from_asset(details)?;
+
+        // Metadata that doesn't follow CIP-25 is not a reason to fail the whole portfolio.
+        let cip25_name = Cip25Metadata::from_asset(details)
+            .ok()
+            .flatten()
+            .and_then(|metadata| metadata.name);
+        let name = registry
+            .as_ref()
+            .map(|registry| registry.name.clone())
+            .or(cip25_name)
+            .unwrap_or_else(|| asset.display_name());
+
+        Ok(Self {
+            kind: AssetKind::classify(&asset, &details.quantity),
+            asset,
+            quantity,
+            name,
+            ticker: registry
+                .as_ref()
+                .and_then(|registry| registry.ticker.clone()),
+            decimals: registry.and_then(|registry| registry.decimals),
+        })
+    }
+
+    /// Quantity with its decimals, such as `12.5`.
+    pub fn formatted_quantity(&self) -> BlockfrostResult<String> {
+        format_quantity(&self.quantity, self.decimals.unwrap_or(0))
+    }
+}
+
+impl BlockfrostAPI {
+    /// Return the holdings of a stake account: ADA, rewards, delegations and native assets.
+    ///
+    /// Each asset is looked up with [`assets_by_id`](Self::assets_by_id), with at most
+    /// [`batch_concurrency`](crate::BlockFrostSettings::batch_concurrency) requests at a time.
+    /// Registry metadata found along the way is cached for
+    /// [`token_registry_metadata`](Self::token_registry_metadata).
+    pub async fn portfolio(&self, stake_address: &str) -> BlockfrostResult<Portfolio> {
+        let (account, holdings) = futures::try_join!(
+            self.accounts(stake_address),
+            self.accounts_addresses_assets(stake_address, Pagination::all())
+        )?;
+
+        let assets = stream::iter(holdings)
+            .map(|holding| async move {
+                let details = self.assets_by_id(&holding.unit).await?;
+
+                self.token_registry
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .insert(holding.unit, TokenRegistryMetadata::from_asset(&details)?);
+
+                PortfolioAsset::from_asset(&details, holding.quantity)
+            })
+            .buffered(self.batch_concurrency())
+            .try_collect()
+            .await?;
+
+        Ok(Portfolio {
+            stake_address: account.stake_address,
+            active: account.active,
+            controlled_amount: parse_lovelace(&account.controlled_amount)?,
+            withdrawable_amount: parse_lovelace(&account.withdrawable_amount)?,
+            pool_id: account.pool_id,
+            drep_id: account.drep_id,
+            assets,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use serde_json::json;
+
+    const POLICY_ID: &str = "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a7";
+
+    fn asset(asset_name: &str, quantity: &str, metadata: serde_json::Value) -> Asset {
+        let json_value = json!({
+            "asset": format!("{POLICY_ID}{asset_name}"),
+            "policy_id": POLICY_ID,
+            "asset_name": asset_name,
+            "fingerprint": "asset1pkpwyknlvul7az0xx8czhl60pyel45rpje4z8w",
+            "quantity": quantity,
+            "initial_mint_tx_hash": "6804edf9712d2b619edb6ac86861fe93a730693183a262b165fcc1ba1bc99cad",
+            "mint_or_burn_count": 1,
+            "onchain_metadata": null,
+            "metadata": metadata
+        });
+
+        serde_json::from_value(json_value).unwrap()
+    }
+
+    #[rstest]
+    #[case("000de14048656c6c6f", "1", AssetKind::Nft)]
+    #[case("0014df104e7574", "1", AssetKind::Fungible)]
+    #[case("001bc2804e7574", "100", AssetKind::RichFungible)]
+    #[case("000643b048656c6c6f", "1", AssetKind::Reference)]
+    #[case("6e7574636f696e", "1", AssetKind::Nft)]
+    #[case("6e7574636f696e", "12000", AssetKind::Fungible)]
+    fn test_classify(#[case] asset_name: &str, #[case] supply: &str, #[case] kind: AssetKind) {
+        let asset = AssetId::new(POLICY_ID, asset_name).unwrap();
+
+        assert_eq!(AssetKind::classify(&asset, supply), kind);
+    }
+
+    #[test]
+    fn test_from_asset() {
+        let metadata = json!({
+            "name": "nutcoin",
+            "description": "The Nut Coin",
+            "ticker": "nutc",
+            "url": "https://www.stakenuts.com/",
+            "logo": null,
+            "decimals": 6
+        });
+        let details = asset("6e7574636f696e", "12000000000", metadata);
+        let holding = PortfolioAsset::from_asset(&details, "12500000".to_string()).unwrap();
+
+        assert_eq!(holding.name, "nutcoin");
+        assert_eq!(holding.ticker.as_deref(), Some("nutc"));
+        assert_eq!(holding.kind, AssetKind::Fungible);
+        assert_eq!(holding.formatted_quantity().unwrap(), "12.5");
+
+        // No registry entry
+        let details = asset("000de14048656c6c6f", "1", json!(null));
+        let holding = PortfolioAsset::from_asset(&details, "1".to_string()).unwrap();
+
+        assert_eq!(holding.name, "Hello");
+        assert_eq!(holding.decimals, None);
+        assert_eq!(holding.kind, AssetKind::Nft);
+        assert_eq!(holding.formatted_quantity().unwrap(), "1");
+    }
+}
@@ -0,0 +1,620 @@
+use crate::{
+    utils::parse_lovelace, BlockfrostAPI, BlockfrostError, BlockfrostResult, JsonMap, JsonValue,
+    Pagination,
+};
+use blockfrost_openapi::models::epoch_param_content::EpochParamContent;
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// ID of the predefined DRep that abstains from every vote.
+pub const DREP_ALWAYS_ABSTAIN: &str = "drep_always_abstain";
+/// ID of the predefined DRep that votes yes on no-confidence actions only.
+pub const DREP_ALWAYS_NO_CONFIDENCE: &str = "drep_always_no_confidence";
+
+/// Type of a governance action, as named by the API.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GovernanceActionType {
+    ParameterChange,
+    HardForkInitiation,
+    TreasuryWithdrawals,
+    NoConfidence,
+    /// Update of the constitutional committee.
+    NewCommittee,
+    NewConstitution,
+    InfoAction,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoterRole {
+    ConstitutionalCommittee,
+    Drep,
+    Spo,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalVoteChoice {
+    Yes,
+    No,
+    Abstain,
+}
+
+/// A vote on a proposal, as returned by [`proposals_votes`](BlockfrostAPI::proposals_votes).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalVote {
+    pub voter_role: VoterRole,
+    /// DRep ID, pool ID or committee hot key of the voter.
+    pub voter: String,
+    pub vote: ProposalVoteChoice,
+}
+
+/// Group of a protocol parameter, which sets who votes on its changes and their threshold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ParameterGroup {
+    Network,
+    Economic,
+    Technical,
+    Governance,
+}
+
+impl ParameterGroup {
+    /// Group of a parameter, by its name in
+    /// [`epochs_parameters`](BlockfrostAPI::epochs_parameters).
+    pub fn of(parameter: &str) -> Option<Self> {
+        match parameter {
+            "max_block_size"
+            | "max_tx_size"
+            | "max_block_header_size"
+            | "max_val_size"
+            | "max_tx_ex_mem"
+            | "max_tx_ex_steps"
+            | "max_block_ex_mem"
+            | "max_block_ex_steps"
+            | "max_collateral_inputs" => Some(ParameterGroup::Network),
+            "min_fee_a"
+            | "min_fee_b"
+            | "key_deposit"
+            | "pool_deposit"
+            | "rho"
+            | "tau"
+            | "min_pool_cost"
+            | "coins_per_utxo_size"
+            | "price_mem"
+            | "price_step"
+            | "min_fee_ref_script_cost_per_byte" => Some(ParameterGroup::Economic),
+            "a0" | "e_max" | "n_opt" | "cost_models" | "collateral_percent" => {
+                Some(ParameterGroup::Technical)
+            }
+            "gov_action_lifetime"
+            | "gov_action_deposit"
+            | "drep_deposit"
+            | "drep_activity"
+            | "committee_min_size"
+            | "committee_max_term_length" => Some(ParameterGroup::Governance),
+            parameter if parameter.starts_with("pvt") || parameter.starts_with("dvt_") => {
+                Some(ParameterGroup::Governance)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether changes to a parameter also need the approval of stake pools.
+    pub fn is_security_relevant(parameter: &str) -> bool {
+        matches!(
+            parameter,
+            "max_block_size"
+                | "max_tx_size"
+                | "max_block_header_size"
+                | "max_val_size"
+                | "max_block_ex_mem"
+                | "max_block_ex_steps"
+                | "min_fee_a"
+                | "min_fee_b"
+                | "coins_per_utxo_size"
+                | "gov_action_deposit"
+                | "min_fee_ref_script_cost_per_byte"
+        )
+    }
+
+    fn drep_threshold_name(self) -> &'static str {
+        match self {
+            ParameterGroup::Network => "dvt_p_p_network_group",
+            ParameterGroup::Economic => "dvt_p_p_economic_group",
+            ParameterGroup::Technical => "dvt_p_p_technical_group",
+            ParameterGroup::Governance => "dvt_p_p_gov_group",
+        }
+    }
+}
+
+/// Share of yes votes a proposal needs from DReps and stake pools to be ratified, `None` when
+/// the body doesn't vote on it.
+///
+/// Info actions can't be ratified, so they have no thresholds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VotingThresholds {
+    pub drep: Option<f64>,
+    pub spo: Option<f64>,
+}
+
+impl VotingThresholds {
+    /// Thresholds of an action from the protocol parameters.
+    ///
+    /// Parameter changes use the highest threshold of the groups of `changed_parameters`, or of
+    /// all groups if they are unknown, and need stake pools to vote only if a security relevant
+    /// parameter changes. Committee updates use the thresholds of a committee in confidence.
+    pub fn new(
+        action: GovernanceActionType, params: &EpochParamContent, changed_parameters: &[String],
+    ) -> BlockfrostResult<Self> {
+        let params = to_json_map(params)?;
+        let threshold = |name: &str| params.get(name).and_then(JsonValue::as_f64);
+
+        let (drep, spo) = match action {
+            GovernanceActionType::NoConfidence => (
+                threshold("dvt_motion_no_confidence"),
+                threshold("pvt_motion_no_confidence"),
+            ),
+            GovernanceActionType::NewCommittee => (
+                threshold("dvt_committee_normal"),
+                threshold("pvt_committee_normal"),
+            ),
+            GovernanceActionType::NewConstitution => {
+                (threshold("dvt_update_to_constitution"), None)
+            }
+            GovernanceActionType::HardForkInitiation => (
+                threshold("dvt_hard_fork_initiation"),
+                threshold("pvt_hard_fork_initiation"),
+            ),
+            GovernanceActionType::TreasuryWithdrawals => {
+                (threshold("dvt_treasury_withdrawal"), None)
+            }
+            GovernanceActionType::ParameterChange => {
+                let mut groups: Vec<_> = changed_parameters
+                    .iter()
+                    .filter_map(|parameter| ParameterGroup::of(parameter))
+                    .collect();
+                if groups.is_empty() {
+                    groups = vec![
+                        ParameterGroup::Network,
+                        ParameterGroup::Economic,
+                        ParameterGroup::Technical,
+                        ParameterGroup::Governance,
+                    ];
+                }
+
+                let drep = groups
+                    .into_iter()
+                    .filter_map(|group| threshold(group.drep_threshold_name()))
+                    .reduce(f64::max);
+                let security_relevant = changed_parameters.is_empty()
+                    || changed_parameters
+                        .iter()
+                        .any(|parameter| ParameterGroup::is_security_relevant(parameter));
+                let spo = security_relevant
+                    .then(|| {
+                        threshold("pvt_p_p_security_group")
+                            .or_else(|| threshold("pvtpp_security_group"))
+                    })
+                    .flatten();
+
+                (drep, spo)
+            }
+            GovernanceActionType::InfoAction => (None, None),
+        };
+
+        Ok(Self { drep, spo })
+    }
+}
+
+/// Votes of a body on a proposal, in lovelace of stake for DReps and stake pools, and in
+/// members for the constitutional committee.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VoteTally {
+    pub yes: u64,
+    /// Votes against, including stake that didn't vote.
+    pub no: u64,
+    pub abstain: u64,
+    /// Share of yes votes needed for ratification, `None` if unknown or if the body doesn't
+    /// vote on the proposal.
+    pub threshold: Option<f64>,
+}
+
+impl VoteTally {
+    /// Share of yes votes, excluding abstentions.
+    pub fn ratio(&self) -> f64 {
+        match self.yes + self.no {
+            0 => 0.0,
+            total => self.yes as f64 / total as f64,
+        }
+    }
+
+    /// Whether the body currently approves the proposal, `None` without a threshold.
+    pub fn meets_threshold(&self) -> Option<bool> {
+        self.threshold.map(|threshold| self.ratio() >= threshold)
+    }
+}
+
+/// Stake that can vote on proposals, by DRep and stake pool ID.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VotingStake {
+    /// Stake delegated to each active DRep, in lovelace.
+    pub dreps: HashMap<String, u64>,
+    /// Stake delegated to [`DREP_ALWAYS_ABSTAIN`].
+    pub always_abstain: u64,
+    /// Stake delegated to [`DREP_ALWAYS_NO_CONFIDENCE`].
+    pub always_no_confidence: u64,
+    /// Active stake of each pool, in lovelace.
+    pub pools: HashMap<String, u64>,
+}
+
+/// Where a governance action stands, returned by
+/// [`proposal_tally`](BlockfrostAPI::proposal_tally).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProposalTally {
+    pub action: GovernanceActionType,
+    pub dreps: VoteTally,
+    /// Pools that didn't vote count as voting no, without the defaults the ledger derives from
+    /// the DRep delegation of their reward account.
+    pub spos: VoteTally,
+    /// Counts the votes cast. The committee threshold isn't a protocol parameter, see
+    /// [`ProposalTally::committee_meets`].
+    pub committee: VoteTally,
+}
+
+impl ProposalTally {
+    /// Count `votes`, in the order they were cast, so later votes of a voter replace earlier
+    /// ones.
+    pub fn new(
+        action: GovernanceActionType, votes: &[ProposalVote], stake: &VotingStake,
+        thresholds: VotingThresholds,
+    ) -> Self {
+        let votes: HashMap<_, _> = votes
+            .iter()
+            .map(|vote| ((vote.voter_role, vote.voter.as_str()), vote.vote))
+            .collect();
+        let votes_of = |role: VoterRole| {
+            votes
+                .iter()
+                .filter(move |((voter_role, _), _)| *voter_role == role)
+                .map(|((_, voter), vote)| (*voter, *vote))
+        };
+
+        // Stake of the predefined DReps counts as a vote, and abstentions aren't counted.
+        let mut dreps = stake_tally(votes_of(VoterRole::Drep), &stake.dreps);
+        dreps.abstain += stake.always_abstain;
+        if action == GovernanceActionType::NoConfidence {
+            dreps.yes += stake.always_no_confidence;
+        } else {
+            dreps.no += stake.always_no_confidence;
+        }
+        dreps.threshold = thresholds.drep;
+
+        let mut spos = stake_tally(votes_of(VoterRole::Spo), &stake.pools);
+        spos.threshold = thresholds.spo;
+
+        let mut committee = VoteTally::default();
+        for (_, vote) in votes_of(VoterRole::ConstitutionalCommittee) {
+            *tally_entry(&mut committee, vote) += 1;
+        }
+
+        Self {
+            action,
+            dreps,
+            spos,
+            committee,
+        }
+    }
+
+    /// Whether the constitutional committee currently approves the proposal, given its
+    /// `threshold` and its number of active `members`, `None` if it doesn't vote on this action.
+    ///
+    /// Members that didn't vote count as voting no.
+    pub fn committee_meets(&self, threshold: f64, members: u64) -> Option<bool> {
+        if matches!(
+            self.action,
+            GovernanceActionType::NoConfidence | GovernanceActionType::NewCommittee
+        ) {
+            return None;
+        }
+
+        let tally = VoteTally {
+            no: members.saturating_sub(self.committee.yes + self.committee.abstain),
+            threshold: Some(threshold),
+            ..self.committee
+        };
+
+        tally.meets_threshold()
+    }
+}
+
+impl BlockfrostAPI {
+    /// Return the current tally of a governance action: the yes, no and abstain votes of DReps,
+    /// stake pools and the constitutional committee, against the thresholds of the latest
+    /// protocol parameters.
+    ///
+    /// DRep stake is looked up with [`dreps_by_id`](Self::dreps_by_id) for every registered
+    /// DRep, [`batch_concurrency`](crate::BlockFrostSettings::batch_concurrency) at a time, and
+    /// pool stake with [`pools_extended`](Self::pools_extended), so this makes many requests.
+    ///
+    /// The API doesn't expose the constitutional committee, so the committee tally has no
+    /// threshold and only counts the votes cast: pass its threshold and number of active members
+    /// to [`ProposalTally::committee_meets`], from another source such as `cardano-cli`.
+    pub async fn proposal_tally(
+        &self, tx_hash: &str, cert_index: i32,
+    ) -> BlockfrostResult<ProposalTally> {
+        let (proposal, votes, params, dreps, pools) = futures::try_join!(
+            self.proposals_by_id(tx_hash, cert_index),
+            self.proposals_votes(tx_hash, cert_index, Pagination::all()),
+            self.epochs_latest_parameters(),
+            self.dreps(Pagination::all()),
+            self.pools_extended(Pagination::all())
+        )?;
+
+        let action = convert(
+            to_json_map(&proposal)?
+                .remove("governance_type")
+                .unwrap_or_default(),
+        )?;
+        let votes = votes
+            .iter()
+            .map(|vote| convert(JsonValue::Object(to_json_map(vote)?)))
+            .collect::<BlockfrostResult<Vec<ProposalVote>>>()?;
+
+        let changed_parameters = match action {
+            GovernanceActionType::ParameterChange => {
+                let parameters = self.proposals_parameters(tx_hash, cert_index).await?;
+
+                match to_json_map(&parameters)?.get("parameters") {
+                    Some(JsonValue::Object(parameters)) => parameters
+                        .iter()
+                        .filter(|(_, value)| !value.is_null())
+                        .map(|(name, _)| name.clone())
+                        .collect(),
+                    _ => Vec::new(),
+                }
+            }
+            _ => Vec::new(),
+        };
+
+        let predefined = [DREP_ALWAYS_ABSTAIN, DREP_ALWAYS_NO_CONFIDENCE];
+        let registered = dreps
+            .into_iter()
+            .filter(|drep| !predefined.contains(&drep.drep_id.as_str()));
+
+        // Stake of inactive DReps doesn't count.
+        let drep_stake: Vec<_> = stream::iter(registered)
+            .map(|drep| async move {
+                let drep = self.dreps_by_id(&drep.drep_id).await?;
+                let amount = parse_lovelace(&drep.amount)?;
+
+                Ok::<_, BlockfrostError>(
+                    (drep.active && !drep.expired).then_some((drep.drep_id, amount)),
+                )
+            })
+            .buffered(self.batch_concurrency())
+            .try_collect()
+            .await?;
+
+        let (always_abstain, always_no_confidence) = futures::try_join!(
+            self.predefined_drep_stake(DREP_ALWAYS_ABSTAIN),
+            self.predefined_drep_stake(DREP_ALWAYS_NO_CONFIDENCE)
+        )?;
+
+        let stake = VotingStake {
+            dreps: drep_stake.into_iter().flatten().collect(),
+            always_abstain,
+            always_no_confidence,
+            pools: pools
+                .into_iter()
+                .map(|pool| Ok((pool.pool_id, parse_lovelace(&pool.active_stake)?)))
+                .collect::<BlockfrostResult<_>>()?,
+        };
+
+        Ok(ProposalTally::new(
+            action,
+            &votes,
+            &stake,
+            VotingThresholds::new(action, &params, &changed_parameters)?,
+        ))
+    }
+
+    async fn predefined_drep_stake(&self, drep_id: &str) -> BlockfrostResult<u64> {
+        match self.dreps_by_id(drep_id).await {
+            Ok(drep) => parse_lovelace(&drep.amount),
+            Err(BlockfrostError::Response { reason, .. }) if reason.status_code == 404 => Ok(0),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+// Tally votes weighted by stake, counting stake that didn't vote as no.
+fn stake_tally<'a>(
+    votes: impl Iterator<Item = (&'a str, ProposalVoteChoice)>, stake: &HashMap<String, u64>,
+) -> VoteTally {
+    let mut tally = VoteTally::default();
+
+    for (voter, vote) in votes {
+        *tally_entry(&mut tally, vote) += stake.get(voter).copied().unwrap_or_default();
+    }
+
+    let total: u64 = stake.values().sum();
+    tally.no += total.saturating_sub(tally.yes + tally.no + tally.abstain);
+    tally
+}
+
+fn tally_entry(tally: &mut VoteTally, vote: ProposalVoteChoice) -> &mut u64 {
+    match vote {
+        ProposalVoteChoice::Yes => &mut tally.yes,
+        ProposalVoteChoice::No => &mut tally.no,
+        ProposalVoteChoice::Abstain => &mut tally.abstain,
+    }
+}
+
+fn to_json_map(value: &impl Serialize) -> BlockfrostResult<JsonMap> {
+    match serde_json::to_value(value) {
+        Ok(JsonValue::Object(map)) => Ok(map),
+        Ok(value) => Err(BlockfrostError::Parsing {
+            message: format!("expected a JSON object, got {value}"),
+        }),
+        Err(reason) => Err(BlockfrostError::Parsing {
+            message: reason.to_string(),
+        }),
+    }
+}
+
+// Read a value of a model, whose enums may not match the API, into a type of this module.
+fn convert<T: DeserializeOwned>(value: JsonValue) -> BlockfrostResult<T> {
+    T::deserialize(&value).map_err(|reason| BlockfrostError::Parsing {
+        message: format!("unexpected governance value {value}: {reason}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use serde_json::json;
+
+    fn params() -> EpochParamContent {
+        let json_value = json!({
+            "pvt_motion_no_confidence": 0.51,
+            "pvt_committee_normal": 0.51,
+            "pvt_hard_fork_initiation": 0.51,
+            "pvtpp_security_group": 0.51,
+            "pvt_p_p_security_group": 0.51,
+            "dvt_motion_no_confidence": 0.67,
+            "dvt_committee_normal": 0.67,
+            "dvt_update_to_constitution": 0.75,
+            "dvt_hard_fork_initiation": 0.6,
+            "dvt_p_p_network_group": 0.67,
+            "dvt_p_p_economic_group": 0.67,
+            "dvt_p_p_technical_group": 0.67,
+            "dvt_p_p_gov_group": 0.75,
+            "dvt_treasury_withdrawal": 0.67
+        });
+        let mut params = to_json_map(&EpochParamContent::default()).unwrap();
+        params.extend(json_value.as_object().unwrap().clone());
+
+        serde_json::from_value(JsonValue::Object(params)).unwrap()
+    }
+
+    fn vote(voter_role: &str, voter: &str, vote: &str) -> ProposalVote {
+        let json_value = json!({
+            "tx_hash": "b302de601defdf11a5261ed31a263804dac4a582a888c998ce24dec5",
+            "cert_index": 0,
+            "voter_role": voter_role,
+            "voter": voter,
+            "vote": vote
+        });
+
+        convert(json_value).unwrap()
+    }
+
+    fn stake() -> VotingStake {
+        VotingStake {
+            dreps: HashMap::from([
+                ("drep1a".to_string(), 600),
+                ("drep1b".to_string(), 200),
+                ("drep1c".to_string(), 100),
+            ]),
+            always_abstain: 50,
+            always_no_confidence: 100,
+            pools: HashMap::from([("pool1a".to_string(), 700), ("pool1b".to_string(), 300)]),
+        }
+    }
+
+    #[rstest]
+    #[case(GovernanceActionType::NoConfidence, &[], Some(0.67), Some(0.51))]
+    #[case(GovernanceActionType::NewConstitution, &[], Some(0.75), None)]
+    #[case(GovernanceActionType::TreasuryWithdrawals, &[], Some(0.67), None)]
+    #[case(GovernanceActionType::InfoAction, &[], None, None)]
+    #[case(GovernanceActionType::ParameterChange, &["n_opt"], Some(0.67), None)]
+    #[case(
+        GovernanceActionType::ParameterChange,
+        &["max_tx_size", "drep_activity"],
+        Some(0.75),
+        Some(0.51)
+    )]
+    #[case(GovernanceActionType::ParameterChange, &[], Some(0.75), Some(0.51))]
+    fn test_thresholds(
+        #[case] action: GovernanceActionType, #[case] changed: &[&str], #[case] drep: Option<f64>,
+        #[case] spo: Option<f64>,
+    ) {
+        let changed: Vec<String> = changed.iter().map(ToString::to_string).collect();
+        let thresholds = VotingThresholds::new(action, &params(), &changed).unwrap();
+
+        assert_eq!(thresholds, VotingThresholds { drep, spo });
+    }
+
+    #[test]
+    fn test_tally() {
+        let votes = [
+            vote("drep", "drep1a", "no"),
+            // Replaces the previous vote
+            vote("drep", "drep1a", "yes"),
+            vote("drep", "drep1b", "abstain"),
+            vote("spo", "pool1b", "yes"),
+            vote("constitutional_committee", "cc_hot1a", "yes"),
+            vote("constitutional_committee", "cc_hot1b", "no"),
+        ];
+        let thresholds =
+            VotingThresholds::new(GovernanceActionType::HardForkInitiation, &params(), &[])
+                .unwrap();
+        let tally = ProposalTally::new(
+            GovernanceActionType::HardForkInitiation,
+            &votes,
+            &stake(),
+            thresholds,
+        );
+
+        // drep1c and always no confidence count as no
+        assert_eq!(
+            (tally.dreps.yes, tally.dreps.no, tally.dreps.abstain),
+            (600, 200, 250)
+        );
+        assert_eq!(tally.dreps.ratio(), 0.75);
+        assert_eq!(tally.dreps.meets_threshold(), Some(true));
+
+        assert_eq!((tally.spos.yes, tally.spos.no), (300, 700));
+        assert_eq!(tally.spos.meets_threshold(), Some(false));
+
+        assert_eq!((tally.committee.yes, tally.committee.no), (1, 1));
+        assert_eq!(tally.committee_meets(0.5, 2), Some(true));
+        assert_eq!(tally.committee_meets(0.67, 3), Some(false));
+    }
+
+    #[test]
+    fn test_tally_no_confidence() {
+        let votes = [vote("drep", "drep1b", "yes")];
+        let thresholds =
+            VotingThresholds::new(GovernanceActionType::NoConfidence, &params(), &[]).unwrap();
+        let tally = ProposalTally::new(
+            GovernanceActionType::NoConfidence,
+            &votes,
+            &stake(),
+            thresholds,
+        );
+
+        // Always no confidence votes yes
+        assert_eq!((tally.dreps.yes, tally.dreps.no), (300, 700));
+        assert_eq!(tally.dreps.meets_threshold(), Some(false));
+        assert_eq!(tally.committee_meets(0.67, 7), None);
+    }
+
+    #[rstest]
+    #[case("max_block_size", Some(ParameterGroup::Network), true)]
+    #[case("min_fee_a", Some(ParameterGroup::Economic), true)]
+    #[case("key_deposit", Some(ParameterGroup::Economic), false)]
+    #[case("cost_models", Some(ParameterGroup::Technical), false)]
+    #[case("gov_action_deposit", Some(ParameterGroup::Governance), true)]
+    #[case("dvt_treasury_withdrawal", Some(ParameterGroup::Governance), false)]
+    #[case("protocol_major_ver", None, false)]
+    fn test_parameter_group(
+        #[case] parameter: &str, #[case] group: Option<ParameterGroup>, #[case] security: bool,
+    ) {
+        assert_eq!(ParameterGroup::of(parameter), group);
+        assert_eq!(ParameterGroup::is_security_relevant(parameter), security);
+    }
+}
@@ -0,0 +1,326 @@
+use crate::utils::blake2b_224;
+#[cfg(feature = "cbor")]
+use crate::{
+    cbor::{Decoder, Encoder, MAJOR_BYTES},
+    transaction::Script,
+    BlockfrostAPI, BlockfrostError, BlockfrostResult,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Language of a script, which prefixes its bytes when hashing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ScriptLanguage {
+    #[serde(rename = "timelock")]
+    Native,
+    #[serde(rename = "plutusV1")]
+    PlutusV1,
+    #[serde(rename = "plutusV2")]
+    PlutusV2,
+    #[serde(rename = "plutusV3")]
+    PlutusV3,
+}
+
+impl ScriptLanguage {
+    /// Tag of the language, as used by the ledger.
+    pub fn tag(&self) -> u8 {
+        match self {
+            ScriptLanguage::Native => 0,
+            ScriptLanguage::PlutusV1 => 1,
+            ScriptLanguage::PlutusV2 => 2,
+            ScriptLanguage::PlutusV3 => 3,
+        }
+    }
+}
+
+impl fmt::Display for ScriptLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ScriptLanguage::Native => "native",
+            ScriptLanguage::PlutusV1 => "Plutus V1",
+            ScriptLanguage::PlutusV2 => "Plutus V2",
+            ScriptLanguage::PlutusV3 => "Plutus V3",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// Hash of a script from its language and serialized bytes, in hex.
+///
+/// For Plutus scripts, `bytes` is the flat-encoded program wrapped in a CBOR byte string.
+pub fn script_hash(language: ScriptLanguage, bytes: &[u8]) -> String {
+    let mut data = Vec::with_capacity(bytes.len() + 1);
+
+    data.push(language.tag());
+    data.extend(bytes);
+    hex::encode(blake2b_224(&data))
+}
+
+/// A Plutus script, as stored on chain.
+///
+/// Returned by [`scripts_hash_cbor_decoded`](BlockfrostAPI::scripts_hash_cbor_decoded), or built
+/// from CBOR with [`PlutusScript::from_cbor`].
+#[cfg(feature = "cbor")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlutusScript {
+    language: ScriptLanguage,
+    // The flat-encoded program, wrapped in a CBOR byte string.
+    bytes: Vec<u8>,
+    program: Vec<u8>,
+}
+
+#[cfg(feature = "cbor")]
+impl PlutusScript {
+    /// Read a Plutus script from its CBOR, with one or two layers of byte string wrapping the
+    /// flat-encoded program, or from the program itself.
+    pub fn from_cbor(language: ScriptLanguage, data: &[u8]) -> BlockfrostResult<Self> {
+        if language == ScriptLanguage::Native {
+            return Err(BlockfrostError::Parsing {
+                message: "expected a Plutus script, found a native script".to_string(),
+            });
+        }
+
+        // Unwrap byte strings until reaching the program, which never starts with one as its
+        // first byte is the major version of Plutus. The innermost byte string is kept as is, as
+        // it is what the ledger hashes.
+        let mut bytes = None;
+        let mut program = data.to_vec();
+
+        while let Some(inner) = single_byte_string(&program) {
+            bytes = Some(program);
+            program = inner;
+        }
+
+        let bytes = bytes.unwrap_or_else(|| {
+            let mut encoder = Encoder::new();
+            encoder.bytes(&program);
+            encoder.into_bytes()
+        });
+
+        Ok(Self {
+            language,
+            bytes,
+            program,
+        })
+    }
+
+    /// Read a Plutus script from hex-encoded CBOR, as returned by the API.
+    pub fn from_hex(language: ScriptLanguage, cbor: &str) -> BlockfrostResult<Self> {
+        let data = hex::decode(cbor).map_err(|reason| BlockfrostError::Cbor {
+            message: format!("invalid hex: {reason}"),
+        })?;
+
+        Self::from_cbor(language, &data)
+    }
+
+    pub fn language(&self) -> ScriptLanguage {
+        self.language
+    }
+
+    /// Serialized script, the flat-encoded program wrapped in a CBOR byte string.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Size of the serialized script, in bytes.
+    pub fn size(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Flat-encoded UPLC program.
+    pub fn program(&self) -> &[u8] {
+        &self.program
+    }
+
+    /// Version of UPLC the program is written in, such as `(1, 1, 0)`.
+    pub fn program_version(&self) -> BlockfrostResult<(u64, u64, u64)> {
+        let mut bytes = self.program().iter();
+
+        // Version numbers are flat naturals, 7 bits per byte with a continuation bit.
+        let mut natural = || -> BlockfrostResult<u64> {
+            let mut value = 0u64;
+
+            for shift in (0..64).step_by(7) {
+                let byte = bytes.next().ok_or_else(|| BlockfrostError::Parsing {
+                    message: "truncated program version".to_string(),
+                })?;
+                value |= u64::from(byte & 0x7f) << shift;
+
+                if byte & 0x80 == 0 {
+                    return Ok(value);
+                }
+            }
+
+            Err(BlockfrostError::Parsing {
+                message: "program version overflows".to_string(),
+            })
+        };
+
+        Ok((natural()?, natural()?, natural()?))
+    }
+
+    /// Hash of the script, in hex.
+    pub fn hash(&self) -> String {
+        script_hash(self.language, &self.bytes)
+    }
+
+    /// Check that the hash of the script is `expected`.
+    pub fn verify_hash(&self, expected: &str) -> BlockfrostResult<()> {
+        let hash = self.hash();
+
+        if hash.eq_ignore_ascii_case(expected) {
+            Ok(())
+        } else {
+            Err(BlockfrostError::HashMismatch {
+                expected: expected.to_string(),
+                computed: hash,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl Script {
+    pub fn language(&self) -> ScriptLanguage {
+        match self {
+            Script::Native(_) => ScriptLanguage::Native,
+            Script::PlutusV1(_) => ScriptLanguage::PlutusV1,
+            Script::PlutusV2(_) => ScriptLanguage::PlutusV2,
+            Script::PlutusV3(_) => ScriptLanguage::PlutusV3,
+        }
+    }
+
+    /// Hash of the script, in hex.
+    pub fn hash(&self) -> String {
+        match self {
+            Script::Native(bytes)
+            | Script::PlutusV1(bytes)
+            | Script::PlutusV2(bytes)
+            | Script::PlutusV3(bytes) => script_hash(self.language(), bytes),
+        }
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl BlockfrostAPI {
+    /// Return a Plutus script, checking that its hash is `script_hash`.
+    ///
+    /// Fails with [`BlockfrostError::HashMismatch`] if the returned script, prefixed with its
+    /// language, doesn't hash to `script_hash`. Native scripts are returned by
+    /// [`scripts_hash_json_decoded`](BlockfrostAPI::scripts_hash_json_decoded) instead.
+    pub async fn scripts_hash_cbor_decoded(
+        &self, script_hash: &str,
+    ) -> BlockfrostResult<PlutusScript> {
+        let (script, script_cbor) = futures::try_join!(
+            self.scripts_by_id(script_hash),
+            self.scripts_hash_cbor(script_hash)
+        )?;
+
+        let language = serde_json::to_value(script.r#type)
+            .and_then(serde_json::from_value::<ScriptLanguage>)
+            .map_err(|reason| BlockfrostError::Parsing {
+                message: format!("unknown script type: {reason}"),
+            })?;
+        let cbor = script_cbor.cbor.ok_or_else(|| BlockfrostError::Parsing {
+            message: format!("script {script_hash} has no CBOR, it is a {language} script"),
+        })?;
+
+        let plutus_script = PlutusScript::from_hex(language, &cbor)?;
+        plutus_script.verify_hash(script_hash)?;
+
+        Ok(plutus_script)
+    }
+}
+
+// Content of `data` if it is exactly one CBOR byte string.
+#[cfg(feature = "cbor")]
+fn single_byte_string(data: &[u8]) -> Option<Vec<u8>> {
+    let mut decoder = Decoder::new(data);
+
+    if decoder.peek_major().ok()? != MAJOR_BYTES {
+        return None;
+    }
+
+    decoder.bytes().ok().filter(|_| decoder.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "cbor")]
+    use rstest::rstest;
+
+    // Always succeeding Plutus V1 script
+    #[cfg(feature = "cbor")]
+    const ALWAYS_SUCCEEDS: &str = "4e4d01000033222220051200120011";
+    const ALWAYS_SUCCEEDS_HASH: &str = "67f33146617a5e61936081db3b2117cbf59bd2123748f58ac9678656";
+
+    #[cfg(feature = "cbor")]
+    #[rstest]
+    // Double wrapped, as returned by the API and in witness sets
+    #[case(ALWAYS_SUCCEEDS)]
+    #[case("4d01000033222220051200120011")]
+    #[case("01000033222220051200120011")]
+    fn test_plutus_script(#[case] cbor: &str) {
+        let script = PlutusScript::from_hex(ScriptLanguage::PlutusV1, cbor).unwrap();
+
+        assert_eq!(script.hash(), ALWAYS_SUCCEEDS_HASH);
+        assert_eq!(script.size(), 14);
+        assert_eq!(hex::encode(script.program()), "01000033222220051200120011");
+        assert_eq!(script.program_version().unwrap(), (1, 0, 0));
+        assert!(script.verify_hash(ALWAYS_SUCCEEDS_HASH).is_ok());
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_verify_hash() {
+        // Same bytes, with another language
+        let script = PlutusScript::from_hex(ScriptLanguage::PlutusV2, ALWAYS_SUCCEEDS).unwrap();
+
+        assert!(matches!(
+            script.verify_hash(ALWAYS_SUCCEEDS_HASH),
+            Err(BlockfrostError::HashMismatch { .. })
+        ));
+        assert!(PlutusScript::from_hex(ScriptLanguage::Native, ALWAYS_SUCCEEDS).is_err());
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_transaction_script_hash() {
+        let script = Script::PlutusV1(hex::decode("4d01000033222220051200120011").unwrap());
+        // sig e09d36c79dec9bd1b3d9e152247701cd0bb860b5ebfd1de8abb6735a
+        let native = Script::Native(
+            hex::decode("8200581ce09d36c79dec9bd1b3d9e152247701cd0bb860b5ebfd1de8abb6735a")
+                .unwrap(),
+        );
+
+        assert_eq!(script.hash(), ALWAYS_SUCCEEDS_HASH);
+        assert_eq!(
+            native.hash(),
+            "208bdcaf2d83ae026964e23659c703a377473168a39cbdc2b0241115"
+        );
+    }
+
+    #[test]
+    fn test_script_hash() {
+        let program = hex::decode("4d01000033222220051200120011").unwrap();
+
+        assert_eq!(
+            script_hash(ScriptLanguage::PlutusV1, &program),
+            ALWAYS_SUCCEEDS_HASH
+        );
+    }
+
+    #[test]
+    fn test_script_language() {
+        let language = serde_json::from_str::<ScriptLanguage>("\"plutusV3\"").unwrap();
+
+        assert_eq!(language, ScriptLanguage::PlutusV3);
+        assert_eq!(language.tag(), 3);
+        assert_eq!(
+            serde_json::from_str::<ScriptLanguage>("\"timelock\"").unwrap(),
+            ScriptLanguage::Native
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::DEFAULT_BATCH_SIZE;
 use std::{collections::HashMap, time::Duration};
 
 #[derive(Debug, Clone)]
@@ -6,6 +7,9 @@ pub struct BlockFrostSettings {
     pub base_url: Option<String>,
     pub retry_settings: RetrySettings,
     pub headers: HashMap<String, String>,
+    /// Maximum number of requests in flight for batch methods such as
+    /// [`transactions_by_hashes`](crate::BlockfrostAPI::transactions_by_hashes).
+    pub batch_concurrency: usize,
 }
 
 impl BlockFrostSettings {
@@ -14,6 +18,7 @@ impl BlockFrostSettings {
             base_url: None,
             retry_settings: RetrySettings::default(),
             headers: HashMap::new(),
+            batch_concurrency: DEFAULT_BATCH_SIZE as usize,
         }
     }
 }
@@ -0,0 +1,573 @@
+use crate::{format_quantity, utils::parse_lovelace, BlockfrostAPI, BlockfrostResult, Pagination};
+use crate::{BlockfrostError, ChainTime, Order, DEFAULT_PAGINATION_PAGE_ITEMS_COUNT};
+use blockfrost_openapi::models::account_reward_content_inner::AccountRewardContentInner;
+use futures::{future, stream, Future, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
+
+/// Epochs the latest epoch must be ahead of an epoch for it to be exported.
+///
+/// Rewards earned during an epoch are only calculated once the following one ends, so the two
+/// most recent epochs can still change.
+pub const STAKING_EXPORT_EPOCH_LAG: i32 = 2;
+
+/// Kind of a row in a staking history export.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StakingEventType {
+    /// Reward for operating the delegated pool.
+    LeaderReward,
+    /// Reward for delegating to a pool.
+    MemberReward,
+    /// Refund of the deposit of a retired pool.
+    PoolDepositRefund,
+    /// Withdrawal of rewards to a payment address.
+    Withdrawal,
+    /// Delegation to a pool, effective two epochs later.
+    Delegation,
+    /// Registration of the stake address.
+    Registration,
+    /// Deregistration of the stake address.
+    Deregistration,
+    /// Transfer from the reserves or the treasury (move instantaneous rewards).
+    Mir,
+}
+
+impl StakingEventType {
+    /// Name of the type as written in exports.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StakingEventType::LeaderReward => "leader_reward",
+            StakingEventType::MemberReward => "member_reward",
+            StakingEventType::PoolDepositRefund => "pool_deposit_refund",
+            StakingEventType::Withdrawal => "withdrawal",
+            StakingEventType::Delegation => "delegation",
+            StakingEventType::Registration => "registration",
+            StakingEventType::Deregistration => "deregistration",
+            StakingEventType::Mir => "mir",
+        }
+    }
+}
+
+/// A reward, withdrawal, delegation, registration or MIR of a stake address.
+///
+/// Times are Unix timestamps in seconds (UTC).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StakingEvent {
+    /// Epoch the reward was earned in, or the epoch of the transaction.
+    pub epoch: i32,
+    pub epoch_start: i64,
+    pub epoch_end: i64,
+    pub event_type: StakingEventType,
+    /// Pool that paid the reward or that was delegated to.
+    pub pool_id: Option<String>,
+    /// Transaction of the event, for everything but rewards.
+    pub tx_hash: Option<String>,
+    /// Amount in lovelace, for rewards, withdrawals and MIRs.
+    pub amount: Option<u64>,
+    /// Block time of the transaction, or the end of the epoch for rewards.
+    pub timestamp: i64,
+}
+
+impl StakingEvent {
+    /// Create an event from an item of [`accounts_rewards`](BlockfrostAPI::accounts_rewards).
+    ///
+    /// Epoch times and the timestamp are left to zero, as they need
+    /// [`epochs_by_number`](BlockfrostAPI::epochs_by_number).
+    pub fn from_reward(reward: &AccountRewardContentInner) -> BlockfrostResult<Self> {
+        let reward_type = string_field(reward, "type")?;
+        let event_type = match reward_type.as_str() {
+            "leader" => StakingEventType::LeaderReward,
+            "member" => StakingEventType::MemberReward,
+            "pool_deposit_refund" => StakingEventType::PoolDepositRefund,
+            _ => {
+                return Err(BlockfrostError::Parsing {
+                    message: format!("unknown reward type '{reward_type}'"),
+                })
+            }
+        };
+
+        Ok(Self {
+            epoch: reward.epoch,
+            epoch_start: 0,
+            epoch_end: 0,
+            event_type,
+            pool_id: Some(reward.pool_id.clone()),
+            tx_hash: None,
+            amount: Some(parse_lovelace(&reward.amount)?),
+            timestamp: 0,
+        })
+    }
+
+    /// Amount in ADA, such as `12.695385`.
+    pub fn amount_ada(&self) -> Option<String> {
+        self.amount
+            .map(|amount| format_quantity(&amount.to_string(), 6).unwrap_or_default())
+    }
+
+    fn row(&self) -> StakingExportRow<'_> {
+        StakingExportRow {
+            epoch: self.epoch,
+            epoch_start: self.epoch_start,
+            epoch_end: self.epoch_end,
+            event_type: self.event_type,
+            pool_id: self.pool_id.as_deref(),
+            tx_hash: self.tx_hash.as_deref(),
+            amount_lovelace: self.amount,
+            amount_ada: self.amount_ada(),
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+/// A line of an export, in the order of the CSV columns.
+#[derive(Serialize)]
+struct StakingExportRow<'a> {
+    epoch: i32,
+    epoch_start: i64,
+    epoch_end: i64,
+    #[serde(rename = "type")]
+    event_type: StakingEventType,
+    pool_id: Option<&'a str>,
+    tx_hash: Option<&'a str>,
+    amount_lovelace: Option<u64>,
+    amount_ada: Option<String>,
+    timestamp: i64,
+}
+
+const CSV_HEADER: &str =
+    "epoch,epoch_start,epoch_end,type,pool_id,tx_hash,amount_lovelace,amount_ada,timestamp";
+
+/// Output format of [`StakingHistory::write`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    /// Comma separated values, with a header line.
+    Csv,
+    /// One JSON object per line.
+    Ndjson,
+}
+
+/// Staking events of a stake address, up to an epoch whose data is final.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StakingHistory {
+    pub stake_address: String,
+    /// Last epoch covered, to resume from on the next export.
+    pub last_epoch: i32,
+    /// Events ordered by epoch, then by time.
+    pub events: Vec<StakingEvent>,
+}
+
+impl StakingHistory {
+    /// Write the events in `format`. The CSV `header` is omitted when appending to an export.
+    pub fn write<W: Write>(
+        &self, format: ExportFormat, header: bool, mut writer: W,
+    ) -> BlockfrostResult<()> {
+        if format == ExportFormat::Csv && header {
+            writeln!(writer, "{CSV_HEADER}")?;
+        }
+
+        for event in &self.events {
+            let row = event.row();
+
+            match format {
+                ExportFormat::Csv => writeln!(
+                    writer,
+                    "{},{},{},{},{},{},{},{},{}",
+                    row.epoch,
+                    row.epoch_start,
+                    row.epoch_end,
+                    row.event_type.as_str(),
+                    row.pool_id.unwrap_or_default(),
+                    row.tx_hash.unwrap_or_default(),
+                    row.amount_lovelace
+                        .map(|amount| amount.to_string())
+                        .unwrap_or_default(),
+                    row.amount_ada.unwrap_or_default(),
+                    row.timestamp,
+                )?,
+                ExportFormat::Ndjson => {
+                    serde_json::to_writer(&mut writer, &row).map_err(std::io::Error::from)?;
+                    writeln!(writer)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl BlockfrostAPI {
+    /// Return the rewards, withdrawals, delegations, registrations and MIRs of a stake address,
+    /// after `after_epoch` and up to [`STAKING_EXPORT_EPOCH_LAG`] epochs before the latest one.
+    ///
+    /// Account lists are fetched newest first, stopping at the first event in or before
+    /// `after_epoch`, so resuming an export only looks up the new events. Transactions are looked
+    /// up with [`transaction_by_hash`](Self::transaction_by_hash) and epochs with
+    /// [`epochs_by_number`](Self::epochs_by_number), with at most
+    /// [`batch_concurrency`](crate::BlockFrostSettings::batch_concurrency) requests at a time.
+    pub async fn staking_history(
+        &self, stake_address: &str, after_epoch: Option<i32>,
+    ) -> BlockfrostResult<StakingHistory> {
+        let (latest, chain_time) = futures::try_join!(self.epochs_latest(), self.chain_time())?;
+
+        let last_epoch = latest.epoch - STAKING_EXPORT_EPOCH_LAG;
+        let after = after_epoch.unwrap_or(i32::MIN);
+
+        let (rewards, withdrawals, delegations, registrations, mirs) = futures::try_join!(
+            self.staking_events(
+                |pagination| self.accounts_rewards(stake_address, pagination),
+                |reward| async move { StakingEvent::from_reward(&reward) },
+                after,
+            ),
+            self.staking_events(
+                |pagination| self.accounts_withdrawals(stake_address, pagination),
+                |withdrawal| async move {
+                    let amount = parse_lovelace(&withdrawal.amount)?;
+                    let event_type = StakingEventType::Withdrawal;
+
+                    self.transaction_event(
+                        chain_time,
+                        event_type,
+                        withdrawal.tx_hash,
+                        None,
+                        Some(amount),
+                    )
+                    .await
+                },
+                after,
+            ),
+            self.staking_events(
+                |pagination| self.accounts_delegations(stake_address, pagination),
+                |delegation| async move {
+                    let pool_id = Some(delegation.pool_id);
+                    let event_type = StakingEventType::Delegation;
+
+                    self.transaction_event(
+                        chain_time,
+                        event_type,
+                        delegation.tx_hash,
+                        pool_id,
+                        None,
+                    )
+                    .await
+                },
+                after,
+            ),
+            self.staking_events(
+                |pagination| self.accounts_registrations(stake_address, pagination),
+                |registration| async move {
+                    let event_type = match string_field(&registration, "action")?.as_str() {
+                        "deregistered" => StakingEventType::Deregistration,
+                        _ => StakingEventType::Registration,
+                    };
+
+                    self.transaction_event(chain_time, event_type, registration.tx_hash, None, None)
+                        .await
+                },
+                after,
+            ),
+            self.staking_events(
+                |pagination| self.accounts_mirs(stake_address, pagination),
+                |mir| async move {
+                    let amount = parse_lovelace(&mir.amount)?;
+
+                    self.transaction_event(
+                        chain_time,
+                        StakingEventType::Mir,
+                        mir.tx_hash,
+                        None,
+                        Some(amount),
+                    )
+                    .await
+                },
+                after,
+            ),
+        )?;
+
+        let mut events: Vec<StakingEvent> =
+            [rewards, withdrawals, delegations, registrations, mirs]
+                .into_iter()
+                .flatten()
+                .filter(|event| event.epoch <= last_epoch)
+                .collect();
+
+        let epochs: BTreeSet<i32> = events.iter().map(|event| event.epoch).collect();
+        let bounds: BTreeMap<i32, (i64, i64)> = stream::iter(epochs)
+            .map(|epoch| async move {
+                let epoch = self.epochs_by_number(epoch).await?;
+
+                Ok::<_, BlockfrostError>((
+                    epoch.epoch,
+                    (i64::from(epoch.start_time), i64::from(epoch.end_time)),
+                ))
+            })
+            .buffered(self.batch_concurrency())
+            .try_collect()
+            .await?;
+
+        for event in &mut events {
+            let (start, end) = bounds.get(&event.epoch).copied().unwrap_or_default();
+
+            event.epoch_start = start;
+            event.epoch_end = end;
+
+            if event.tx_hash.is_none() {
+                event.timestamp = end;
+            }
+        }
+
+        events.sort_by_key(|event| (event.epoch, event.timestamp));
+
+        Ok(StakingHistory {
+            stake_address: stake_address.to_string(),
+            last_epoch: last_epoch.max(after_epoch.unwrap_or(last_epoch)),
+            events,
+        })
+    }
+
+    /// Events of a list endpoint of an account, fetched newest first a page at a time until one
+    /// is in or before `after_epoch`.
+    async fn staking_events<T, P, E>(
+        &self, fetch: impl Fn(Pagination) -> P, event: impl Fn(T) -> E, after_epoch: i32,
+    ) -> BlockfrostResult<Vec<StakingEvent>>
+    where
+        P: Future<Output = BlockfrostResult<Vec<T>>>,
+        E: Future<Output = BlockfrostResult<StakingEvent>>,
+    {
+        let mut events = Vec::new();
+
+        for page in 1.. {
+            let pagination =
+                Pagination::new(Order::Desc, page, DEFAULT_PAGINATION_PAGE_ITEMS_COUNT);
+            let items = fetch(pagination).await?;
+            let count = items.len();
+
+            let batch: Vec<StakingEvent> = stream::iter(items)
+                .map(&event)
+                .buffered(self.batch_concurrency())
+                .try_take_while(|event| future::ready(Ok(event.epoch > after_epoch)))
+                .try_collect()
+                .await?;
+            let done = batch.len() < count || count < DEFAULT_PAGINATION_PAGE_ITEMS_COUNT;
+
+            events.extend(batch);
+
+            if done {
+                break;
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Event of a transaction, whose epoch and time are taken from
+    /// [`transaction_by_hash`](Self::transaction_by_hash).
+    async fn transaction_event(
+        &self, chain_time: &ChainTime, event_type: StakingEventType, tx_hash: String,
+        pool_id: Option<String>, amount: Option<u64>,
+    ) -> BlockfrostResult<StakingEvent> {
+        let tx = self.transaction_by_hash(&tx_hash).await?;
+
+        Ok(StakingEvent {
+            epoch: chain_time.slot_to_epoch(tx.slot as u64).epoch as i32,
+            epoch_start: 0,
+            epoch_end: 0,
+            event_type,
+            pool_id,
+            tx_hash: Some(tx_hash),
+            amount,
+            timestamp: i64::from(tx.block_time),
+        })
+    }
+
+    /// Write the [`staking_history`](Self::staking_history) of a stake address to `writer` and
+    /// return the last epoch covered.
+    ///
+    /// To resume an export, pass the returned epoch as `after_epoch` on the next call and append
+    /// to the same output: the CSV header is only written when `after_epoch` is `None`.
+    pub async fn export_staking_history<W: Write>(
+        &self, stake_address: &str, after_epoch: Option<i32>, format: ExportFormat, writer: W,
+    ) -> BlockfrostResult<i32> {
+        let history = self.staking_history(stake_address, after_epoch).await?;
+
+        history.write(format, after_epoch.is_none(), writer)?;
+
+        Ok(history.last_epoch)
+    }
+}
+
+/// String field of an API model, read from its JSON form so generated enum names are not relied
+/// upon.
+fn string_field<T: Serialize>(model: &T, field: &str) -> BlockfrostResult<String> {
+    let value = serde_json::to_value(model).map_err(|reason| BlockfrostError::Parsing {
+        message: format!("invalid {field}: {reason}"),
+    })?;
+
+    Ok(value
+        .get(field)
+        .and_then(|value| value.as_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const POOL_ID: &str = "pool1pu5jlj4q9w9jlxeu370a3c9myx47md5j5m2str0naunn2q3lkdy";
+    const TX_HASH: &str = "48a9625c841eea0dd2bb6cf551eabe6523b7290c9ce34be74eedef2dd8f7ecc5";
+
+    fn history() -> StakingHistory {
+        StakingHistory {
+            stake_address: "stake1ux3g2c9dx2nhhehyrezyxpkstartcqmu9hk63qgfkccw5rqttygt7"
+                .to_string(),
+            last_epoch: 216,
+            events: vec![
+                StakingEvent {
+                    epoch: 215,
+                    epoch_start: 1603403091,
+                    epoch_end: 1603835091,
+                    event_type: StakingEventType::Delegation,
+                    pool_id: Some(POOL_ID.to_string()),
+                    tx_hash: Some(TX_HASH.to_string()),
+                    amount: None,
+                    timestamp: 1603500000,
+                },
+                StakingEvent {
+                    epoch: 215,
+                    epoch_start: 1603403091,
+                    epoch_end: 1603835091,
+                    event_type: StakingEventType::MemberReward,
+                    pool_id: Some(POOL_ID.to_string()),
+                    tx_hash: None,
+                    amount: Some(12695385),
+                    timestamp: 1603835091,
+                },
+            ],
+        }
+    }
+
+    #[rstest]
+    #[case("leader", StakingEventType::LeaderReward)]
+    #[case("member", StakingEventType::MemberReward)]
+    #[case("pool_deposit_refund", StakingEventType::PoolDepositRefund)]
+    fn test_from_reward(#[case] reward_type: &str, #[case] event_type: StakingEventType) {
+        let reward = serde_json::from_value(json!({
+            "epoch": 215,
+            "amount": "12695385",
+            "pool_id": POOL_ID,
+            "type": reward_type
+        }))
+        .unwrap();
+        let event = StakingEvent::from_reward(&reward).unwrap();
+
+        assert_eq!(event.epoch, 215);
+        assert_eq!(event.event_type, event_type);
+        assert_eq!(event.pool_id.as_deref(), Some(POOL_ID));
+        assert_eq!(event.amount, Some(12695385));
+        assert_eq!(event.amount_ada().as_deref(), Some("12.695385"));
+    }
+
+    #[tokio::test]
+    async fn test_staking_events() {
+        let api = BlockfrostAPI::new("mainnetxxxxxxxxxxxxxxxxxxxxxxxxxxx", Default::default());
+        // Epochs of the events of an account, newest first, over two pages
+        let epochs: Vec<i32> = (100..250).rev().collect();
+        let pages = AtomicUsize::new(0);
+        let looked_up = AtomicUsize::new(0);
+
+        let fetch = |pagination: Pagination| {
+            pages.fetch_add(1, Ordering::Relaxed);
+
+            let page = epochs
+                .chunks(DEFAULT_PAGINATION_PAGE_ITEMS_COUNT)
+                .nth(pagination.page - 1)
+                .unwrap_or_default()
+                .to_vec();
+
+            future::ready(Ok(page))
+        };
+        let event = |epoch: i32| {
+            looked_up.fetch_add(1, Ordering::Relaxed);
+
+            let mut event = history().events[1].clone();
+            event.epoch = epoch;
+            future::ready(Ok(event))
+        };
+
+        let events = api.staking_events(fetch, event, 200).await.unwrap();
+
+        assert_eq!(events.len(), 49);
+        assert!(events.iter().all(|event| event.epoch > 200));
+        // Only the first page is fetched, and the lookups stop soon after the resumed epoch
+        assert_eq!(pages.load(Ordering::Relaxed), 1);
+        assert!(looked_up.load(Ordering::Relaxed) <= 50 + api.batch_concurrency());
+
+        let events = api.staking_events(fetch, event, i32::MIN).await.unwrap();
+
+        assert_eq!(events.len(), 150);
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut output = Vec::new();
+
+        history()
+            .write(ExportFormat::Csv, true, &mut output)
+            .unwrap();
+
+        let expected = format!(
+            "{CSV_HEADER}\n\
+             215,1603403091,1603835091,delegation,{POOL_ID},{TX_HASH},,,1603500000\n\
+             215,1603403091,1603835091,member_reward,{POOL_ID},,12695385,12.695385,1603835091\n"
+        );
+
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+
+        // Resumed exports are appended without a header
+        let mut output = Vec::new();
+
+        history()
+            .write(ExportFormat::Csv, false, &mut output)
+            .unwrap();
+
+        assert!(String::from_utf8(output).unwrap().starts_with("215,"));
+    }
+
+    #[test]
+    fn test_write_ndjson() {
+        let mut output = Vec::new();
+
+        history()
+            .write(ExportFormat::Ndjson, true, &mut output)
+            .unwrap();
+
+        let rows: Vec<serde_json::Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[1],
+            json!({
+                "epoch": 215,
+                "epoch_start": 1603403091,
+                "epoch_end": 1603835091,
+                "type": "member_reward",
+                "pool_id": POOL_ID,
+                "tx_hash": null,
+                "amount_lovelace": 12695385,
+                "amount_ada": "12.695385",
+                "timestamp": 1603835091
+            })
+        );
+    }
+}
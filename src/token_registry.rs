@@ -0,0 +1,300 @@
+use crate::{AssetId, BlockfrostAPI, BlockfrostError, BlockfrostResult, LOVELACE_UNIT};
+use blockfrost_openapi::models::asset::Asset;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::PoisonError,
+};
+
+/// Number of assets whose registry metadata is cached by
+/// [`token_registry_metadata`](BlockfrostAPI::token_registry_metadata). Beyond it, the assets
+/// cached first are evicted.
+pub const TOKEN_REGISTRY_CACHE_SIZE: usize = 10_000;
+
+/// Off-chain metadata of a fungible token, from the [CIP-26] token registry.
+///
+/// Read from the `metadata` of [`assets_by_id`](BlockfrostAPI::assets_by_id) with
+/// [`TokenRegistryMetadata::from_asset`], or through
+/// [`token_registry_metadata`](BlockfrostAPI::token_registry_metadata), which caches it.
+///
+/// [CIP-26]: https://cips.cardano.org/cip/CIP-0026
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenRegistryMetadata {
+    pub name: String,
+    pub description: String,
+    pub ticker: Option<String>,
+    pub url: Option<String>,
+    /// Base64-encoded PNG logo.
+    pub logo: Option<String>,
+    /// Number of decimals of quantities, `None` if not registered.
+    pub decimals: Option<u32>,
+}
+
+impl TokenRegistryMetadata {
+    /// Registry metadata of an asset, or `None` if it has no registry entry.
+    pub fn from_asset(asset: &Asset) -> BlockfrostResult<Option<Self>> {
+        let mut asset = serde_json::to_value(asset).map_err(|reason| BlockfrostError::Parsing {
+            message: format!("invalid asset: {reason}"),
+        })?;
+
+        match asset["metadata"].take() {
+            serde_json::Value::Null => Ok(None),
+            metadata => serde_json::from_value::<Self>(metadata)
+                .map(Some)
+                .map_err(|reason| BlockfrostError::Parsing {
+                    message: format!("invalid token registry metadata: {reason}"),
+                }),
+        }
+    }
+
+    /// Metadata of ADA, so lovelace amounts are formatted like other tokens.
+    pub fn ada() -> Self {
+        Self {
+            name: "ADA".to_string(),
+            description: "Native currency of Cardano".to_string(),
+            ticker: Some("ADA".to_string()),
+            url: None,
+            logo: None,
+            decimals: Some(6),
+        }
+    }
+
+    /// Format a raw `quantity` with the token's decimals and ticker, such as `12.5 HOSKY`.
+    ///
+    /// Falls back to the name when the token has no ticker.
+    pub fn format_quantity(&self, quantity: &str) -> BlockfrostResult<String> {
+        let amount = format_quantity(quantity, self.decimals.unwrap_or(0))?;
+
+        Ok(format!(
+            "{amount} {}",
+            self.ticker.as_ref().unwrap_or(&self.name)
+        ))
+    }
+}
+
+/// Format a raw `quantity`, as returned by the API, as a decimal number with `decimals` digits
+/// after the point.
+///
+/// Trailing zeros are removed: `format_quantity("12500000", 6)` is `12.5`.
+pub fn format_quantity(quantity: &str, decimals: u32) -> BlockfrostResult<String> {
+    let (sign, digits) = match quantity.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", quantity),
+    };
+
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(BlockfrostError::Parsing {
+            message: format!("invalid quantity '{quantity}'"),
+        });
+    }
+
+    let digits = digits.trim_start_matches('0');
+    let decimals = decimals as usize;
+    // Pad so there is at least one digit before the point.
+    let digits = format!("{digits:0>width$}", width = decimals + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+
+    let sign = if integer == "0" && fraction.is_empty() {
+        ""
+    } else {
+        sign
+    };
+
+    if fraction.is_empty() {
+        Ok(format!("{sign}{integer}"))
+    } else {
+        Ok(format!("{sign}{integer}.{fraction}"))
+    }
+}
+
+/// Registry metadata by asset unit, `None` for assets without a registry entry, keeping the last
+/// [`TOKEN_REGISTRY_CACHE_SIZE`] assets inserted.
+#[derive(Debug, Default)]
+pub(crate) struct TokenRegistryCache {
+    entries: HashMap<String, Option<TokenRegistryMetadata>>,
+    // Cached units, oldest first.
+    units: VecDeque<String>,
+}
+
+impl TokenRegistryCache {
+    fn get(&self, asset: &str) -> Option<&Option<TokenRegistryMetadata>> {
+        self.entries.get(asset)
+    }
+
+    pub(crate) fn insert(&mut self, asset: String, metadata: Option<TokenRegistryMetadata>) {
+        if self.entries.insert(asset.clone(), metadata).is_some() {
+            return;
+        }
+
+        self.units.push_back(asset);
+
+        if self.units.len() > TOKEN_REGISTRY_CACHE_SIZE {
+            if let Some(oldest) = self.units.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.units.clear();
+    }
+}
+
+impl BlockfrostAPI {
+    /// Return the [CIP-26] registry metadata of `asset`, or `None` if it has no registry entry.
+    ///
+    /// Results, including missing entries, are cached and shared by clones of this client, so
+    /// repeated lookups don't call [`assets_by_id`](Self::assets_by_id) again. The cache holds up
+    /// to [`TOKEN_REGISTRY_CACHE_SIZE`] assets, and is emptied by
+    /// [`clear_token_registry_cache`](Self::clear_token_registry_cache).
+    ///
+    /// [CIP-26]: https://cips.cardano.org/cip/CIP-0026
+    pub async fn token_registry_metadata(
+        &self, asset: &str,
+    ) -> BlockfrostResult<Option<TokenRegistryMetadata>> {
+        if asset == LOVELACE_UNIT {
+            return Ok(Some(TokenRegistryMetadata::ada()));
+        }
+
+        let cached = self
+            .token_registry
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(asset)
+            .cloned();
+        if let Some(metadata) = cached {
+            return Ok(metadata);
+        }
+
+        let metadata = TokenRegistryMetadata::from_asset(&self.assets_by_id(asset).await?)?;
+
+        self.token_registry
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(asset.to_string(), metadata.clone());
+
+        Ok(metadata)
+    }
+
+    /// Forget the registry metadata cached by
+    /// [`token_registry_metadata`](Self::token_registry_metadata), for this client and its clones,
+    /// so that registry updates are fetched again.
+    pub fn clear_token_registry_cache(&self) {
+        self.token_registry
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Format a raw `quantity` of `asset` for display, such as `12.5 HOSKY`.
+    ///
+    /// Uses the registry metadata of the asset, see
+    /// [`token_registry_metadata`](Self::token_registry_metadata). Assets without a registry
+    /// entry are formatted without decimals, followed by their name.
+    pub async fn format_asset_quantity(
+        &self, asset: &str, quantity: &str,
+    ) -> BlockfrostResult<String> {
+        match self.token_registry_metadata(asset).await? {
+            Some(metadata) => metadata.format_quantity(quantity),
+            None => {
+                let amount = format_quantity(quantity, 0)?;
+
+                Ok(format!(
+                    "{amount} {}",
+                    AssetId::from_unit(asset)?.display_name()
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use serde_json::json;
+
+    #[rstest]
+    #[case("12500000", 6, "12.5")]
+    #[case("12000000", 6, "12")]
+    #[case("1", 6, "0.000001")]
+    #[case("0", 6, "0")]
+    #[case("000123", 2, "1.23")]
+    #[case("12500000", 0, "12500000")]
+    #[case("-1500", 3, "-1.5")]
+    #[case("-0", 3, "0")]
+    #[case(
+        "340282366920938463463374607431768211455",
+        18,
+        "340282366920938463463.374607431768211455"
+    )]
+    fn test_format_quantity(#[case] quantity: &str, #[case] decimals: u32, #[case] expected: &str) {
+        assert_eq!(format_quantity(quantity, decimals).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("-")]
+    #[case("1.5")]
+    #[case("+1")]
+    #[case("12 ")]
+    fn test_format_quantity_invalid(#[case] quantity: &str) {
+        assert!(format_quantity(quantity, 6).is_err());
+    }
+
+    #[test]
+    fn test_cache() {
+        let mut cache = TokenRegistryCache::default();
+
+        for index in 0..=TOKEN_REGISTRY_CACHE_SIZE {
+            cache.insert(index.to_string(), None);
+        }
+        // Updating an entry doesn't evict anything
+        cache.insert(TOKEN_REGISTRY_CACHE_SIZE.to_string(), None);
+
+        assert_eq!(cache.entries.len(), TOKEN_REGISTRY_CACHE_SIZE);
+        assert_eq!(cache.units.len(), TOKEN_REGISTRY_CACHE_SIZE);
+        assert!(cache.get("0").is_none());
+        assert!(cache.get("1").is_some());
+
+        cache.clear();
+        assert!(cache.get("1").is_none());
+        assert!(cache.units.is_empty());
+    }
+
+    #[test]
+    fn test_from_asset() {
+        let json_value = json!({
+            "asset": "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a76e7574636f696e",
+            "policy_id": "b0d07d45fe9514f80213f4020e5a61241458be626841cde717cb38a7",
+            "asset_name": "6e7574636f696e",
+            "fingerprint": "asset1pkpwyknlvul7az0xx8czhl60pyel45rpje4z8w",
+            "quantity": "12000",
+            "initial_mint_tx_hash": "6804edf9712d2b619edb6ac86861fe93a730693183a262b165fcc1ba1bc99cad",
+            "mint_or_burn_count": 1,
+            "onchain_metadata": null,
+            "metadata": {
+                "name": "nutcoin",
+                "description": "The Nut Coin",
+                "ticker": "nutc",
+                "url": "https://www.stakenuts.com/",
+                "logo": "iVBORw0KGgoAAAAAAABGdBTUEAALGPC/xhBQAAAAAASUVORK5CYII=",
+                "decimals": 6
+            }
+        });
+        let asset = serde_json::from_value::<Asset>(json_value).unwrap();
+        let metadata = TokenRegistryMetadata::from_asset(&asset).unwrap().unwrap();
+
+        assert_eq!(metadata.ticker.as_deref(), Some("nutc"));
+        assert_eq!(metadata.decimals, Some(6));
+        assert_eq!(metadata.format_quantity("12500000").unwrap(), "12.5 nutc");
+        assert_eq!(
+            TokenRegistryMetadata::ada()
+                .format_quantity("1000000")
+                .unwrap(),
+            "1 ADA"
+        );
+    }
+}
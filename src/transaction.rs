@@ -0,0 +1,1526 @@
+use crate::{
+    cbor::{Decoder, MAJOR_ARRAY, MAJOR_BYTES, MAJOR_MAP, MAJOR_TAG, MAJOR_TEXT},
+    utils::{blake2b_224, blake2b_256},
+    BlockfrostAPI, BlockfrostError, BlockfrostResult, ExUnits, GovActionId, Pagination,
+    RedeemerPurpose,
+};
+use blockfrost_openapi::models::block_content_txs_cbor_inner::BlockContentTxsCborInner;
+use std::{
+    collections::{BTreeMap, HashSet},
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+/// Native assets, by hex policy ID then hex asset name.
+pub type MultiAsset<T> = BTreeMap<String, BTreeMap<String, T>>;
+
+/// A transaction decoded from its CBOR representation.
+///
+/// Returned by [`transactions_cbor_decoded`](BlockfrostAPI::transactions_cbor_decoded) and
+/// [`blocks_txs_cbor_decoded`](BlockfrostAPI::blocks_txs_cbor_decoded), or built with
+/// [`Transaction::from_cbor`]. Transactions from Shelley to Conway are supported.
+///
+/// Hashes, keys and addresses are hex encoded, like in the rest of the API.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transaction {
+    /// Hash of the transaction, recomputed from the body bytes.
+    pub hash: String,
+    pub body: TransactionBody,
+    pub witness_set: WitnessSet,
+    /// `false` if the scripts of the transaction failed and only its collateral was consumed.
+    pub is_valid: bool,
+    pub auxiliary_data: Option<AuxiliaryData>,
+    /// Size of the serialized transaction, in bytes.
+    pub size: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TransactionBody {
+    pub inputs: Vec<TransactionInput>,
+    pub outputs: Vec<TransactionOutput>,
+    pub fee: u64,
+    /// Slot after which the transaction is invalid.
+    pub ttl: Option<u64>,
+    pub certificates: Vec<Certificate>,
+    /// Amounts withdrawn, by hex reward account.
+    pub withdrawals: BTreeMap<String, u64>,
+    pub auxiliary_data_hash: Option<String>,
+    /// Slot before which the transaction is invalid.
+    pub validity_interval_start: Option<u64>,
+    /// Minted (positive) and burned (negative) assets.
+    pub mint: MultiAsset<i64>,
+    pub script_data_hash: Option<String>,
+    pub collateral: Vec<TransactionInput>,
+    /// Hashes of the keys required to sign the transaction.
+    pub required_signers: Vec<String>,
+    pub network_id: Option<u8>,
+    pub collateral_return: Option<TransactionOutput>,
+    pub total_collateral: Option<u64>,
+    pub reference_inputs: Vec<TransactionInput>,
+    pub voting_procedures: Vec<VotingProcedure>,
+    pub proposal_procedures: Vec<ProposalProcedure>,
+    pub current_treasury_value: Option<u64>,
+    pub donation: Option<u64>,
+}
+
+/// Reference to the output of a previous transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TransactionInput {
+    pub tx_hash: String,
+    pub index: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransactionOutput {
+    /// Raw address bytes, in hex.
+    pub address: String,
+    pub value: Value,
+    pub datum: Option<Datum>,
+    pub script_ref: Option<Script>,
+}
+
+/// Amount of lovelace and native assets.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Value {
+    pub coin: u64,
+    pub assets: MultiAsset<u64>,
+}
+
+/// Datum attached to an output.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Datum {
+    /// Hash of a datum, provided in the witness set of the spending transaction.
+    Hash(String),
+    /// Raw CBOR of an inline datum.
+    Inline(Vec<u8>),
+}
+
+/// A script, with its raw bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Script {
+    /// CBOR of a native (timelock) script.
+    Native(Vec<u8>),
+    /// Flat-encoded Plutus V1 program, wrapped in a CBOR byte string.
+    PlutusV1(Vec<u8>),
+    PlutusV2(Vec<u8>),
+    PlutusV3(Vec<u8>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Credential {
+    /// Hash of a verification key.
+    Key(String),
+    Script(String),
+}
+
+/// Delegated representative a stake credential delegates its voting power to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DRep {
+    Key(String),
+    Script(String),
+    AlwaysAbstain,
+    AlwaysNoConfidence,
+}
+
+/// Link to off-chain metadata, with the blake2b-256 hash of its content.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Anchor {
+    pub url: String,
+    pub data_hash: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Certificate {
+    StakeRegistration(Credential),
+    StakeDeregistration(Credential),
+    StakeDelegation {
+        credential: Credential,
+        pool: String,
+    },
+    PoolRegistration(Box<PoolParams>),
+    PoolRetirement {
+        pool: String,
+        epoch: u64,
+    },
+    /// Conway stake registration, with an explicit deposit.
+    Registration {
+        credential: Credential,
+        deposit: u64,
+    },
+    /// Conway stake deregistration, with an explicit refund.
+    Unregistration {
+        credential: Credential,
+        refund: u64,
+    },
+    VoteDelegation {
+        credential: Credential,
+        drep: DRep,
+    },
+    StakeVoteDelegation {
+        credential: Credential,
+        pool: String,
+        drep: DRep,
+    },
+    StakeRegistrationDelegation {
+        credential: Credential,
+        pool: String,
+        deposit: u64,
+    },
+    VoteRegistrationDelegation {
+        credential: Credential,
+        drep: DRep,
+        deposit: u64,
+    },
+    StakeVoteRegistrationDelegation {
+        credential: Credential,
+        pool: String,
+        drep: DRep,
+        deposit: u64,
+    },
+    AuthCommitteeHot {
+        cold: Credential,
+        hot: Credential,
+    },
+    ResignCommitteeCold {
+        cold: Credential,
+        anchor: Option<Anchor>,
+    },
+    DRepRegistration {
+        credential: Credential,
+        deposit: u64,
+        anchor: Option<Anchor>,
+    },
+    DRepUnregistration {
+        credential: Credential,
+        refund: u64,
+    },
+    DRepUpdate {
+        credential: Credential,
+        anchor: Option<Anchor>,
+    },
+    /// Pre-Conway certificates (genesis key delegation and MIRs), with their raw CBOR.
+    Legacy {
+        kind: u64,
+        cbor: Vec<u8>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolParams {
+    pub operator: String,
+    pub vrf_key_hash: String,
+    pub pledge: u64,
+    pub cost: u64,
+    /// Margin as a `(numerator, denominator)` fraction.
+    pub margin: (u64, u64),
+    pub reward_account: String,
+    pub owners: Vec<String>,
+    pub relays: Vec<Relay>,
+    pub metadata: Option<Anchor>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Relay {
+    SingleHostAddr {
+        port: Option<u16>,
+        ipv4: Option<Ipv4Addr>,
+        ipv6: Option<Ipv6Addr>,
+    },
+    SingleHostName {
+        port: Option<u16>,
+        dns_name: String,
+    },
+    MultiHostName {
+        dns_name: String,
+    },
+}
+
+/// A vote cast in the transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VotingProcedure {
+    pub voter: Voter,
+    pub action_id: GovActionId,
+    pub vote: Vote,
+    pub anchor: Option<Anchor>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Voter {
+    CommitteeKey(String),
+    CommitteeScript(String),
+    DRepKey(String),
+    DRepScript(String),
+    StakePool(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Vote {
+    No,
+    Yes,
+    Abstain,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProposalProcedure {
+    pub deposit: u64,
+    /// Reward account receiving the deposit back, in hex.
+    pub reward_account: String,
+    pub gov_action: GovAction,
+    pub anchor: Anchor,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GovAction {
+    ParameterChange {
+        prev_action: Option<GovActionId>,
+        /// Raw CBOR of the protocol parameters update.
+        update: Vec<u8>,
+        policy_hash: Option<String>,
+    },
+    HardForkInitiation {
+        prev_action: Option<GovActionId>,
+        protocol_version: (u64, u64),
+    },
+    TreasuryWithdrawals {
+        withdrawals: BTreeMap<String, u64>,
+        policy_hash: Option<String>,
+    },
+    NoConfidence {
+        prev_action: Option<GovActionId>,
+    },
+    UpdateCommittee {
+        prev_action: Option<GovActionId>,
+        removed: Vec<Credential>,
+        /// New members, with the epoch at which their term ends.
+        added: Vec<(Credential, u64)>,
+        /// Quorum as a `(numerator, denominator)` fraction.
+        threshold: (u64, u64),
+    },
+    NewConstitution {
+        prev_action: Option<GovActionId>,
+        anchor: Anchor,
+        script_hash: Option<String>,
+    },
+    Info,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WitnessSet {
+    pub vkey_witnesses: Vec<VKeyWitness>,
+    pub native_scripts: Vec<Script>,
+    pub bootstrap_witnesses: Vec<BootstrapWitness>,
+    pub plutus_scripts: Vec<Script>,
+    /// Raw CBOR of the datums provided by the transaction.
+    pub plutus_data: Vec<Vec<u8>>,
+    pub redeemers: Vec<Redeemer>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VKeyWitness {
+    pub vkey: String,
+    pub signature: String,
+}
+
+/// Witness of a Byron address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BootstrapWitness {
+    pub public_key: String,
+    pub signature: String,
+    pub chain_code: String,
+    pub attributes: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Redeemer {
+    pub purpose: RedeemerPurpose,
+    pub index: u32,
+    /// Raw CBOR of the redeemer data.
+    pub data: Vec<u8>,
+    pub ex_units: ExUnits,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AuxiliaryData {
+    /// Metadata, by label.
+    pub metadata: BTreeMap<u64, Metadatum>,
+    pub scripts: Vec<Script>,
+}
+
+/// A transaction metadata value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Metadatum {
+    Int(i128),
+    Bytes(Vec<u8>),
+    Text(String),
+    List(Vec<Metadatum>),
+    Map(Vec<(Metadatum, Metadatum)>),
+}
+
+impl Transaction {
+    /// Decode a transaction from its CBOR bytes.
+    pub fn from_cbor(data: &[u8]) -> BlockfrostResult<Self> {
+        decode_transaction(data).map(|(transaction, _)| transaction)
+    }
+
+    /// Decode a transaction from its hex-encoded CBOR, as returned by the API.
+    pub fn from_hex(cbor: &str) -> BlockfrostResult<Self> {
+        let data = hex::decode(cbor).map_err(|reason| BlockfrostError::Cbor {
+            message: format!("invalid hex: {reason}"),
+        })?;
+
+        Self::from_cbor(&data)
+    }
+
+    /// Check that the recomputed hash of the transaction is `expected`.
+    pub fn verify_hash(&self, expected: &str) -> BlockfrostResult<()> {
+        if self.hash.eq_ignore_ascii_case(expected) {
+            Ok(())
+        } else {
+            Err(BlockfrostError::HashMismatch {
+                expected: expected.to_string(),
+                computed: self.hash.clone(),
+            })
+        }
+    }
+
+    /// Decode a transaction about to be submitted and [`validate`](Transaction::validate) it.
+    ///
+    /// Only the Conway format, with the validity flag, is accepted.
+    pub fn validate_submission(data: &[u8]) -> BlockfrostResult<Self> {
+        let (transaction, has_validity_flag) = decode_transaction(data)?;
+
+        // Transactions have four elements since Alonzo, the older format is rejected by Conway.
+        if !has_validity_flag {
+            return Err(invalid_transaction("expected a Conway transaction"));
+        }
+
+        transaction.validate()?;
+        Ok(transaction)
+    }
+
+    /// Check that the transaction is not obviously invalid.
+    ///
+    /// Fails with [`BlockfrostError::InvalidTransaction`] if the transaction has no inputs or no
+    /// witnesses at all, lacks the signature of a required signer, or holds certificates removed
+    /// in Conway. This doesn't replace the validation done by the node.
+    pub fn validate(&self) -> BlockfrostResult<()> {
+        let witness_set = &self.witness_set;
+
+        if self.body.inputs.is_empty() {
+            return Err(invalid_transaction("transaction has no inputs"));
+        }
+
+        if let Some(Certificate::Legacy { kind, .. }) = self
+            .body
+            .certificates
+            .iter()
+            .find(|certificate| matches!(certificate, Certificate::Legacy { .. }))
+        {
+            return Err(invalid_transaction(format!(
+                "certificate of kind {kind} is not allowed since Conway"
+            )));
+        }
+
+        if witness_set.vkey_witnesses.is_empty()
+            && witness_set.bootstrap_witnesses.is_empty()
+            && witness_set.native_scripts.is_empty()
+            && witness_set.plutus_scripts.is_empty()
+        {
+            return Err(invalid_transaction("transaction has no witnesses"));
+        }
+
+        let signers: HashSet<String> = witness_set
+            .vkey_witnesses
+            .iter()
+            .filter_map(|witness| hex::decode(&witness.vkey).ok())
+            .map(|vkey| hex::encode(blake2b_224(&vkey)))
+            .collect();
+
+        if let Some(signer) = self
+            .body
+            .required_signers
+            .iter()
+            .find(|signer| !signers.contains(*signer))
+        {
+            return Err(invalid_transaction(format!(
+                "missing signature of required signer {signer}"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl BlockfrostAPI {
+    /// Validate and submit an already serialized transaction to the network.
+    ///
+    /// Unlike [`transactions_submit`](BlockfrostAPI::transactions_submit), the transaction is
+    /// checked with [`Transaction::validate_submission`] before being sent, and the hash returned
+    /// by the server is checked against the hash computed locally.
+    pub async fn transactions_submit_checked(
+        &self, transaction_data: Vec<u8>,
+    ) -> BlockfrostResult<String> {
+        let transaction = Transaction::validate_submission(&transaction_data)?;
+        let hash = self.transactions_submit(transaction_data).await?;
+
+        transaction.verify_hash(&hash)?;
+        Ok(transaction.hash)
+    }
+
+    /// Return the requested transaction decoded from its CBOR.
+    ///
+    /// Fails with [`BlockfrostError::HashMismatch`] if the hash of the returned transaction is
+    /// not `hash`.
+    pub async fn transactions_cbor_decoded(&self, hash: &str) -> BlockfrostResult<Transaction> {
+        let transaction = Transaction::from_hex(&self.transactions_cbor(hash).await?.cbor)?;
+
+        transaction.verify_hash(hash)?;
+        Ok(transaction)
+    }
+
+    /// Return the transactions within a specific block, decoded from their CBOR.
+    pub async fn blocks_txs_cbor_decoded(
+        &self, hash_or_number: &str, pagination: Pagination,
+    ) -> BlockfrostResult<Vec<Transaction>> {
+        decode_block_txs(self.blocks_txs_cbor(hash_or_number, pagination).await?)
+    }
+
+    /// Return the transactions within the latest block, decoded from their CBOR.
+    pub async fn blocks_latest_txs_cbor_decoded(
+        &self, pagination: Pagination,
+    ) -> BlockfrostResult<Vec<Transaction>> {
+        decode_block_txs(self.blocks_latest_txs_cbor(pagination).await?)
+    }
+}
+
+fn invalid_transaction(reason: impl ToString) -> BlockfrostError {
+    BlockfrostError::InvalidTransaction {
+        reason: reason.to_string(),
+    }
+}
+
+fn decode_block_txs(txs: Vec<BlockContentTxsCborInner>) -> BlockfrostResult<Vec<Transaction>> {
+    txs.into_iter()
+        .map(|tx| {
+            let transaction = Transaction::from_hex(&tx.cbor)?;
+
+            transaction.verify_hash(&tx.tx_hash)?;
+            Ok(transaction)
+        })
+        .collect()
+}
+
+// Decode a transaction, telling whether it has the validity flag added in Alonzo.
+fn decode_transaction(data: &[u8]) -> BlockfrostResult<(Transaction, bool)> {
+    let mut decoder = Decoder::new(data);
+    let length = decoder.array()?;
+
+    if !matches!(length, Some(3 | 4) | None) {
+        return Err(decoder.error("expected a transaction of 3 or 4 elements"));
+    }
+
+    let body_bytes = decoder.raw()?;
+    let body = decode_body(&mut Decoder::new(body_bytes))?;
+    let witness_set = decode_witness_set(&mut decoder)?;
+    // Only transactions since Alonzo have the validity flag.
+    let has_validity_flag = match length {
+        Some(length) => length == 4,
+        None => decoder.is_bool()?,
+    };
+    let is_valid = if has_validity_flag {
+        decoder.bool()?
+    } else {
+        true
+    };
+    let auxiliary_data = decoder.nullable(decode_auxiliary_data)?;
+
+    if length.is_none() {
+        decoder.end()?;
+    }
+
+    if !decoder.is_empty() {
+        return Err(decoder.error("trailing bytes after transaction"));
+    }
+
+    let transaction = Transaction {
+        hash: hex::encode(blake2b_256(body_bytes)),
+        body,
+        witness_set,
+        is_valid,
+        auxiliary_data,
+        size: data.len(),
+    };
+
+    Ok((transaction, has_validity_flag))
+}
+
+fn decode_body(decoder: &mut Decoder) -> BlockfrostResult<TransactionBody> {
+    let mut body = TransactionBody::default();
+
+    for (key, raw) in decoder.map_of(Decoder::u64, Decoder::raw)? {
+        let decoder = &mut Decoder::new(raw);
+
+        match key {
+            0 => body.inputs = decoder.set_of(decode_input)?,
+            1 => body.outputs = decoder.array_of(decode_output)?,
+            2 => body.fee = decoder.u64()?,
+            3 => body.ttl = Some(decoder.u64()?),
+            4 => body.certificates = decoder.set_of(decode_certificate)?,
+            5 => body.withdrawals = decode_withdrawals(decoder)?,
+            7 => body.auxiliary_data_hash = Some(decoder.hex::<32>()?),
+            8 => body.validity_interval_start = Some(decoder.u64()?),
+            9 => body.mint = decode_multi_asset(decoder, decode_i64)?,
+            11 => body.script_data_hash = Some(decoder.hex::<32>()?),
+            13 => body.collateral = decoder.set_of(decode_input)?,
+            14 => body.required_signers = decoder.set_of(Decoder::hex::<28>)?,
+            15 => {
+                let network_id = decoder.u64()?;
+                let network_id = u8::try_from(network_id)
+                    .map_err(|_| decoder.error(format!("invalid network id {network_id}")))?;
+
+                body.network_id = Some(network_id);
+            }
+            16 => body.collateral_return = Some(decode_output(decoder)?),
+            17 => body.total_collateral = Some(decoder.u64()?),
+            18 => body.reference_inputs = decoder.set_of(decode_input)?,
+            19 => body.voting_procedures = decode_voting_procedures(decoder)?,
+            20 => body.proposal_procedures = decoder.set_of(decode_proposal_procedure)?,
+            21 => body.current_treasury_value = Some(decoder.u64()?),
+            22 => body.donation = Some(decoder.u64()?),
+            // Fields from older eras, such as protocol parameter updates.
+            _ => {}
+        }
+    }
+
+    Ok(body)
+}
+
+fn decode_input(decoder: &mut Decoder) -> BlockfrostResult<TransactionInput> {
+    decoder.tuple(|decoder| {
+        Ok(TransactionInput {
+            tx_hash: decoder.hex::<32>()?,
+            index: decoder.u32()?,
+        })
+    })
+}
+
+fn decode_output(decoder: &mut Decoder) -> BlockfrostResult<TransactionOutput> {
+    if decoder.peek_major()? == MAJOR_ARRAY {
+        // Legacy output: [address, value, ?datum_hash]
+        let length = decoder.array()?;
+
+        if !matches!(length, Some(2 | 3) | None) {
+            return Err(decoder.error("expected an output of 2 or 3 elements"));
+        }
+
+        let address = hex::encode(decoder.bytes()?);
+        let value = decode_value(decoder)?;
+        let has_datum = match length {
+            Some(length) => length == 3,
+            None => !decoder.is_break()?,
+        };
+        let datum = if has_datum {
+            Some(Datum::Hash(decoder.hex::<32>()?))
+        } else {
+            None
+        };
+
+        if length.is_none() {
+            decoder.end()?;
+        }
+
+        return Ok(TransactionOutput {
+            address,
+            value,
+            datum,
+            script_ref: None,
+        });
+    }
+
+    let mut output = TransactionOutput {
+        address: String::new(),
+        value: Value::default(),
+        datum: None,
+        script_ref: None,
+    };
+
+    for (key, raw) in decoder.map_of(Decoder::u64, Decoder::raw)? {
+        let decoder = &mut Decoder::new(raw);
+
+        match key {
+            0 => output.address = hex::encode(decoder.bytes()?),
+            1 => output.value = decode_value(decoder)?,
+            2 => output.datum = Some(decode_datum(decoder)?),
+            3 => output.script_ref = Some(decode_script_ref(decoder)?),
+            _ => {}
+        }
+    }
+
+    Ok(output)
+}
+
+fn decode_value(decoder: &mut Decoder) -> BlockfrostResult<Value> {
+    if decoder.peek_major()? == MAJOR_ARRAY {
+        decoder.tuple(|decoder| {
+            Ok(Value {
+                coin: decoder.u64()?,
+                assets: decode_multi_asset(decoder, Decoder::u64)?,
+            })
+        })
+    } else {
+        Ok(Value {
+            coin: decoder.u64()?,
+            assets: MultiAsset::new(),
+        })
+    }
+}
+
+fn decode_multi_asset<'a, T>(
+    decoder: &mut Decoder<'a>,
+    mut decode_amount: impl FnMut(&mut Decoder<'a>) -> BlockfrostResult<T>,
+) -> BlockfrostResult<MultiAsset<T>> {
+    let policies = decoder.map_of(Decoder::hex::<28>, |decoder| {
+        let assets = decoder.map_of(
+            |decoder| decoder.bytes().map(hex::encode),
+            &mut decode_amount,
+        )?;
+
+        Ok(assets.into_iter().collect())
+    })?;
+
+    Ok(policies.into_iter().collect())
+}
+
+fn decode_i64(decoder: &mut Decoder) -> BlockfrostResult<i64> {
+    let value = decoder.int()?;
+
+    i64::try_from(value).map_err(|_| decoder.error(format!("integer {value} is out of range")))
+}
+
+fn decode_datum(decoder: &mut Decoder) -> BlockfrostResult<Datum> {
+    decoder.tuple(|decoder| match decoder.u64()? {
+        0 => Ok(Datum::Hash(decoder.hex::<32>()?)),
+        1 => Ok(Datum::Inline(decoder.embedded_cbor()?)),
+        kind => Err(decoder.error(format!("unknown datum option {kind}"))),
+    })
+}
+
+// Script reference: tag 24 wrapping `[language, script]`.
+fn decode_script_ref(decoder: &mut Decoder) -> BlockfrostResult<Script> {
+    let bytes = decoder.embedded_cbor()?;
+
+    decode_script(&mut Decoder::new(&bytes))
+}
+
+fn decode_script(decoder: &mut Decoder) -> BlockfrostResult<Script> {
+    decoder.tuple(|decoder| match decoder.u64()? {
+        0 => decode_native_script(decoder),
+        1 => Ok(Script::PlutusV1(decoder.bytes()?)),
+        2 => Ok(Script::PlutusV2(decoder.bytes()?)),
+        3 => Ok(Script::PlutusV3(decoder.bytes()?)),
+        language => Err(decoder.error(format!("unknown script language {language}"))),
+    })
+}
+
+fn decode_withdrawals(decoder: &mut Decoder) -> BlockfrostResult<BTreeMap<String, u64>> {
+    let withdrawals = decoder.map_of(|decoder| decoder.bytes().map(hex::encode), Decoder::u64)?;
+
+    Ok(withdrawals.into_iter().collect())
+}
+
+fn decode_credential(decoder: &mut Decoder) -> BlockfrostResult<Credential> {
+    decoder.tuple(|decoder| match decoder.u64()? {
+        0 => Ok(Credential::Key(decoder.hex::<28>()?)),
+        1 => Ok(Credential::Script(decoder.hex::<28>()?)),
+        kind => Err(decoder.error(format!("unknown credential kind {kind}"))),
+    })
+}
+
+fn decode_drep(decoder: &mut Decoder) -> BlockfrostResult<DRep> {
+    decoder.tuple(|decoder| match decoder.u64()? {
+        0 => Ok(DRep::Key(decoder.hex::<28>()?)),
+        1 => Ok(DRep::Script(decoder.hex::<28>()?)),
+        2 => Ok(DRep::AlwaysAbstain),
+        3 => Ok(DRep::AlwaysNoConfidence),
+        kind => Err(decoder.error(format!("unknown DRep kind {kind}"))),
+    })
+}
+
+fn decode_anchor(decoder: &mut Decoder) -> BlockfrostResult<Anchor> {
+    decoder.tuple(|decoder| {
+        Ok(Anchor {
+            url: decoder.text()?,
+            data_hash: decoder.hex::<32>()?,
+        })
+    })
+}
+
+// Tag of rational numbers.
+const TAG_RATIONAL: u64 = 30;
+
+// Unit interval, a rational number between 0 and 1 tagged with 30.
+fn decode_unit_interval(decoder: &mut Decoder) -> BlockfrostResult<(u64, u64)> {
+    let tag = decoder.tag()?;
+
+    if tag != TAG_RATIONAL {
+        return Err(decoder.error(format!("expected a rational number, found tag {tag}")));
+    }
+
+    decoder.tuple(|decoder| Ok((decoder.u64()?, decoder.u64()?)))
+}
+
+fn decode_certificate(decoder: &mut Decoder) -> BlockfrostResult<Certificate> {
+    let raw = decoder.raw()?;
+
+    // Certificates removed in Conway are kept as is, without decoding their content.
+    let mut kind_decoder = Decoder::new(raw);
+    kind_decoder.array()?;
+    if let kind @ (5 | 6) = kind_decoder.u64()? {
+        return Ok(Certificate::Legacy {
+            kind,
+            cbor: raw.to_vec(),
+        });
+    }
+
+    Decoder::new(raw).tuple(decode_certificate_fields)
+}
+
+fn decode_certificate_fields(decoder: &mut Decoder) -> BlockfrostResult<Certificate> {
+    let certificate = match decoder.u64()? {
+        0 => Certificate::StakeRegistration(decode_credential(decoder)?),
+        1 => Certificate::StakeDeregistration(decode_credential(decoder)?),
+        2 => Certificate::StakeDelegation {
+            credential: decode_credential(decoder)?,
+            pool: decoder.hex::<28>()?,
+        },
+        3 => Certificate::PoolRegistration(Box::new(decode_pool_params(decoder)?)),
+        4 => Certificate::PoolRetirement {
+            pool: decoder.hex::<28>()?,
+            epoch: decoder.u64()?,
+        },
+        7 => Certificate::Registration {
+            credential: decode_credential(decoder)?,
+            deposit: decoder.u64()?,
+        },
+        8 => Certificate::Unregistration {
+            credential: decode_credential(decoder)?,
+            refund: decoder.u64()?,
+        },
+        9 => Certificate::VoteDelegation {
+            credential: decode_credential(decoder)?,
+            drep: decode_drep(decoder)?,
+        },
+        10 => Certificate::StakeVoteDelegation {
+            credential: decode_credential(decoder)?,
+            pool: decoder.hex::<28>()?,
+            drep: decode_drep(decoder)?,
+        },
+        11 => Certificate::StakeRegistrationDelegation {
+            credential: decode_credential(decoder)?,
+            pool: decoder.hex::<28>()?,
+            deposit: decoder.u64()?,
+        },
+        12 => Certificate::VoteRegistrationDelegation {
+            credential: decode_credential(decoder)?,
+            drep: decode_drep(decoder)?,
+            deposit: decoder.u64()?,
+        },
+        13 => Certificate::StakeVoteRegistrationDelegation {
+            credential: decode_credential(decoder)?,
+            pool: decoder.hex::<28>()?,
+            drep: decode_drep(decoder)?,
+            deposit: decoder.u64()?,
+        },
+        14 => Certificate::AuthCommitteeHot {
+            cold: decode_credential(decoder)?,
+            hot: decode_credential(decoder)?,
+        },
+        15 => Certificate::ResignCommitteeCold {
+            cold: decode_credential(decoder)?,
+            anchor: decoder.nullable(decode_anchor)?,
+        },
+        16 => Certificate::DRepRegistration {
+            credential: decode_credential(decoder)?,
+            deposit: decoder.u64()?,
+            anchor: decoder.nullable(decode_anchor)?,
+        },
+        17 => Certificate::DRepUnregistration {
+            credential: decode_credential(decoder)?,
+            refund: decoder.u64()?,
+        },
+        18 => Certificate::DRepUpdate {
+            credential: decode_credential(decoder)?,
+            anchor: decoder.nullable(decode_anchor)?,
+        },
+        kind => return Err(decoder.error(format!("unknown certificate kind {kind}"))),
+    };
+
+    Ok(certificate)
+}
+
+fn decode_pool_params(decoder: &mut Decoder) -> BlockfrostResult<PoolParams> {
+    Ok(PoolParams {
+        operator: decoder.hex::<28>()?,
+        vrf_key_hash: decoder.hex::<32>()?,
+        pledge: decoder.u64()?,
+        cost: decoder.u64()?,
+        margin: decode_unit_interval(decoder)?,
+        reward_account: hex::encode(decoder.bytes()?),
+        owners: decoder.set_of(Decoder::hex::<28>)?,
+        relays: decoder.array_of(decode_relay)?,
+        metadata: decoder.nullable(decode_anchor)?,
+    })
+}
+
+fn decode_relay(decoder: &mut Decoder) -> BlockfrostResult<Relay> {
+    decoder.tuple(decode_relay_fields)
+}
+
+fn decode_relay_fields(decoder: &mut Decoder) -> BlockfrostResult<Relay> {
+    let port = |decoder: &mut Decoder| {
+        decoder.nullable(|decoder| {
+            let port = decoder.u64()?;
+
+            u16::try_from(port).map_err(|_| decoder.error(format!("invalid port {port}")))
+        })
+    };
+
+    match decoder.u64()? {
+        0 => Ok(Relay::SingleHostAddr {
+            port: port(decoder)?,
+            ipv4: decoder.nullable(|decoder| decoder.bytes_array::<4>().map(Ipv4Addr::from))?,
+            ipv6: decoder.nullable(|decoder| {
+                // IPv6 addresses are serialized as four little-endian 32 bits words.
+                let bytes = decoder.bytes_array::<16>()?;
+                let mut octets = [0u8; 16];
+
+                for (word, chunk) in bytes.chunks_exact(4).enumerate() {
+                    for (i, byte) in chunk.iter().rev().enumerate() {
+                        octets[word * 4 + i] = *byte;
+                    }
+                }
+                Ok(Ipv6Addr::from(octets))
+            })?,
+        }),
+        1 => Ok(Relay::SingleHostName {
+            port: port(decoder)?,
+            dns_name: decoder.text()?,
+        }),
+        2 => Ok(Relay::MultiHostName {
+            dns_name: decoder.text()?,
+        }),
+        kind => Err(decoder.error(format!("unknown relay kind {kind}"))),
+    }
+}
+
+fn decode_voting_procedures(decoder: &mut Decoder) -> BlockfrostResult<Vec<VotingProcedure>> {
+    let voters = decoder.map_of(decode_voter, |decoder| {
+        decoder.map_of(decode_gov_action_id, |decoder| {
+            decoder.tuple(|decoder| {
+                let vote = match decoder.u64()? {
+                    0 => Vote::No,
+                    1 => Vote::Yes,
+                    2 => Vote::Abstain,
+                    vote => return Err(decoder.error(format!("unknown vote {vote}"))),
+                };
+
+                Ok((vote, decoder.nullable(decode_anchor)?))
+            })
+        })
+    })?;
+
+    Ok(voters
+        .into_iter()
+        .flat_map(|(voter, votes)| {
+            votes
+                .into_iter()
+                .map(move |(action_id, (vote, anchor))| VotingProcedure {
+                    voter: voter.clone(),
+                    action_id,
+                    vote,
+                    anchor,
+                })
+        })
+        .collect())
+}
+
+fn decode_voter(decoder: &mut Decoder) -> BlockfrostResult<Voter> {
+    let (kind, hash) = decoder.tuple(|decoder| Ok((decoder.u64()?, decoder.hex::<28>()?)))?;
+
+    match kind {
+        0 => Ok(Voter::CommitteeKey(hash)),
+        1 => Ok(Voter::CommitteeScript(hash)),
+        2 => Ok(Voter::DRepKey(hash)),
+        3 => Ok(Voter::DRepScript(hash)),
+        4 => Ok(Voter::StakePool(hash)),
+        kind => Err(decoder.error(format!("unknown voter kind {kind}"))),
+    }
+}
+
+fn decode_gov_action_id(decoder: &mut Decoder) -> BlockfrostResult<GovActionId> {
+    decoder.tuple(|decoder| {
+        let tx_hash = decoder.bytes_array()?;
+        let index = decoder.u64()?;
+        let index = u16::try_from(index)
+            .map_err(|_| decoder.error(format!("governance action index {index} is too large")))?;
+
+        Ok(GovActionId::from_parts(tx_hash, index))
+    })
+}
+
+fn decode_proposal_procedure(decoder: &mut Decoder) -> BlockfrostResult<ProposalProcedure> {
+    decoder.tuple(|decoder| {
+        Ok(ProposalProcedure {
+            deposit: decoder.u64()?,
+            reward_account: hex::encode(decoder.bytes()?),
+            gov_action: decode_gov_action(decoder)?,
+            anchor: decode_anchor(decoder)?,
+        })
+    })
+}
+
+fn decode_gov_action(decoder: &mut Decoder) -> BlockfrostResult<GovAction> {
+    decoder.tuple(decode_gov_action_fields)
+}
+
+fn decode_gov_action_fields(decoder: &mut Decoder) -> BlockfrostResult<GovAction> {
+    let prev_action = |decoder: &mut Decoder| decoder.nullable(decode_gov_action_id);
+    let policy_hash = |decoder: &mut Decoder| decoder.nullable(Decoder::hex::<28>);
+
+    let action = match decoder.u64()? {
+        0 => GovAction::ParameterChange {
+            prev_action: prev_action(decoder)?,
+            update: decoder.raw()?.to_vec(),
+            policy_hash: policy_hash(decoder)?,
+        },
+        1 => GovAction::HardForkInitiation {
+            prev_action: prev_action(decoder)?,
+            protocol_version: decoder.tuple(|decoder| Ok((decoder.u64()?, decoder.u64()?)))?,
+        },
+        2 => GovAction::TreasuryWithdrawals {
+            withdrawals: decode_withdrawals(decoder)?,
+            policy_hash: policy_hash(decoder)?,
+        },
+        3 => GovAction::NoConfidence {
+            prev_action: prev_action(decoder)?,
+        },
+        4 => GovAction::UpdateCommittee {
+            prev_action: prev_action(decoder)?,
+            removed: decoder.set_of(decode_credential)?,
+            added: decoder.map_of(decode_credential, Decoder::u64)?,
+            threshold: decode_unit_interval(decoder)?,
+        },
+        5 => {
+            let prev_action = prev_action(decoder)?;
+            let (anchor, script_hash) =
+                decoder.tuple(|decoder| Ok((decode_anchor(decoder)?, policy_hash(decoder)?)))?;
+
+            GovAction::NewConstitution {
+                prev_action,
+                anchor,
+                script_hash,
+            }
+        }
+        6 => GovAction::Info,
+        kind => return Err(decoder.error(format!("unknown governance action {kind}"))),
+    };
+
+    Ok(action)
+}
+
+fn decode_witness_set(decoder: &mut Decoder) -> BlockfrostResult<WitnessSet> {
+    let mut witness_set = WitnessSet::default();
+
+    for (key, raw) in decoder.map_of(Decoder::u64, Decoder::raw)? {
+        let decoder = &mut Decoder::new(raw);
+        let plutus_scripts = &mut witness_set.plutus_scripts;
+
+        match key {
+            0 => witness_set.vkey_witnesses = decoder.set_of(decode_vkey_witness)?,
+            1 => witness_set.native_scripts = decoder.set_of(decode_native_script)?,
+            2 => witness_set.bootstrap_witnesses = decoder.set_of(decode_bootstrap_witness)?,
+            3 => plutus_scripts.extend(decode_plutus_scripts(decoder, Script::PlutusV1)?),
+            4 => witness_set.plutus_data = decoder.set_of(|decoder| Ok(decoder.raw()?.to_vec()))?,
+            5 => witness_set.redeemers = decode_redeemers(decoder)?,
+            6 => plutus_scripts.extend(decode_plutus_scripts(decoder, Script::PlutusV2)?),
+            7 => plutus_scripts.extend(decode_plutus_scripts(decoder, Script::PlutusV3)?),
+            _ => {}
+        }
+    }
+
+    Ok(witness_set)
+}
+
+fn decode_native_script(decoder: &mut Decoder) -> BlockfrostResult<Script> {
+    Ok(Script::Native(decoder.raw()?.to_vec()))
+}
+
+fn decode_plutus_scripts(
+    decoder: &mut Decoder, script: fn(Vec<u8>) -> Script,
+) -> BlockfrostResult<Vec<Script>> {
+    Ok(decoder
+        .set_of(Decoder::bytes)?
+        .into_iter()
+        .map(script)
+        .collect())
+}
+
+fn decode_vkey_witness(decoder: &mut Decoder) -> BlockfrostResult<VKeyWitness> {
+    decoder.tuple(|decoder| {
+        Ok(VKeyWitness {
+            vkey: decoder.hex::<32>()?,
+            signature: decoder.hex::<64>()?,
+        })
+    })
+}
+
+fn decode_bootstrap_witness(decoder: &mut Decoder) -> BlockfrostResult<BootstrapWitness> {
+    decoder.tuple(|decoder| {
+        Ok(BootstrapWitness {
+            public_key: decoder.hex::<32>()?,
+            signature: decoder.hex::<64>()?,
+            chain_code: decoder.hex::<32>()?,
+            attributes: hex::encode(decoder.bytes()?),
+        })
+    })
+}
+
+// Redeemers are either a list of `[tag, index, data, ex_units]` or, since Conway, a map of
+// `[tag, index] => [data, ex_units]`.
+fn decode_redeemers(decoder: &mut Decoder) -> BlockfrostResult<Vec<Redeemer>> {
+    if decoder.peek_major()? == MAJOR_MAP {
+        let entries = decoder.map_of(
+            |decoder| {
+                decoder.tuple(|decoder| Ok((decode_redeemer_purpose(decoder)?, decoder.u32()?)))
+            },
+            |decoder| {
+                decoder.tuple(|decoder| Ok((decoder.raw()?.to_vec(), decode_ex_units(decoder)?)))
+            },
+        )?;
+
+        return Ok(entries
+            .into_iter()
+            .map(|((purpose, index), (data, ex_units))| Redeemer {
+                purpose,
+                index,
+                data,
+                ex_units,
+            })
+            .collect());
+    }
+
+    decoder.array_of(|decoder| {
+        decoder.tuple(|decoder| {
+            Ok(Redeemer {
+                purpose: decode_redeemer_purpose(decoder)?,
+                index: decoder.u32()?,
+                data: decoder.raw()?.to_vec(),
+                ex_units: decode_ex_units(decoder)?,
+            })
+        })
+    })
+}
+
+fn decode_redeemer_purpose(decoder: &mut Decoder) -> BlockfrostResult<RedeemerPurpose> {
+    match decoder.u64()? {
+        0 => Ok(RedeemerPurpose::Spend),
+        1 => Ok(RedeemerPurpose::Mint),
+        2 => Ok(RedeemerPurpose::Certificate),
+        3 => Ok(RedeemerPurpose::Withdrawal),
+        4 => Ok(RedeemerPurpose::Vote),
+        5 => Ok(RedeemerPurpose::Propose),
+        tag => Err(decoder.error(format!("unknown redeemer tag {tag}"))),
+    }
+}
+
+fn decode_ex_units(decoder: &mut Decoder) -> BlockfrostResult<ExUnits> {
+    decoder.tuple(|decoder| {
+        Ok(ExUnits {
+            memory: decoder.u64()?,
+            steps: decoder.u64()?,
+        })
+    })
+}
+
+// Tag of the map form of auxiliary data, since Alonzo.
+const TAG_AUXILIARY_DATA: u64 = 259;
+
+// Auxiliary data is either a metadata map (Shelley), `[metadata, native_scripts]` (Allegra and
+// Mary) or a map tagged with 259 (since Alonzo).
+fn decode_auxiliary_data(decoder: &mut Decoder) -> BlockfrostResult<AuxiliaryData> {
+    match decoder.peek_major()? {
+        MAJOR_MAP => Ok(AuxiliaryData {
+            metadata: decode_metadata(decoder)?,
+            scripts: Vec::new(),
+        }),
+        MAJOR_ARRAY => decoder.tuple(|decoder| {
+            Ok(AuxiliaryData {
+                metadata: decode_metadata(decoder)?,
+                scripts: decoder.array_of(decode_native_script)?,
+            })
+        }),
+        MAJOR_TAG => {
+            let tag = decoder.tag()?;
+
+            if tag != TAG_AUXILIARY_DATA {
+                return Err(decoder.error(format!("expected auxiliary data, found tag {tag}")));
+            }
+
+            let mut auxiliary_data = AuxiliaryData::default();
+
+            for (key, raw) in decoder.map_of(Decoder::u64, Decoder::raw)? {
+                let decoder = &mut Decoder::new(raw);
+                let scripts = &mut auxiliary_data.scripts;
+
+                match key {
+                    0 => auxiliary_data.metadata = decode_metadata(decoder)?,
+                    1 => scripts.extend(decoder.array_of(decode_native_script)?),
+                    2 => scripts.extend(decode_plutus_scripts(decoder, Script::PlutusV1)?),
+                    3 => scripts.extend(decode_plutus_scripts(decoder, Script::PlutusV2)?),
+                    4 => scripts.extend(decode_plutus_scripts(decoder, Script::PlutusV3)?),
+                    _ => {}
+                }
+            }
+
+            Ok(auxiliary_data)
+        }
+        major => Err(decoder.error(format!("expected auxiliary data, found major type {major}"))),
+    }
+}
+
+fn decode_metadata(decoder: &mut Decoder) -> BlockfrostResult<BTreeMap<u64, Metadatum>> {
+    Ok(decoder
+        .map_of(Decoder::u64, decode_metadatum)?
+        .into_iter()
+        .collect())
+}
+
+fn decode_metadatum(decoder: &mut Decoder) -> BlockfrostResult<Metadatum> {
+    match decoder.peek_major()? {
+        MAJOR_BYTES => Ok(Metadatum::Bytes(decoder.bytes()?)),
+        MAJOR_TEXT => Ok(Metadatum::Text(decoder.text()?)),
+        MAJOR_ARRAY => Ok(Metadatum::List(decoder.array_of(decode_metadatum)?)),
+        MAJOR_MAP => Ok(Metadatum::Map(
+            decoder.map_of(decode_metadatum, decode_metadatum)?,
+        )),
+        _ => Ok(Metadatum::Int(decoder.int()?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Conway transaction with registration and vote delegation certificates, a vote, an info
+    // action, a map output with an inline datum and a reference script, and metadata.
+    const CONWAY_TX: &str = concat!(
+        "84aa00d90102828258201111111111111111111111111111111111111111111111111111111111111111",
+        "008258201111111111111111111111111111111111111111111111111111111111111111010182825839",
+        "01aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaabbbbbbbbbbbbbbbbbbbbbbbbbb",
+        "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbb821a001e8480a1581c444444444444444444444444444444444444",
+        "44444444444444444444a143544f4b05a400583901aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        "aaaaaaaaaaaaaabbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb011a0016e36002",
+        "8201d81845d87981182a03d8185282024f4e4d01000033222220051200120011021a0002bf20031a08f0",
+        "d18004d9010282840b8200581c3333333333333333333333333333333333333333333333333333333358",
+        "1c222222222222222222222222222222222222222222222222222222221a001e848083098200581c3333",
+        "3333333333333333333333333333333333333333333333333333810205a1581de1333333333333333333",
+        "333333333333333333333333333333333333331903e809a1581c44444444444444444444444444444444",
+        "444444444444444444444444a143544f4b2213a18202581c555555555555555555555555555555555555",
+        "55555555555555555555a182582066666666666666666666666666666666666666666666666666666666",
+        "66666666008201f614d9010281841b000000174876e800581de133333333333333333333333333333333",
+        "333333333333333333333333810682781d68747470733a2f2f6578616d706c652e636f6d2f696e666f2e",
+        "6a736f6e58207777777777777777777777777777777777777777777777777777777777777777161901f4",
+        "a200d9010281825820888888888888888888888888888888888888888888888888888888888888888858",
+        "409999999999999999999999999999999999999999999999999999999999999999999999999999999999",
+        "999999999999999999999999999999999999999999999905a182000082d87980821906a41a00074534f5",
+        "d90103a100a11902a2a1636d7367816568656c6c6f",
+    );
+
+    // Shelley transaction, without validity flag.
+    const SHELLEY_TX: &str = concat!(
+        "83a40081825820abababababababababababababababababababababababababababababababab030181",
+        "82581d61cccccccccccccccccccccccccccccccccccccccccccccccccccccccc1a000f3e58021a00029b",
+        "f8031a004c4b40a100818258208888888888888888888888888888888888888888888888888888888888",
+        "888888584099999999999999999999999999999999999999999999999999999999999999999999999999",
+        "999999999999999999999999999999999999999999999999999999a10124",
+    );
+
+    #[test]
+    fn test_conway_transaction() {
+        let tx = Transaction::from_hex(CONWAY_TX).unwrap();
+        let body = &tx.body;
+        let tx_hash = "1111111111111111111111111111111111111111111111111111111111111111";
+        let key = "33333333333333333333333333333333333333333333333333333333";
+        let policy = "44444444444444444444444444444444444444444444444444444444";
+
+        assert_eq!(
+            tx.hash,
+            "f46657ec1586ab88e9d1dc9fd8c5bb706cfa20e740aa016585c7ae60a48683c7"
+        );
+        assert_eq!(tx.size, CONWAY_TX.len() / 2);
+        assert!(tx.is_valid);
+        assert_eq!(
+            body.inputs,
+            vec![
+                TransactionInput {
+                    tx_hash: tx_hash.to_string(),
+                    index: 0,
+                },
+                TransactionInput {
+                    tx_hash: tx_hash.to_string(),
+                    index: 1,
+                },
+            ]
+        );
+        assert_eq!(body.fee, 180000);
+        assert_eq!(body.ttl, Some(150000000));
+        assert_eq!(body.donation, Some(500));
+        assert_eq!(body.mint[policy]["544f4b"], -3);
+        assert_eq!(body.withdrawals[&format!("e1{key}")], 1000);
+
+        // Legacy output, with native assets
+        assert_eq!(body.outputs[0].value.coin, 2000000);
+        assert_eq!(body.outputs[0].value.assets[policy]["544f4b"], 5);
+        assert_eq!(body.outputs[0].datum, None);
+        // Post-Alonzo output
+        assert_eq!(body.outputs[1].address, body.outputs[0].address);
+        assert_eq!(body.outputs[1].value.coin, 1500000);
+        assert_eq!(
+            body.outputs[1].datum,
+            Some(Datum::Inline(vec![0xd8, 0x79, 0x81, 0x18, 0x2a]))
+        );
+        assert!(matches!(
+            body.outputs[1].script_ref,
+            Some(Script::PlutusV2(_))
+        ));
+
+        assert_eq!(
+            body.certificates,
+            vec![
+                Certificate::StakeRegistrationDelegation {
+                    credential: Credential::Key(key.to_string()),
+                    pool: "22222222222222222222222222222222222222222222222222222222".to_string(),
+                    deposit: 2000000,
+                },
+                Certificate::VoteDelegation {
+                    credential: Credential::Key(key.to_string()),
+                    drep: DRep::AlwaysAbstain,
+                },
+            ]
+        );
+        assert_eq!(
+            body.voting_procedures,
+            vec![VotingProcedure {
+                voter: Voter::DRepKey(
+                    "55555555555555555555555555555555555555555555555555555555".to_string()
+                ),
+                action_id: GovActionId::new(&"66".repeat(32), 0).unwrap(),
+                vote: Vote::Yes,
+                anchor: None,
+            }]
+        );
+        assert_eq!(body.proposal_procedures.len(), 1);
+        assert_eq!(body.proposal_procedures[0].deposit, 100000000000);
+        assert_eq!(body.proposal_procedures[0].gov_action, GovAction::Info);
+        assert_eq!(
+            body.proposal_procedures[0].anchor.url,
+            "https://example.com/info.json"
+        );
+
+        assert_eq!(tx.witness_set.vkey_witnesses.len(), 1);
+        assert_eq!(
+            tx.witness_set.redeemers,
+            vec![Redeemer {
+                purpose: RedeemerPurpose::Spend,
+                index: 0,
+                data: vec![0xd8, 0x79, 0x80],
+                ex_units: ExUnits {
+                    memory: 1700,
+                    steps: 476468,
+                },
+            }]
+        );
+
+        let metadata = &tx.auxiliary_data.unwrap().metadata;
+        assert_eq!(
+            metadata[&674],
+            Metadatum::Map(vec![(
+                Metadatum::Text("msg".to_string()),
+                Metadatum::List(vec![Metadatum::Text("hello".to_string())]),
+            )])
+        );
+    }
+
+    #[test]
+    fn test_shelley_transaction() {
+        let tx = Transaction::from_hex(SHELLEY_TX).unwrap();
+
+        assert_eq!(
+            tx.hash,
+            "f92f16993aa3f0462a9d9e1764512a391ea35994a3cf8ea5dce179610335193e"
+        );
+        assert!(tx.is_valid);
+        assert_eq!(tx.body.inputs[0].index, 3);
+        assert_eq!(tx.body.outputs[0].value.coin, 999000);
+        assert_eq!(tx.body.fee, 171000);
+        assert_eq!(tx.witness_set.vkey_witnesses[0].vkey, "88".repeat(32));
+        assert_eq!(tx.auxiliary_data.unwrap().metadata[&1], Metadatum::Int(-5));
+    }
+
+    #[test]
+    fn test_verify_hash() {
+        let tx = Transaction::from_hex(SHELLEY_TX).unwrap();
+
+        assert!(tx
+            .verify_hash("F92F16993AA3F0462A9D9E1764512A391EA35994A3CF8EA5DCE179610335193E")
+            .is_ok());
+        assert!(matches!(
+            tx.verify_hash(&"00".repeat(32)),
+            Err(BlockfrostError::HashMismatch { .. })
+        ));
+    }
+
+    // Re-encode a transaction as an indefinite-length array.
+    fn indefinite(data: &[u8]) -> Vec<u8> {
+        [&[0x9f], &data[1..], &[0xff]].concat()
+    }
+
+    #[test]
+    fn test_validate_submission() {
+        let conway = hex::decode(CONWAY_TX).unwrap();
+        let shelley = hex::decode(SHELLEY_TX).unwrap();
+
+        assert!(Transaction::validate_submission(&conway).is_ok());
+        assert!(matches!(
+            Transaction::validate_submission(&shelley),
+            Err(BlockfrostError::InvalidTransaction { .. })
+        ));
+        assert!(Transaction::validate_submission(&conway[..100]).is_err());
+        assert!(Transaction::validate_submission(&indefinite(&conway)).is_ok());
+        assert!(matches!(
+            Transaction::validate_submission(&indefinite(&shelley)),
+            Err(BlockfrostError::InvalidTransaction { .. })
+        ));
+        assert!(Transaction::validate_submission(&indefinite(&conway)[..conway.len()]).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let tx = Transaction::from_hex(CONWAY_TX).unwrap();
+        let invalid = |update: fn(&mut Transaction)| {
+            let mut tx = tx.clone();
+            update(&mut tx);
+            matches!(
+                tx.validate(),
+                Err(BlockfrostError::InvalidTransaction { .. })
+            )
+        };
+
+        assert!(tx.validate().is_ok());
+        assert!(invalid(|tx| tx.body.inputs.clear()));
+        assert!(invalid(|tx| tx.witness_set = WitnessSet::default()));
+        assert!(invalid(|tx| tx.body.certificates.push(
+            Certificate::Legacy {
+                kind: 6,
+                cbor: Vec::new(),
+            }
+        )));
+        // Signed by the vkey witness of the transaction
+        assert!(!invalid(|tx| tx.body.required_signers.push(
+            "6626f96893b3a578cf26970e4d7f5ea1c3546ca8efc9d65347b65258".to_string()
+        )));
+        assert!(invalid(|tx| tx.body.required_signers.push("de".repeat(28))));
+    }
+
+    #[test]
+    fn test_auxiliary_data() {
+        // 259({0: {1: 1}})
+        let data = hex::decode("d90103a100a10101").unwrap();
+        let auxiliary_data = decode_auxiliary_data(&mut Decoder::new(&data)).unwrap();
+
+        assert_eq!(auxiliary_data.metadata[&1], Metadatum::Int(1));
+        // 260({0: {1: 1}}) and 0
+        assert!(decode_auxiliary_data(&mut Decoder::new(
+            &hex::decode("d90104a100a10101").unwrap()
+        ))
+        .is_err());
+        assert!(decode_auxiliary_data(&mut Decoder::new(&[0x00])).is_err());
+    }
+
+    #[test]
+    fn test_relay() {
+        // [0, 3001, null, null] and [0, 70000, null, null]
+        let data = hex::decode("8400190bb9f6f6").unwrap();
+        let overflow = hex::decode("84001a00011170f6f6").unwrap();
+
+        assert_eq!(
+            decode_relay(&mut Decoder::new(&data)).unwrap(),
+            Relay::SingleHostAddr {
+                port: Some(3001),
+                ipv4: None,
+                ipv6: None,
+            }
+        );
+        assert!(decode_relay(&mut Decoder::new(&overflow)).is_err());
+    }
+
+    #[test]
+    fn test_network_id() {
+        // {15: 1} and {15: 257}
+        let body = decode_body(&mut Decoder::new(&hex::decode("a10f01").unwrap())).unwrap();
+
+        assert_eq!(body.network_id, Some(1));
+        assert!(decode_body(&mut Decoder::new(&hex::decode("a10f190101").unwrap())).is_err());
+    }
+
+    #[test]
+    fn test_fixed_arrays() {
+        let hash = "11".repeat(32);
+        // [h'11…', 1], [_ h'11…', 1] and [h'11…', 1, 2]
+        let input = hex::decode(format!("825820{hash}01")).unwrap();
+        let indefinite = hex::decode(format!("9f5820{hash}01ff")).unwrap();
+        let unclosed = hex::decode(format!("9f5820{hash}0102ff")).unwrap();
+        let longer = hex::decode(format!("835820{hash}0102")).unwrap();
+
+        assert_eq!(
+            decode_input(&mut Decoder::new(&input)).unwrap(),
+            decode_input(&mut Decoder::new(&indefinite)).unwrap()
+        );
+        assert!(decode_input(&mut Decoder::new(&unclosed)).is_err());
+        assert!(decode_input(&mut Decoder::new(&longer)).is_err());
+        // [1000, 2000, 3] and [_ 1000, 2000]
+        assert!(
+            decode_ex_units(&mut Decoder::new(&hex::decode("831903e81907d003").unwrap())).is_err()
+        );
+        assert_eq!(
+            decode_ex_units(&mut Decoder::new(&hex::decode("9f1903e81907d0ff").unwrap())).unwrap(),
+            ExUnits {
+                memory: 1000,
+                steps: 2000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_unit_interval() {
+        // 30([1, 2]), [1, 2] and 31([1, 2])
+        assert_eq!(
+            decode_unit_interval(&mut Decoder::new(&hex::decode("d81e820102").unwrap())).unwrap(),
+            (1, 2)
+        );
+        assert!(decode_unit_interval(&mut Decoder::new(&hex::decode("820102").unwrap())).is_err());
+        assert!(
+            decode_unit_interval(&mut Decoder::new(&hex::decode("d81f820102").unwrap())).is_err()
+        );
+    }
+
+    #[test]
+    fn test_invalid_transactions() {
+        assert!(Transaction::from_hex("zz").is_err());
+        // Truncated
+        assert!(Transaction::from_hex(&SHELLEY_TX[..SHELLEY_TX.len() - 2]).is_err());
+        // Trailing bytes
+        assert!(Transaction::from_hex(&format!("{SHELLEY_TX}00")).is_err());
+        // Not a transaction
+        assert!(Transaction::from_hex("8200a0").is_err());
+    }
+}
@@ -1,7 +1,14 @@
 // Use this module as an interface to export all types declared inside of endpoints/
 //
 // These are not used in here, just exporting
-pub use crate::ipfs::{IpfsAdd, IpfsPinList, IpfsPinState, IpfsPinUpdate};
+pub use crate::{
+    api::endpoints::utils::{
+        AdditionalUtxo, EvaluationFailure, ExUnits, RedeemerPointer, RedeemerPurpose,
+        ScriptFailure, TxEvaluation, TxEvaluationFault, TxEvaluationRequest, TxEvaluationResult,
+        UtxoOutput, UtxoReference, UtxoValue,
+    },
+    ipfs::{IpfsAdd, IpfsPinList, IpfsPinState, IpfsPinUpdate},
+};
 
 /// Enum for any possible JSON value.
 ///
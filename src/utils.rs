@@ -1,4 +1,5 @@
-use crate::USER_AGENT;
+use crate::{BlockfrostError, BlockfrostResult, USER_AGENT};
+use blake2::{digest::consts::U20, Blake2b, Digest};
 use reqwest::{
     header::{HeaderMap, HeaderName, HeaderValue},
     Client,
@@ -43,3 +44,27 @@ pub(crate) fn build_header_map(project_id: &str, headers: &HashMap<String, Strin
     }
     header_map
 }
+
+pub(crate) fn parse_lovelace(amount: &str) -> BlockfrostResult<u64> {
+    amount.parse().map_err(|reason| BlockfrostError::Parsing {
+        message: format!("invalid lovelace amount '{amount}': {reason}"),
+    })
+}
+
+pub(crate) fn blake2b_160(data: &[u8]) -> [u8; 20] {
+    let mut hasher = Blake2b::<U20>::new();
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+pub(crate) fn blake2b_224(data: &[u8]) -> [u8; 28] {
+    let mut hasher = Blake2b::<blake2::digest::consts::U28>::new();
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+pub(crate) fn blake2b_256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Blake2b::<blake2::digest::consts::U32>::new();
+    hasher.update(data);
+    hasher.finalize().into()
+}
@@ -0,0 +1,478 @@
+use crate::{
+    BlockfrostAPI, BlockfrostError, BlockfrostResult, Order, Pagination,
+    DEFAULT_PAGINATION_PAGE_ITEMS_COUNT,
+};
+use blockfrost_openapi::models::{
+    address_transactions_content_inner::AddressTransactionsContentInner,
+    address_utxo_content_inner::AddressUtxoContentInner,
+};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use futures_timer::Delay;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+    time::Duration,
+};
+
+/// Position of a transaction in the chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TxPosition {
+    pub block_height: i32,
+    pub tx_index: i32,
+}
+
+/// Reference to a transaction output.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct UtxoRef {
+    pub tx_hash: String,
+    pub output_index: i32,
+}
+
+/// What a watcher knows about a single address.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressCheckpoint {
+    /// Latest transaction already emitted.
+    pub last_transaction: Option<TxPosition>,
+    pub utxos: BTreeSet<UtxoRef>,
+    /// Hashes of the pending transactions already emitted.
+    pub mempool: BTreeSet<String>,
+}
+
+/// State of [`watch_address`](BlockfrostAPI::watch_address) and
+/// [`watch_account`](BlockfrostAPI::watch_account), to persist between runs.
+///
+/// A [`Default`] checkpoint starts at the current state: the first poll records the existing
+/// transactions, UTXOs and mempool entries without emitting them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchCheckpoint {
+    /// Whether the first poll happened.
+    pub initialized: bool,
+    pub addresses: BTreeMap<String, AddressCheckpoint>,
+}
+
+/// A change seen by a watcher.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WatchEvent {
+    /// A transaction of the address was included in a block.
+    Transaction {
+        address: String,
+        transaction: AddressTransactionsContentInner,
+    },
+    /// A new UTXO is held by the address.
+    UtxoAdded(AddressUtxoContentInner),
+    /// A UTXO of the address was spent.
+    UtxoRemoved { address: String, utxo: UtxoRef },
+    /// A transaction involving the address entered the mempool.
+    Mempool { address: String, tx_hash: String },
+    /// Emitted after the events of each poll, to be persisted.
+    Checkpoint(WatchCheckpoint),
+}
+
+/// State of an address during a poll.
+struct AddressSnapshot {
+    /// Transactions after the checkpoint, latest first.
+    transactions: Vec<AddressTransactionsContentInner>,
+    utxos: Vec<AddressUtxoContentInner>,
+    mempool: Vec<String>,
+}
+
+impl AddressCheckpoint {
+    /// Record `snapshot`, returning the changes since the last one if `emit` is set.
+    fn update(&mut self, address: &str, snapshot: AddressSnapshot, emit: bool) -> Vec<WatchEvent> {
+        let mut events = Vec::new();
+
+        for transaction in snapshot.transactions.into_iter().rev() {
+            let position = TxPosition {
+                block_height: transaction.block_height,
+                tx_index: transaction.tx_index,
+            };
+
+            if self.last_transaction >= Some(position) {
+                continue;
+            }
+
+            self.last_transaction = Some(position);
+            events.push(WatchEvent::Transaction {
+                address: address.to_string(),
+                transaction,
+            });
+        }
+
+        let mut utxos = BTreeMap::new();
+
+        for utxo in snapshot.utxos {
+            let utxo_ref = UtxoRef {
+                tx_hash: utxo.tx_hash.clone(),
+                output_index: utxo.output_index,
+            };
+
+            utxos.insert(utxo_ref, utxo);
+        }
+
+        for utxo in &self.utxos {
+            if !utxos.contains_key(utxo) {
+                events.push(WatchEvent::UtxoRemoved {
+                    address: address.to_string(),
+                    utxo: utxo.clone(),
+                });
+            }
+        }
+
+        let known = std::mem::take(&mut self.utxos);
+
+        for (utxo_ref, utxo) in utxos {
+            if !known.contains(&utxo_ref) {
+                events.push(WatchEvent::UtxoAdded(utxo));
+            }
+
+            self.utxos.insert(utxo_ref);
+        }
+
+        let mempool: BTreeSet<String> = snapshot.mempool.into_iter().collect();
+
+        for tx_hash in mempool.difference(&self.mempool) {
+            events.push(WatchEvent::Mempool {
+                address: address.to_string(),
+                tx_hash: tx_hash.clone(),
+            });
+        }
+
+        // Confirmed or dropped transactions leave the mempool, and are forgotten.
+        self.mempool = mempool;
+
+        match emit {
+            true => events,
+            false => Vec::new(),
+        }
+    }
+}
+
+/// What a watcher follows.
+#[derive(Clone)]
+enum WatchSource {
+    Address(String),
+    Account(String),
+}
+
+impl BlockfrostAPI {
+    /// Stream the new transactions, UTXO changes and pending mempool entries of an address,
+    /// polling every `interval`.
+    ///
+    /// Each poll uses [`addresses_transactions`](Self::addresses_transactions) in descending
+    /// order until the last seen transaction, [`addresses_utxos`](Self::addresses_utxos) and
+    /// [`mempool_addresses_address`](Self::mempool_addresses_address), then emits a
+    /// [`WatchEvent::Checkpoint`]. Passing the last checkpoint after a restart resumes from it:
+    /// nothing is missed, and only the events emitted after it are repeated.
+    ///
+    /// Errors are emitted without ending the stream, and the poll is retried after `interval`.
+    pub fn watch_address(
+        &self, address: &str, checkpoint: WatchCheckpoint, interval: Duration,
+    ) -> impl Stream<Item = BlockfrostResult<WatchEvent>> + '_ {
+        self.watch(
+            WatchSource::Address(address.to_string()),
+            checkpoint,
+            interval,
+        )
+    }
+
+    /// Same as [`watch_address`](Self::watch_address), for every address of a stake account
+    /// listed by [`accounts_addresses`](Self::accounts_addresses) on each poll.
+    ///
+    /// The transactions of addresses first used after the watcher started are all emitted.
+    pub fn watch_account(
+        &self, stake_address: &str, checkpoint: WatchCheckpoint, interval: Duration,
+    ) -> impl Stream<Item = BlockfrostResult<WatchEvent>> + '_ {
+        self.watch(
+            WatchSource::Account(stake_address.to_string()),
+            checkpoint,
+            interval,
+        )
+    }
+
+    fn watch(
+        &self, source: WatchSource, checkpoint: WatchCheckpoint, interval: Duration,
+    ) -> impl Stream<Item = BlockfrostResult<WatchEvent>> + '_ {
+        stream::unfold((checkpoint, true), move |(mut checkpoint, first)| {
+            let source = source.clone();
+
+            async move {
+                if !first {
+                    Delay::new(interval).await;
+                }
+
+                let events = match self.watch_poll(&source, &mut checkpoint).await {
+                    Ok(events) => events.into_iter().map(Ok).collect(),
+                    Err(error) => vec![Err(error)],
+                };
+
+                Some((events, (checkpoint, false)))
+            }
+        })
+        .flat_map(stream::iter)
+    }
+
+    /// Poll every address of `source`, updating `checkpoint` only if all requests succeeded.
+    async fn watch_poll(
+        &self, source: &WatchSource, checkpoint: &mut WatchCheckpoint,
+    ) -> BlockfrostResult<Vec<WatchEvent>> {
+        let addresses = match source {
+            WatchSource::Address(address) => vec![address.clone()],
+            WatchSource::Account(stake_address) => {
+                or_empty(self.accounts_addresses(stake_address, Pagination::all()))
+                    .await?
+                    .into_iter()
+                    .map(|address| address.address)
+                    .collect()
+            }
+        };
+
+        let state = &*checkpoint;
+        let snapshots: Vec<(String, AddressSnapshot)> = stream::iter(addresses)
+            .map(|address| async move {
+                let last_transaction = state
+                    .addresses
+                    .get(&address)
+                    .and_then(|address| address.last_transaction);
+                let snapshot = self
+                    .address_snapshot(&address, last_transaction, !state.initialized)
+                    .await?;
+
+                Ok::<_, BlockfrostError>((address, snapshot))
+            })
+            .buffered(self.batch_concurrency())
+            .try_collect()
+            .await?;
+
+        let emit = checkpoint.initialized;
+        let mut events = Vec::new();
+
+        for (address, snapshot) in snapshots {
+            let address_checkpoint = checkpoint.addresses.entry(address.clone()).or_default();
+
+            events.extend(address_checkpoint.update(&address, snapshot, emit));
+        }
+
+        checkpoint.initialized = true;
+        events.push(WatchEvent::Checkpoint(checkpoint.clone()));
+
+        Ok(events)
+    }
+
+    /// Current state of an address. Only its latest transaction is needed for a `baseline`.
+    async fn address_snapshot(
+        &self, address: &str, last_transaction: Option<TxPosition>, baseline: bool,
+    ) -> BlockfrostResult<AddressSnapshot> {
+        let transactions = async {
+            if baseline {
+                let pagination = Pagination::new(Order::Desc, 1, 1);
+
+                return or_empty(self.addresses_transactions(address, pagination)).await;
+            }
+
+            let mut transactions = Vec::new();
+
+            for page in 1.. {
+                let pagination =
+                    Pagination::new(Order::Desc, page, DEFAULT_PAGINATION_PAGE_ITEMS_COUNT);
+                let batch = or_empty(self.addresses_transactions(address, pagination)).await?;
+                let full_page = batch.len() == DEFAULT_PAGINATION_PAGE_ITEMS_COUNT;
+
+                for transaction in batch {
+                    let position = TxPosition {
+                        block_height: transaction.block_height,
+                        tx_index: transaction.tx_index,
+                    };
+
+                    if last_transaction >= Some(position) {
+                        return Ok(transactions);
+                    }
+
+                    transactions.push(transaction);
+                }
+
+                if !full_page {
+                    break;
+                }
+            }
+
+            Ok(transactions)
+        };
+
+        let (transactions, utxos, mempool) = futures::try_join!(
+            transactions,
+            or_empty(self.addresses_utxos(address, Pagination::all())),
+            or_empty(self.mempool_addresses_address(address, Pagination::all())),
+        )?;
+
+        Ok(AddressSnapshot {
+            transactions,
+            utxos,
+            mempool: mempool.into_iter().map(|tx| tx.tx_hash).collect(),
+        })
+    }
+}
+
+/// Treat a 404, returned for addresses that were never used, as an empty list.
+async fn or_empty<T>(
+    request: impl Future<Output = BlockfrostResult<Vec<T>>>,
+) -> BlockfrostResult<Vec<T>> {
+    match request.await {
+        Err(BlockfrostError::Response { reason, .. }) if reason.status_code == 404 => {
+            Ok(Vec::new())
+        }
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ADDRESS: &str = "addr1qxqs59lphg8g6qndelq8xwqn60ag3aeyfcp33c2kdp46a09re5df3pzwwmyq946axfcejy5n4x0y99wqpgtp2gd0k09qsgy6pz";
+
+    fn transaction(tx_hash: &str, block_height: i32) -> AddressTransactionsContentInner {
+        serde_json::from_value(json!({
+            "tx_hash": tx_hash,
+            "tx_index": 0,
+            "block_height": block_height,
+            "block_time": 1635505891
+        }))
+        .unwrap()
+    }
+
+    fn utxo(tx_hash: &str, output_index: i32) -> AddressUtxoContentInner {
+        serde_json::from_value(json!({
+            "address": ADDRESS,
+            "tx_hash": tx_hash,
+            "tx_index": output_index,
+            "output_index": output_index,
+            "amount": [{ "unit": "lovelace", "quantity": "42000000" }],
+            "block": "7eb8e27d18686c7db9a18f8bbcfe34e3fed6e047afaa2d969904d15e934847e6",
+            "data_hash": null,
+            "inline_datum": null,
+            "reference_script_hash": null
+        }))
+        .unwrap()
+    }
+
+    fn snapshot(
+        transactions: Vec<AddressTransactionsContentInner>, utxos: Vec<AddressUtxoContentInner>,
+        mempool: &[&str],
+    ) -> AddressSnapshot {
+        AddressSnapshot {
+            transactions,
+            utxos,
+            mempool: mempool.iter().map(|tx_hash| tx_hash.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_baseline() {
+        let mut checkpoint = AddressCheckpoint::default();
+        let events = checkpoint.update(
+            ADDRESS,
+            snapshot(vec![transaction("a", 10)], vec![utxo("a", 0)], &["b"]),
+            false,
+        );
+
+        assert!(events.is_empty());
+        assert_eq!(
+            checkpoint.last_transaction,
+            Some(TxPosition {
+                block_height: 10,
+                tx_index: 0
+            })
+        );
+        assert_eq!(checkpoint.utxos.len(), 1);
+        assert_eq!(checkpoint.mempool, BTreeSet::from(["b".to_string()]));
+    }
+
+    #[test]
+    fn test_update() {
+        let mut checkpoint = AddressCheckpoint::default();
+
+        checkpoint.update(
+            ADDRESS,
+            snapshot(vec![transaction("a", 10)], vec![utxo("a", 0)], &["b"]),
+            false,
+        );
+
+        // "b" is confirmed and spends the output of "a", "c" is pending
+        let events = checkpoint.update(
+            ADDRESS,
+            snapshot(
+                vec![
+                    transaction("c2", 12),
+                    transaction("b", 11),
+                    transaction("a", 10),
+                ],
+                vec![utxo("b", 0), utxo("c2", 1)],
+                &["c"],
+            ),
+            true,
+        );
+
+        assert_eq!(
+            events,
+            [
+                WatchEvent::Transaction {
+                    address: ADDRESS.to_string(),
+                    transaction: transaction("b", 11),
+                },
+                WatchEvent::Transaction {
+                    address: ADDRESS.to_string(),
+                    transaction: transaction("c2", 12),
+                },
+                WatchEvent::UtxoRemoved {
+                    address: ADDRESS.to_string(),
+                    utxo: UtxoRef {
+                        tx_hash: "a".to_string(),
+                        output_index: 0,
+                    },
+                },
+                WatchEvent::UtxoAdded(utxo("b", 0)),
+                WatchEvent::UtxoAdded(utxo("c2", 1)),
+                WatchEvent::Mempool {
+                    address: ADDRESS.to_string(),
+                    tx_hash: "c".to_string(),
+                },
+            ]
+        );
+
+        // Nothing changed
+        let events = checkpoint.update(
+            ADDRESS,
+            snapshot(vec![], vec![utxo("b", 0), utxo("c2", 1)], &["c"]),
+            true,
+        );
+
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let mut checkpoint = WatchCheckpoint {
+            initialized: true,
+            ..Default::default()
+        };
+
+        checkpoint
+            .addresses
+            .entry(ADDRESS.to_string())
+            .or_default()
+            .update(
+                ADDRESS,
+                snapshot(vec![transaction("a", 10)], vec![utxo("a", 0)], &["b"]),
+                false,
+            );
+
+        let json = serde_json::to_string(&checkpoint).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<WatchCheckpoint>(&json).unwrap(),
+            checkpoint
+        );
+    }
+}